@@ -7,7 +7,7 @@
 mod tasks;
 
 use anyhow::Result;
-use config::SafeConfig;
+use config::{LogFormat, SafeConfig};
 use container::ServiceFactory;
 use log::info;
 use tasks::{
@@ -57,27 +57,34 @@ async fn add_tasks(scheduler: &'static JobScheduler, service_factory: &'static S
 /// registers all scheduled tasks, and manages the application lifecycle.
 ///
 /// The function will:
-/// 1. Initialize logging
-/// 2. Load configuration
-/// 3. Set up database connection
-/// 4. Create and initialize the job scheduler
-/// 5. Register all tasks
-/// 6. Start the scheduler
-/// 7. Wait for shutdown signal
+/// 1. Load configuration
+/// 2. Initialize logging
+/// 3. Start the metrics exporter
+/// 4. Set up database connection
+/// 5. Create and initialize the job scheduler
+/// 6. Register all tasks
+/// 7. Start the scheduler
+/// 8. Wait for shutdown signal
 ///
 /// # Returns
 ///
 /// * `Result<()>` - Success if the application runs and shuts down properly, or an error if initialization fails
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging system
-    libs::logger::init();
-    info!("Starting task scheduler....");
-
     // Load configuration from command line arguments
     let config = SafeConfig::from_args().await?;
     let config = config.get_config().await?;
 
+    // Initialize logging system
+    libs::logger::init(&config.log.level, config.log.format == LogFormat::Json);
+    info!("Starting task scheduler....");
+
+    // Expose metrics on a separate address, the scheduler has no HTTP server
+    if config.metrics.enabled {
+        libs::metrics::serve(config.metrics.scheduler_listen.parse()?)?;
+        info!("Serving metrics on {}", config.metrics.scheduler_listen);
+    }
+
     // Initialize database connection
     let (_, database) = database::mongodb::connect(&config.database.uri, &config.database.db_name).await?;
 
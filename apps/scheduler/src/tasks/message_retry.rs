@@ -4,7 +4,10 @@ use super::Task;
 use anyhow::Result;
 use async_trait::async_trait;
 use container::ServiceFactory;
-use log::{error, info};
+use log::{error, info, warn};
+
+/// 每次执行最多领取的消息数量
+const CLAIM_BATCH_SIZE: usize = 100;
 
 #[derive(Debug)]
 pub enum MessageType {
@@ -46,22 +49,32 @@ impl<'a> Task for MessageSendTask<'a> {
         info!("Starting message retry task...");
 
         let service = self.service_factory.notify_service();
+
+        // 先领取整批消息再发送, 领取是原子操作, 多个调度实例不会拿到同一条消息;
+        // 每条消息发送前再续租, 租约已被其他实例领取的消息会被跳过
         let messages = match self.message_type {
-            MessageType::UnSent => service.get_pending_messages().await?,
-            MessageType::Failed => service.get_failed_messages().await?,
+            MessageType::UnSent => service.claim_pending_messages(CLAIM_BATCH_SIZE).await?,
+            MessageType::Failed => service.claim_failed_messages(CLAIM_BATCH_SIZE).await?,
         };
 
         let total = messages.len();
-        info!("Found {} {} messages to retry", total, self.message_type);
+        info!("Claimed {} {} messages to retry", total, self.message_type);
 
         let mut success_count = 0;
         for message in messages {
             let message_id = message.base.id.clone();
-            match service.retry_message(message).await {
-                Ok(_) => {
+
+            match service.send_claimed(message).await {
+                Ok(true) => {
                     success_count += 1;
                     info!("Successfully retried message {}", message_id);
                 }
+                Ok(false) => {
+                    warn!(
+                        "Lease of message {} was taken over by another worker, skipped",
+                        message_id
+                    );
+                }
                 Err(e) => {
                     error!("Failed to retry message {}: {}", message_id, e);
                 }
@@ -1,12 +1,14 @@
 pub mod cleanup;
 pub mod message_retry;
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
 use anyhow::Result;
 use async_trait::async_trait;
 use log::{error, info};
+use metrics::{counter, histogram};
 use tokio_cron_scheduler::{Job, JobScheduler};
+use tracing::{info_span, Instrument};
 
 #[async_trait]
 pub trait Task: Send + Sync {
@@ -15,15 +17,30 @@ pub trait Task: Send + Sync {
     async fn execute(&self) -> Result<()>;
 }
 
+/// Registers a task with the scheduler
+///
+/// Every run is executed inside a `job` span carrying the task name and a
+/// generated run id, so all log lines of one run can be correlated.
+/// The duration and failures of each run are recorded as metrics.
 pub async fn add_task<T: Task + 'static>(scheduler: &JobScheduler, task: T) -> Result<()> {
     let task = Arc::new(task);
     let task_clone = task.clone();
     let job = Job::new_async(task.cron(), move |_uuid, _l| {
         let task = task_clone.clone();
         Box::pin(async move {
-            if let Err(e) = task.execute().await {
-                error!("Task {} failed: {}", task.name(), e);
+            let run_id = libs::next_id().await;
+            let span = info_span!("job", job = task.name(), run_id = %run_id);
+            async {
+                let started = Instant::now();
+                if let Err(e) = task.execute().await {
+                    error!("Task {} failed: {}", task.name(), e);
+                    counter!("scheduler_job_failures_total", "job" => task.name().to_string()).increment(1);
+                }
+                histogram!("scheduler_job_duration_seconds", "job" => task.name().to_string())
+                    .record(started.elapsed().as_secs_f64());
             }
+            .instrument(span)
+            .await
         })
     })?;
 
@@ -1,8 +1,11 @@
 use config::{Config, SafeConfig};
 use container::ServiceFactory;
+use database::cache::MongoCache;
 use mongodb::{Client, Database};
 use rbac::ActorHandler as RbacActorHandler;
 
+use crate::{login_guard::LoginGuard, oidc::JwksCache, session::SessionStore};
+
 #[derive(Clone)]
 pub struct DatabaseState {
     #[allow(dead_code)]
@@ -22,15 +25,24 @@ pub struct AppState {
     config: SafeConfig,
     rbac: RbacActorHandler,
     services: ServiceFactory,
+    sessions: SessionStore<MongoCache>,
+    login_guard: LoginGuard<MongoCache>,
+    oidc_keys: JwksCache,
 }
 
 impl AppState {
     pub fn new(db_state: DatabaseState, config: SafeConfig, rbac: RbacActorHandler) -> Self {
+        // 会话和登录失败计数保存在数据库中, 多个实例共享
+        let cache = MongoCache::new(db_state.db.clone());
+
         Self {
             services: ServiceFactory::new(db_state.db.clone()),
             db_state,
             config,
             rbac,
+            sessions: SessionStore::new(cache.clone()),
+            login_guard: LoginGuard::new(cache),
+            oidc_keys: JwksCache::default(),
         }
     }
 
@@ -49,6 +61,19 @@ impl AppState {
     pub fn service_factory(&self) -> &ServiceFactory {
         &self.services
     }
+
+    pub fn sessions(&self) -> &SessionStore<MongoCache> {
+        &self.sessions
+    }
+
+    pub fn login_guard(&self) -> &LoginGuard<MongoCache> {
+        &self.login_guard
+    }
+
+    /// 单点登录的签名公钥缓存
+    pub fn oidc_keys(&self) -> &JwksCache {
+        &self.oidc_keys
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -0,0 +1,58 @@
+use axum::{
+    handler::Handler,
+    http::Method,
+    routing::{on, MethodFilter},
+    Router,
+};
+use services::role::PermissionCatalog;
+
+use crate::app_state::AppState;
+
+/// 注册受权限控制的路由, 同时记录权限目录
+///
+/// 角色只能授予目录中的接口, 保证权限和实际的路由一致
+pub struct CatalogRouter {
+    router: Router<AppState>,
+    catalog: PermissionCatalog,
+}
+
+impl CatalogRouter {
+    pub fn new() -> Self {
+        Self {
+            router: Router::new(),
+            catalog: PermissionCatalog::new(),
+        }
+    }
+
+    /// 注册路由并记录到权限目录
+    ///
+    /// # 参数
+    ///
+    /// * `method` - HTTP 方法
+    /// * `path` - 路由路径
+    /// * `module` - 所属模块
+    /// * `description` - 接口描述
+    /// * `handler` - 处理函数
+    pub fn route<H, T>(
+        mut self,
+        method: Method,
+        path: &str,
+        module: &str,
+        description: &str,
+        handler: H,
+    ) -> Self
+    where
+        H: Handler<T, AppState>,
+        T: 'static,
+    {
+        let filter = MethodFilter::try_from(method.clone()).expect("unsupported route method");
+
+        self.router = self.router.route(path, on(filter, handler));
+        self.catalog.add(module, method.as_str(), path, description);
+        self
+    }
+
+    pub fn into_parts(self) -> (Router<AppState>, PermissionCatalog) {
+        (self.router, self.catalog)
+    }
+}
@@ -1,6 +1,10 @@
 use super::response::ApiResponse;
-use crate::{app_state, jwt};
-use axum::{http::StatusCode, response::IntoResponse, Json};
+use crate::{app_state, jwt, oidc};
+use axum::{http::StatusCode, response::IntoResponse};
+use serde::Serialize;
+use services::errors::{Error as ServiceError, Resource};
+use utoipa::ToSchema;
+use validator::{ValidationErrors, ValidationErrorsKind};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -10,6 +14,15 @@ pub enum Error {
     #[error("请求参数错误: {0}")]
     BadRequest(String),
 
+    #[error("未授权: {0}")]
+    Unauthorized(String),
+
+    #[error("{0}")]
+    PermissionDenied(String),
+
+    #[error("{0}")]
+    TooManyRequests(String),
+
     #[error(transparent)]
     Repository(#[from] database::errors::Error),
 
@@ -24,6 +37,12 @@ pub enum Error {
 
     #[error(transparent)]
     AppState(#[from] app_state::Error),
+
+    #[error(transparent)]
+    Oidc(#[from] oidc::Error),
+
+    #[error(transparent)]
+    Service(ServiceError),
 }
 
 impl From<String> for Error {
@@ -44,8 +63,17 @@ impl From<std::io::Error> for Error {
     }
 }
 
-impl From<services::errors::Error> for Error {
-    fn from(err: services::errors::Error) -> Self {
+impl From<ServiceError> for Error {
+    fn from(err: ServiceError) -> Self {
+        match err {
+            ServiceError::Validation(errors) => Error::Validation(errors),
+            err => Error::Service(err),
+        }
+    }
+}
+
+impl From<libs::cache::CacheError> for Error {
+    fn from(err: libs::cache::CacheError) -> Self {
         Error::Internal(err.to_string())
     }
 }
@@ -56,27 +84,308 @@ impl From<rbac::ActorError> for Error {
     }
 }
 
+/// 响应中的错误代码
+///
+/// 客户端按错误代码处理错误, 不要依赖 `errorMessage` 的文本, 已发布的代码不会改变含义
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    InternalError,
+    DatabaseError,
+    BadRequest,
+    ValidationFailed,
+    InvalidArgument,
+    Unauthorized,
+    PermissionDenied,
+    TooManyRequests,
+    AdminNotFound,
+    RoleNotFound,
+    ApiKeyNotFound,
+    MessageNotFound,
+    InternalMessageNotFound,
+    BroadcastNotFound,
+    WebhookNotFound,
+    ProductNotFound,
+    SkuNotFound,
+    CategoryNotFound,
+    BrandNotFound,
+    SupplierNotFound,
+    Conflict,
+    OptimisticLockConflict,
+    BusinessRuleViolation,
+}
+
+impl ErrorCode {
+    /// 错误代码对应的 HTTP 状态码
+    pub fn status(self) -> StatusCode {
+        match self {
+            ErrorCode::InternalError | ErrorCode::DatabaseError => StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::BadRequest | ErrorCode::ValidationFailed | ErrorCode::InvalidArgument => {
+                StatusCode::BAD_REQUEST
+            }
+            ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorCode::PermissionDenied => StatusCode::FORBIDDEN,
+            ErrorCode::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::AdminNotFound
+            | ErrorCode::RoleNotFound
+            | ErrorCode::ApiKeyNotFound
+            | ErrorCode::MessageNotFound
+            | ErrorCode::InternalMessageNotFound
+            | ErrorCode::BroadcastNotFound
+            | ErrorCode::WebhookNotFound
+            | ErrorCode::ProductNotFound
+            | ErrorCode::SkuNotFound
+            | ErrorCode::CategoryNotFound
+            | ErrorCode::BrandNotFound
+            | ErrorCode::SupplierNotFound => StatusCode::NOT_FOUND,
+            ErrorCode::Conflict | ErrorCode::OptimisticLockConflict => StatusCode::CONFLICT,
+            ErrorCode::BusinessRuleViolation => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+}
+
+impl From<Resource> for ErrorCode {
+    fn from(resource: Resource) -> Self {
+        match resource {
+            Resource::Admin => ErrorCode::AdminNotFound,
+            Resource::Role => ErrorCode::RoleNotFound,
+            Resource::ApiKey => ErrorCode::ApiKeyNotFound,
+            Resource::Message => ErrorCode::MessageNotFound,
+            Resource::InternalMessage => ErrorCode::InternalMessageNotFound,
+            Resource::Broadcast => ErrorCode::BroadcastNotFound,
+            Resource::Webhook => ErrorCode::WebhookNotFound,
+            Resource::Product => ErrorCode::ProductNotFound,
+            Resource::Sku => ErrorCode::SkuNotFound,
+            Resource::Category => ErrorCode::CategoryNotFound,
+            Resource::Brand => ErrorCode::BrandNotFound,
+            Resource::Supplier => ErrorCode::SupplierNotFound,
+        }
+    }
+}
+
+impl Error {
+    /// 错误对应的错误代码
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Error::Internal(_) | Error::AppState(_) => ErrorCode::InternalError,
+            Error::BadRequest(_) => ErrorCode::BadRequest,
+            Error::Validation(_) => ErrorCode::ValidationFailed,
+            Error::Unauthorized(_) | Error::Jwt(_) | Error::Oidc(_) => ErrorCode::Unauthorized,
+            Error::PermissionDenied(_) => ErrorCode::PermissionDenied,
+            Error::TooManyRequests(_) => ErrorCode::TooManyRequests,
+            Error::Repository(database::errors::Error::OptimisticLockingError) => {
+                ErrorCode::OptimisticLockConflict
+            }
+            Error::Repository(_) => ErrorCode::DatabaseError,
+            Error::Logic(_) => ErrorCode::BusinessRuleViolation,
+            Error::Service(err) => match err {
+                ServiceError::Internal(_) => ErrorCode::InternalError,
+                ServiceError::NotFound(resource) => (*resource).into(),
+                ServiceError::Conflict(_) => ErrorCode::Conflict,
+                ServiceError::OptimisticLock => ErrorCode::OptimisticLockConflict,
+                ServiceError::PermissionDenied(_) => ErrorCode::PermissionDenied,
+                ServiceError::InvalidArgument(_) => ErrorCode::InvalidArgument,
+                ServiceError::Logic(_) => ErrorCode::BusinessRuleViolation,
+                ServiceError::RepositoryError(_) => ErrorCode::DatabaseError,
+                ServiceError::Validation(_) => ErrorCode::ValidationFailed,
+            },
+        }
+    }
+}
+
+/// 单个字段的校验错误
+///
+/// # 字段
+///
+/// * `field` - 字段路径, 嵌套字段使用 `skus[0].price` 的形式
+/// * `code` - 校验规则, 例如 `length`、`range`、`email`
+/// * `message` - 错误说明
+#[derive(Debug, Serialize, PartialEq, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub code: String,
+    pub message: String,
+}
+
+/// 校验失败时响应的 data 字段
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ValidationErrorBody {
+    pub errors: Vec<FieldError>,
+}
+
+impl From<&ValidationErrors> for ValidationErrorBody {
+    fn from(errors: &ValidationErrors) -> Self {
+        let mut out = vec![];
+        collect_field_errors(errors, "", &mut out);
+        out.sort_by(|a, b| a.field.cmp(&b.field));
+
+        Self { errors: out }
+    }
+}
+
+/// 展开嵌套的校验错误
+fn collect_field_errors(errors: &ValidationErrors, prefix: &str, out: &mut Vec<FieldError>) {
+    for (field, kind) in errors.errors() {
+        let path = if prefix.is_empty() {
+            field.to_string()
+        } else {
+            format!("{}.{}", prefix, field)
+        };
+
+        match kind {
+            ValidationErrorsKind::Field(items) => {
+                for item in items {
+                    out.push(FieldError {
+                        field: path.clone(),
+                        code: item.code.to_string(),
+                        message: item
+                            .message
+                            .as_ref()
+                            .map(|message| message.to_string())
+                            .unwrap_or_else(|| format!("{} 不满足 {} 校验", path, item.code)),
+                    });
+                }
+            }
+            ValidationErrorsKind::Struct(errors) => collect_field_errors(errors, &path, out),
+            ValidationErrorsKind::List(list) => {
+                for (index, errors) in list {
+                    collect_field_errors(errors, &format!("{}[{}]", path, index), out);
+                }
+            }
+        }
+    }
+}
+
 impl IntoResponse for Error {
     fn into_response(self) -> axum::response::Response {
-        let (status, success) = match self {
-            Error::Internal(_) => (500, false),
-            Error::BadRequest(_) => (400, false),
-            Error::Validation(_) => (400, false),
-            Error::Jwt(_) => (401, false),
-            Error::Repository(_) => (500, false),
-            Error::Logic(_) => (500, false),
-            Error::AppState(_) => (500, false),
-        };
+        let code = self.code();
+        let status = code.status().as_u16();
 
-        let body = ApiResponse::<()> {
+        if let Error::Validation(errors) = &self {
+            let body = ValidationErrorBody::from(errors);
+            let messages: Vec<&str> = body.errors.iter().map(|error| error.message.as_str()).collect();
+
+            return ApiResponse {
+                status,
+                message: format!("请求参数错误: {}", messages.join("; ")),
+                code: Some(code),
+                data: Some(body),
+                success: false,
+            }
+            .into_response();
+        }
+
+        ApiResponse::<()> {
             status,
             message: self.to_string(),
+            code: Some(code),
             data: None,
-            success,
-        };
-
-        (StatusCode::OK, Json(body)).into_response()
+            success: false,
+        }
+        .into_response()
     }
 }
 
 pub type Result<T> = std::result::Result<ApiResponse<T>, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use validator::Validate;
+
+    #[derive(Validate)]
+    struct Item {
+        #[validate(range(min = 0))]
+        stock: i32,
+    }
+
+    #[derive(Validate)]
+    struct Request {
+        #[validate(length(min = 3, message = "账号太短"))]
+        account: String,
+        #[validate(nested)]
+        items: Vec<Item>,
+    }
+
+    #[test]
+    fn test_validation_error_body() {
+        let req = Request {
+            account: "a".to_string(),
+            items: vec![Item { stock: 1 }, Item { stock: -1 }],
+        };
+
+        let errors = req.validate().unwrap_err();
+        let body = ValidationErrorBody::from(&errors);
+
+        assert_eq!(
+            body.errors,
+            vec![
+                FieldError {
+                    field: "account".to_string(),
+                    code: "length".to_string(),
+                    message: "账号太短".to_string(),
+                },
+                FieldError {
+                    field: "items[1].stock".to_string(),
+                    code: "range".to_string(),
+                    message: "items[1].stock 不满足 range 校验".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_error_code_and_status() {
+        let cases = [
+            (
+                Error::from(ServiceError::NotFound(Resource::Admin)),
+                ErrorCode::AdminNotFound,
+                StatusCode::NOT_FOUND,
+            ),
+            (
+                Error::from(database::errors::Error::OptimisticLockingError),
+                ErrorCode::OptimisticLockConflict,
+                StatusCode::CONFLICT,
+            ),
+            (
+                Error::from(ServiceError::from(
+                    database::errors::Error::OptimisticLockingError,
+                )),
+                ErrorCode::OptimisticLockConflict,
+                StatusCode::CONFLICT,
+            ),
+            (
+                Error::from(ServiceError::Conflict("账号已存在".to_string())),
+                ErrorCode::Conflict,
+                StatusCode::CONFLICT,
+            ),
+            (
+                Error::from(ServiceError::PermissionDenied("无权限".to_string())),
+                ErrorCode::PermissionDenied,
+                StatusCode::FORBIDDEN,
+            ),
+            (
+                Error::from(ServiceError::from("失败")),
+                ErrorCode::InternalError,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+            (
+                Error::from(entities::errors::Error::from_str("状态错误")),
+                ErrorCode::BusinessRuleViolation,
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ),
+        ];
+
+        for (error, code, status) in cases {
+            assert_eq!(error.code(), code);
+
+            let response = error.into_response();
+            assert_eq!(response.status(), status);
+        }
+
+        assert_eq!(
+            serde_json::to_value(ErrorCode::OptimisticLockConflict).unwrap(),
+            "OPTIMISTIC_LOCK_CONFLICT"
+        );
+    }
+}
@@ -1,67 +1,252 @@
-use axum::{
-    extract::{Path, State},
-    Json,
-};
+use axum::extract::{Path, State};
+use rbac::RBACUser;
 
 use crate::{
     app_state::AppState,
-    core::{errors::Result, response::ApiResponse},
+    core::{
+        errors::Result,
+        extract::{ValidatedJson, ValidatedQuery},
+        response::{ApiResponse, Empty},
+    },
 };
 
-use super::types::{AdminItem, CreateAdminRequest, UpdateAdminRequest, UpdateAdminRoleRequest};
+use super::types::{
+    AdminItem, CreateAdminRequest, EffectivePermissionsResponse, ExplainPermissionRequest,
+    ExplainPermissionResponse, UpdateAdminRequest, UpdateAdminRoleRequest,
+};
 
-pub async fn create_admin(State(state): State<AppState>, Json(req): Json<CreateAdminRequest>) -> Result<()> {
-    state
+#[utoipa::path(
+    post,
+    path = "/admins",
+    tag = "admin",
+    summary = "创建管理员",
+    request_body = CreateAdminRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn create_admin(
+    State(state): State<AppState>,
+    ValidatedJson(req): ValidatedJson<CreateAdminRequest>,
+) -> Result<()> {
+    let config = state.config().await?;
+
+    let user = state
         .service_factory()
         .admin_service()
+        .with_password_policy(config.password_policy)
         .create_admin(req.into())
         .await?;
 
-    state.rbac().reset().await?;
+    state.rbac().assign_user(&user).await?;
 
     ApiResponse::<()>::ok()
 }
 
+#[utoipa::path(
+    get,
+    path = "/admins",
+    tag = "admin",
+    summary = "获取管理员列表",
+    responses((status = 200, description = "OK", body = ApiResponse<Vec<AdminItem>>))
+)]
 pub async fn get_admin_list(State(state): State<AppState>) -> Result<Vec<AdminItem>> {
     let users = state.service_factory().admin_service().get_admin_list().await?;
 
     ApiResponse::ok_with_data(users.into_iter().map(|user| user.into()).collect())
 }
 
+#[utoipa::path(
+    put,
+    path = "/admins/{id}",
+    tag = "admin",
+    summary = "更新管理员",
+    params(("id" = String, Path, description = "管理员ID")),
+    request_body = UpdateAdminRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
 pub async fn update_admin(
     State(state): State<AppState>,
     Path(id): Path<String>,
-    Json(req): Json<UpdateAdminRequest>,
+    ValidatedJson(req): ValidatedJson<UpdateAdminRequest>,
 ) -> Result<()> {
-    state
+    let config = state.config().await?;
+    let password_changed = req.password.is_some();
+    let role_changed = req.role_names.is_some();
+
+    let user = state
         .service_factory()
         .admin_service()
-        .update_admin(req.to_params(id))
+        .with_password_policy(config.password_policy)
+        .update_admin(req.into_params(id.clone()))
         .await?;
 
+    if role_changed {
+        state.rbac().assign_user(&user).await?;
+    }
+
+    // 修改密码后该管理员已登录的会话全部失效
+    if password_changed {
+        state
+            .sessions()
+            .revoke_admin(&id, config.auth.refresh_token_ttl)
+            .await?;
+    }
+
     ApiResponse::<()>::ok()
 }
 
+#[utoipa::path(
+    delete,
+    path = "/admins/{id}",
+    tag = "admin",
+    summary = "删除管理员",
+    params(("id" = String, Path, description = "管理员ID")),
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
 pub async fn delete_admin(State(state): State<AppState>, Path(id): Path<String>) -> Result<()> {
-    state.service_factory().admin_service().delete_admin(id).await?;
+    let config = state.config().await?;
 
-    state.rbac().reset().await?;
+    let user = state
+        .service_factory()
+        .admin_service()
+        .delete_admin(id.clone())
+        .await?;
+
+    state
+        .sessions()
+        .revoke_admin(&id, config.auth.refresh_token_ttl)
+        .await?;
+
+    state.rbac().unassign_user(user.subject()).await?;
 
     ApiResponse::<()>::ok()
 }
 
+#[utoipa::path(
+    put,
+    path = "/admins/{id}/role",
+    tag = "admin",
+    summary = "修改管理员角色",
+    params(("id" = String, Path, description = "管理员ID")),
+    request_body = UpdateAdminRoleRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
 pub async fn update_admin_role(
     State(state): State<AppState>,
     Path(id): Path<String>,
-    Json(req): Json<UpdateAdminRoleRequest>,
+    ValidatedJson(req): ValidatedJson<UpdateAdminRoleRequest>,
 ) -> Result<()> {
+    let user = state
+        .service_factory()
+        .admin_service()
+        .update_admin_role(req.into_params(id))
+        .await?;
+
+    state.rbac().assign_user(&user).await?;
+
+    ApiResponse::<()>::ok()
+}
+
+#[utoipa::path(
+    put,
+    path = "/admins/{id}/enable",
+    tag = "admin",
+    summary = "启用管理员",
+    params(("id" = String, Path, description = "管理员ID")),
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn enable_admin(State(state): State<AppState>, Path(id): Path<String>) -> Result<()> {
+    state
+        .service_factory()
+        .admin_service()
+        .set_admin_active(&id, true)
+        .await?;
+
+    ApiResponse::<()>::ok()
+}
+
+#[utoipa::path(
+    put,
+    path = "/admins/{id}/disable",
+    tag = "admin",
+    summary = "禁用管理员",
+    params(("id" = String, Path, description = "管理员ID")),
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn disable_admin(State(state): State<AppState>, Path(id): Path<String>) -> Result<()> {
+    let config = state.config().await?;
+
     state
         .service_factory()
         .admin_service()
-        .update_admin_role(req.to_params(id))
+        .set_admin_active(&id, false)
         .await?;
 
-    state.rbac().reset().await?;
+    state
+        .sessions()
+        .revoke_admin(&id, config.auth.refresh_token_ttl)
+        .await?;
+
+    ApiResponse::<()>::ok()
+}
+
+/// 解除管理员因连续登录失败造成的锁定
+#[utoipa::path(
+    put,
+    path = "/admins/{id}/unlock",
+    tag = "admin",
+    summary = "解除管理员登录锁定",
+    params(("id" = String, Path, description = "管理员ID")),
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn unlock_admin(State(state): State<AppState>, Path(id): Path<String>) -> Result<()> {
+    let user = state.service_factory().admin_service().get_admin(&id).await?;
+
+    state.login_guard().unlock(&user.secret.account).await?;
 
     ApiResponse::<()>::ok()
 }
+
+/// 查看管理员的有效权限, 包括多个角色和继承的父角色
+#[utoipa::path(
+    get,
+    path = "/admins/{id}/permissions",
+    tag = "admin",
+    summary = "查看管理员的有效权限",
+    params(("id" = String, Path, description = "管理员ID")),
+    responses((status = 200, description = "OK", body = ApiResponse<EffectivePermissionsResponse>))
+)]
+pub async fn get_admin_permissions(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<EffectivePermissionsResponse> {
+    let user = state.service_factory().admin_service().get_admin(&id).await?;
+
+    let permissions = state.rbac().effective_permissions(user.subject()).await?;
+
+    ApiResponse::ok_with_data(permissions.into())
+}
+
+/// 模拟管理员访问指定接口的权限检查, 说明允许或拒绝的原因, 不会实际访问该接口
+#[utoipa::path(
+    get,
+    path = "/admins/{id}/permissions/explain",
+    tag = "admin",
+    summary = "模拟检查管理员的接口权限",
+    params(("id" = String, Path, description = "管理员ID"), ExplainPermissionRequest),
+    responses((status = 200, description = "OK", body = ApiResponse<ExplainPermissionResponse>))
+)]
+pub async fn explain_admin_permission(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    ValidatedQuery(req): ValidatedQuery<ExplainPermissionRequest>,
+) -> Result<ExplainPermissionResponse> {
+    let user = state.service_factory().admin_service().get_admin(&id).await?;
+
+    let explanation = state
+        .rbac()
+        .explain(user.subject(), req.method.to_uppercase(), req.path)
+        .await?;
+    let roles = state.service_factory().role_service().get_role_list().await?;
+
+    ApiResponse::ok_with_data(ExplainPermissionResponse::new(explanation, &roles))
+}
@@ -1,16 +1,21 @@
-use entities::Admin;
+use entities::{Admin, Role};
+use rbac::{EffectivePermissions, Explanation};
 use serde::{Deserialize, Serialize};
 use services::admin::{CreateAdminParams, UpdateAdminParams, UpdateAdminRoleParams};
+use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateAdminRequest {
     #[validate(length(min = 3, max = 32, message = "账号长度必须在3-32个字符之间"))]
     pub account: String,
-    #[validate(length(min = 6, max = 32, message = "密码长度必须在6-32个字符之间"))]
+    /// 密码强度由密码策略检查
+    #[validate(length(min = 1, message = "密码不能为空"))]
     pub password: String,
+    #[validate(length(min = 1, max = 32, message = "名称长度必须在1-32个字符之间"))]
     pub name: String,
-    pub role_name: String,
+    #[validate(length(min = 1, message = "至少需要指定一个角色"))]
+    pub role_names: Vec<String>,
 }
 
 impl From<CreateAdminRequest> for CreateAdminParams {
@@ -19,51 +24,55 @@ impl From<CreateAdminRequest> for CreateAdminParams {
             account: req.account,
             password: req.password,
             name: req.name,
-            role_name: req.role_name,
+            role_names: req.role_names,
         }
     }
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateAdminRequest {
+    #[validate(length(min = 1, max = 32, message = "名称长度必须在1-32个字符之间"))]
     pub name: Option<String>,
-    #[validate(length(min = 6, max = 32, message = "密码长度必须在6-32个字符之间"))]
+    /// 密码强度由密码策略检查
+    #[validate(length(min = 1, message = "密码不能为空"))]
     pub password: Option<String>,
-    pub role_name: Option<String>,
+    #[validate(length(min = 1, message = "至少需要指定一个角色"))]
+    pub role_names: Option<Vec<String>>,
 }
 
 impl UpdateAdminRequest {
-    pub fn to_params(self, id: String) -> UpdateAdminParams {
+    pub fn into_params(self, id: String) -> UpdateAdminParams {
         UpdateAdminParams {
             id,
             name: self.name,
             password: self.password,
-            role_name: self.role_name,
+            role_names: self.role_names,
         }
     }
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateAdminRoleRequest {
-    #[validate(length(min = 2, max = 32, message = "角色名称长度必须在2-32个字符之间"))]
-    pub role_name: String,
+    #[validate(length(min = 1, message = "至少需要指定一个角色"))]
+    pub role_names: Vec<String>,
 }
 
 impl UpdateAdminRoleRequest {
-    pub fn to_params(self, id: String) -> UpdateAdminRoleParams {
+    pub fn into_params(self, id: String) -> UpdateAdminRoleParams {
         UpdateAdminRoleParams {
             id,
-            role_name: self.role_name,
+            role_names: self.role_names,
         }
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct AdminItem {
     pub id: String,
     pub account: String,
     pub name: String,
-    pub role_name: String,
+    pub role_names: Vec<String>,
+    pub is_active: bool,
     pub created_at: u64,
 }
 
@@ -73,8 +82,106 @@ impl From<Admin> for AdminItem {
             id: admin.base.id,
             account: admin.secret.account,
             name: admin.name,
-            role_name: admin.role_name,
+            role_names: admin.role_names,
+            is_active: admin.is_active,
             created_at: admin.base.created_at,
         }
     }
 }
+
+/// 管理员的有效权限
+///
+/// # 字段
+///
+/// * `roles` - 直接拥有和继承的全部角色
+/// * `policies` - 这些角色授予的全部权限
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EffectivePermissionsResponse {
+    pub roles: Vec<String>,
+    pub policies: Vec<PolicyItem>,
+}
+
+/// 角色授予的一条权限
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PolicyItem {
+    pub role: String,
+    pub method: String,
+    pub path: String,
+}
+
+impl From<EffectivePermissions> for EffectivePermissionsResponse {
+    fn from(permissions: EffectivePermissions) -> Self {
+        Self {
+            roles: permissions.roles,
+            policies: permissions
+                .policies
+                .into_iter()
+                .map(|policy| PolicyItem {
+                    role: policy.role,
+                    method: policy.method,
+                    path: policy.path,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExplainPermissionRequest {
+    #[validate(length(min = 1, message = "请求方法不能为空"))]
+    pub method: String,
+    #[validate(length(min = 1, message = "请求路径不能为空"))]
+    pub path: String,
+}
+
+/// 授予本次请求的角色权限
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PermissionGrant {
+    pub role: String,
+    pub module: String,
+    pub method: String,
+    pub path: String,
+    pub description: String,
+}
+
+/// 权限检查的判定说明
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExplainPermissionResponse {
+    pub allowed: bool,
+    pub reason: String,
+    pub roles: Vec<String>,
+    pub grants: Vec<PermissionGrant>,
+}
+
+impl ExplainPermissionResponse {
+    /// 根据判定说明生成响应, 从角色配置中找到匹配权限对应的路由项
+    pub fn new(explanation: Explanation, roles: &[Role]) -> Self {
+        let grants = explanation
+            .matched
+            .into_iter()
+            .map(|policy| {
+                let item = roles
+                    .iter()
+                    .filter(|role| role.name == policy.role)
+                    .flat_map(|role| role.permissions.iter())
+                    .find(|item| item.method == policy.method && item.path == policy.path);
+
+                PermissionGrant {
+                    module: item.map(|item| item.module.clone()).unwrap_or_default(),
+                    description: item.map(|item| item.description.clone()).unwrap_or_default(),
+                    role: policy.role,
+                    method: policy.method,
+                    path: policy.path,
+                }
+            })
+            .collect();
+
+        Self {
+            allowed: explanation.allowed,
+            reason: explanation.reason,
+            roles: explanation.roles,
+            grants,
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use axum::extract::{Extension, Path, State};
+
+use crate::{
+    app_state::AppState,
+    core::{
+        errors::Result,
+        extract::ValidatedJson,
+        response::{ApiResponse, Empty},
+        schema::UserID,
+    },
+};
+
+use super::types::{ApiKeyResponse, ApiKeyTokenResponse, CreateApiKeyRequest, UpdateApiKeyRequest};
+
+#[utoipa::path(
+    post,
+    path = "/api-keys",
+    tag = "api_key",
+    summary = "创建 API 密钥",
+    request_body = CreateApiKeyRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<ApiKeyTokenResponse>))
+)]
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    user_id: Extension<UserID>,
+    ValidatedJson(req): ValidatedJson<CreateApiKeyRequest>,
+) -> Result<ApiKeyTokenResponse> {
+    let (api_key, token) = state
+        .service_factory()
+        .api_key_service()
+        .create_api_key(req.into_params(user_id.0.into()))
+        .await?;
+
+    state.rbac().assign_user(&api_key).await?;
+
+    ApiResponse::ok_with_data(token.into())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api-keys",
+    tag = "api_key",
+    summary = "获取 API 密钥列表",
+    responses((status = 200, description = "OK", body = ApiResponse<Vec<ApiKeyResponse>>))
+)]
+pub async fn get_api_key_list(State(state): State<AppState>) -> Result<Vec<ApiKeyResponse>> {
+    let api_keys = state
+        .service_factory()
+        .api_key_service()
+        .get_api_key_list()
+        .await?;
+
+    ApiResponse::ok_with_data(api_keys.into_iter().map(ApiKeyResponse::from).collect())
+}
+
+#[utoipa::path(
+    put,
+    path = "/api-keys/{id}",
+    tag = "api_key",
+    summary = "更新 API 密钥",
+    params(("id" = String, Path, description = "API 密钥ID")),
+    request_body = UpdateApiKeyRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn update_api_key(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    ValidatedJson(req): ValidatedJson<UpdateApiKeyRequest>,
+) -> Result<()> {
+    let role_changed = req.role_name.is_some();
+
+    let api_key = state
+        .service_factory()
+        .api_key_service()
+        .update_api_key(req.into_params(id))
+        .await?;
+
+    if role_changed {
+        state.rbac().assign_user(&api_key).await?;
+    }
+
+    ApiResponse::<()>::ok()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api-keys/{id}",
+    tag = "api_key",
+    summary = "删除 API 密钥",
+    params(("id" = String, Path, description = "API 密钥ID")),
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn delete_api_key(State(state): State<AppState>, Path(id): Path<String>) -> Result<()> {
+    let api_key = state
+        .service_factory()
+        .api_key_service()
+        .delete_api_key(id)
+        .await?;
+
+    state.rbac().unassign_user(api_key.subject()).await?;
+
+    ApiResponse::<()>::ok()
+}
@@ -0,0 +1,4 @@
+mod api_key_handlers;
+mod types;
+
+pub use api_key_handlers::*;
@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use services::api_key::{ApiKeyDetail, ApiKeyToken, CreateApiKeyParams, UpdateApiKeyParams};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateApiKeyRequest {
+    #[validate(length(min = 1, max = 64, message = "名称长度必须在1-64个字符之间"))]
+    pub name: String,
+    #[validate(length(min = 1, message = "角色不能为空"))]
+    pub role_name: String,
+    /// 过期时间(秒级时间戳), 为空表示永不过期
+    pub expires_at: Option<i64>,
+}
+
+impl CreateApiKeyRequest {
+    pub fn into_params(self, created_by: String) -> CreateApiKeyParams {
+        CreateApiKeyParams {
+            name: self.name,
+            role_name: self.role_name,
+            expires_at: self.expires_at,
+            created_by,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateApiKeyRequest {
+    #[validate(length(min = 1, max = 64, message = "名称长度必须在1-64个字符之间"))]
+    pub name: Option<String>,
+    pub role_name: Option<String>,
+    pub expires_at: Option<i64>,
+    pub is_active: Option<bool>,
+}
+
+impl UpdateApiKeyRequest {
+    pub fn into_params(self, id: String) -> UpdateApiKeyParams {
+        UpdateApiKeyParams {
+            id,
+            name: self.name,
+            role_name: self.role_name,
+            expires_at: self.expires_at,
+            is_active: self.is_active,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiKeyResponse {
+    pub id: String,
+    pub name: String,
+    pub key_id: String,
+    pub role_name: String,
+    pub expires_at: i64,
+    pub last_used_at: i64,
+    pub is_active: bool,
+    pub created_by: String,
+    pub created_at: u64,
+}
+
+impl From<ApiKeyDetail> for ApiKeyResponse {
+    fn from(detail: ApiKeyDetail) -> Self {
+        Self {
+            id: detail.id,
+            name: detail.name,
+            key_id: detail.key_id,
+            role_name: detail.role_name,
+            expires_at: detail.expires_at,
+            last_used_at: detail.last_used_at,
+            is_active: detail.is_active,
+            created_by: detail.created_by,
+            created_at: detail.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiKeyTokenResponse {
+    pub id: String,
+    pub key_id: String,
+    pub token: String,
+}
+
+impl From<ApiKeyToken> for ApiKeyTokenResponse {
+    fn from(token: ApiKeyToken) -> Self {
+        Self {
+            id: token.id,
+            key_id: token.key_id,
+            token: token.token,
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use axum::extract::{Extension, Path, Query, State};
+use log::{error, info};
+
+use crate::{
+    app_state::AppState,
+    core::{
+        errors::Result,
+        extract::ValidatedJson,
+        response::ApiResponse,
+        schema::{RequestId, UserID},
+    },
+};
+
+use super::types::{BroadcastQueryRequest, BroadcastResponse, CreateBroadcastRequest};
+
+/// 创建广播
+///
+/// 广播记录和待发送消息创建完成后立即返回广播ID, 消息在后台投递,
+/// 投递进度可以通过广播详情查询
+#[utoipa::path(
+    post,
+    path = "/messages/broadcasts",
+    tag = "broadcast",
+    summary = "创建广播",
+    request_body = CreateBroadcastRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<String>))
+)]
+pub async fn create_broadcast(
+    State(state): State<AppState>,
+    user_id: Extension<UserID>,
+    Extension(request_id): Extension<RequestId>,
+    ValidatedJson(req): ValidatedJson<CreateBroadcastRequest>,
+) -> Result<String> {
+    let params = req.into_params(user_id.0.into(), request_id.0)?;
+    let broadcast = state
+        .service_factory()
+        .broadcast_service()
+        .create_broadcast(params)
+        .await?;
+
+    info!(
+        "Broadcast {} created for {} recipients",
+        broadcast.base.id, broadcast.total
+    );
+
+    let services = state.service_factory().clone();
+    let broadcast_id = broadcast.base.id.clone();
+    tokio::spawn(async move {
+        if let Err(e) = services.notify_service().deliver_broadcast(&broadcast_id).await {
+            error!("Failed to deliver broadcast {}: {}", broadcast_id, e);
+        }
+    });
+
+    ApiResponse::ok_with_data(broadcast.base.id)
+}
+
+#[utoipa::path(
+    get,
+    path = "/messages/broadcasts",
+    tag = "broadcast",
+    summary = "获取广播列表",
+    params(BroadcastQueryRequest),
+    responses((status = 200, description = "OK", body = ApiResponse<Vec<BroadcastResponse>>))
+)]
+pub async fn get_broadcast_list(
+    State(state): State<AppState>,
+    Query(query): Query<BroadcastQueryRequest>,
+) -> Result<Vec<BroadcastResponse>> {
+    let broadcasts = state
+        .service_factory()
+        .broadcast_service()
+        .get_broadcast_list(query.into_query())
+        .await?;
+
+    ApiResponse::ok_with_data(broadcasts.into_iter().map(BroadcastResponse::from).collect())
+}
+
+#[utoipa::path(
+    get,
+    path = "/messages/broadcasts/{id}",
+    tag = "broadcast",
+    summary = "获取广播详情",
+    params(("id" = String, Path, description = "广播ID")),
+    responses((status = 200, description = "OK", body = ApiResponse<BroadcastResponse>))
+)]
+pub async fn get_broadcast(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<BroadcastResponse> {
+    let broadcast = state
+        .service_factory()
+        .broadcast_service()
+        .get_broadcast(&id)
+        .await?;
+
+    ApiResponse::ok_with_data(broadcast.into())
+}
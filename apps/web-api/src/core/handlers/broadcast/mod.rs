@@ -0,0 +1,4 @@
+mod broadcast_handlers;
+mod types;
+
+pub use broadcast_handlers::*;
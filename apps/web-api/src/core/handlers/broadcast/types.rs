@@ -0,0 +1,95 @@
+use entities::errors::Error as EntityError;
+use entities::{BroadcastStatus, BroadcastTarget, MessageCategory, MessageChannel};
+use serde::{Deserialize, Serialize};
+use services::broadcast::{BroadcastDetail, BroadcastQuery, CreateBroadcastParams};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateBroadcastRequest {
+    pub channel: String,
+    pub target: BroadcastTarget,
+    pub category: Option<String>,
+    #[validate(length(min = 1))]
+    pub subject: String,
+    #[validate(length(min = 1))]
+    pub content: String,
+}
+
+impl CreateBroadcastRequest {
+    pub fn into_params(
+        self,
+        created_by: String,
+        request_id: String,
+    ) -> Result<CreateBroadcastParams, EntityError> {
+        Ok(CreateBroadcastParams {
+            channel: MessageChannel::from_str(&self.channel)?,
+            target: self.target,
+            category: match self.category {
+                Some(c) => MessageCategory::from_str(&c)?,
+                None => MessageCategory::Announcement,
+            },
+            subject: self.subject,
+            content: self.content,
+            created_by,
+            request_id: Some(request_id),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BroadcastQueryRequest {
+    pub status: Option<BroadcastStatus>,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+impl BroadcastQueryRequest {
+    pub fn into_query(self) -> BroadcastQuery {
+        BroadcastQuery {
+            status: self.status,
+            page: self.page.unwrap_or(1),
+            page_size: self.page_size.unwrap_or(20),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BroadcastResponse {
+    pub id: String,
+    pub channel: String,
+    pub target: BroadcastTarget,
+    pub category: String,
+    pub subject: String,
+    pub content: String,
+    pub status: String,
+    pub total: u64,
+    pub skipped: u64,
+    pub sent: u64,
+    pub failed: u64,
+    pub progress: f64,
+    pub created_by: String,
+    pub created_at: u64,
+}
+
+impl From<BroadcastDetail> for BroadcastResponse {
+    fn from(detail: BroadcastDetail) -> Self {
+        Self {
+            id: detail.id,
+            channel: format!("{:?}", detail.channel),
+            target: detail.target,
+            category: detail.category.to_string(),
+            subject: detail.subject,
+            content: detail.content,
+            status: format!("{:?}", detail.status),
+            total: detail.total,
+            skipped: detail.skipped,
+            sent: detail.sent,
+            failed: detail.failed,
+            progress: detail.progress,
+            created_by: detail.created_by,
+            created_at: detail.created_at,
+        }
+    }
+}
@@ -2,25 +2,73 @@ use axum::extract::{Extension, Path, Query, State};
 
 use crate::{
     app_state::AppState,
-    core::{errors::Result, response::ApiResponse, schema::UserID},
+    core::{
+        errors::Result,
+        extract::ValidatedJson,
+        response::{ApiResponse, Empty, ListResponse},
+        schema::UserID,
+    },
 };
 
-use super::types::{GetMessagesRequest, InternalMessageResponse};
+use super::types::{
+    GetMessagesRequest, InternalMessageResponse, MarkMessagesReadRequest, UnreadCountResponse,
+    UpdatedCountResponse,
+};
 
+#[utoipa::path(
+    get,
+    path = "/messages/internal",
+    tag = "internal_message",
+    summary = "获取我的站内消息",
+    params(GetMessagesRequest),
+    responses((status = 200, description = "OK", body = ApiResponse<ListResponse<InternalMessageResponse>>))
+)]
 pub async fn get_my_messages(
     State(state): State<AppState>,
     Query(query): Query<GetMessagesRequest>,
     user_id: Extension<UserID>,
-) -> Result<Vec<InternalMessageResponse>> {
-    let messages = state
+) -> Result<ListResponse<InternalMessageResponse>> {
+    let query = query.into_query(user_id.0.into())?;
+    let page = state
         .service_factory()
         .internal_message_service()
-        .get_my_messages(user_id.0.into(), query.page, query.page_size, query.status)
+        .get_my_messages(query)
         .await?;
 
-    ApiResponse::ok_with_data(messages.into_iter().map(|m| m.into()).collect())
+    ApiResponse::ok_with_data(ListResponse {
+        items: page.items.into_iter().map(|m| m.into()).collect(),
+        total: page.total,
+    })
 }
 
+#[utoipa::path(
+    get,
+    path = "/messages/internal/unread-count",
+    tag = "internal_message",
+    summary = "获取未读站内消息数量",
+    responses((status = 200, description = "OK", body = ApiResponse<UnreadCountResponse>))
+)]
+pub async fn get_unread_count(
+    State(state): State<AppState>,
+    user_id: Extension<UserID>,
+) -> Result<UnreadCountResponse> {
+    let count = state
+        .service_factory()
+        .internal_message_service()
+        .get_unread_count(user_id.0.into())
+        .await?;
+
+    ApiResponse::ok_with_data(UnreadCountResponse { count })
+}
+
+#[utoipa::path(
+    post,
+    path = "/messages/internal/{id}/read",
+    tag = "internal_message",
+    summary = "标记站内消息为已读",
+    params(("id" = String, Path, description = "消息ID")),
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
 pub async fn mark_message_as_read(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -34,3 +82,89 @@ pub async fn mark_message_as_read(
 
     ApiResponse::<()>::ok()
 }
+
+#[utoipa::path(
+    post,
+    path = "/messages/internal/read",
+    tag = "internal_message",
+    summary = "批量标记站内消息为已读",
+    request_body = MarkMessagesReadRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<UpdatedCountResponse>))
+)]
+pub async fn mark_messages_as_read(
+    State(state): State<AppState>,
+    user_id: Extension<UserID>,
+    ValidatedJson(req): ValidatedJson<MarkMessagesReadRequest>,
+) -> Result<UpdatedCountResponse> {
+    let updated = state
+        .service_factory()
+        .internal_message_service()
+        .mark_many_as_read(req.ids, user_id.0.into())
+        .await?;
+
+    ApiResponse::ok_with_data(UpdatedCountResponse { updated })
+}
+
+#[utoipa::path(
+    post,
+    path = "/messages/internal/read-all",
+    tag = "internal_message",
+    summary = "标记全部站内消息为已读",
+    responses((status = 200, description = "OK", body = ApiResponse<UpdatedCountResponse>))
+)]
+pub async fn mark_all_messages_as_read(
+    State(state): State<AppState>,
+    user_id: Extension<UserID>,
+) -> Result<UpdatedCountResponse> {
+    let updated = state
+        .service_factory()
+        .internal_message_service()
+        .mark_all_as_read(user_id.0.into())
+        .await?;
+
+    ApiResponse::ok_with_data(UpdatedCountResponse { updated })
+}
+
+#[utoipa::path(
+    post,
+    path = "/messages/internal/{id}/archive",
+    tag = "internal_message",
+    summary = "归档站内消息",
+    params(("id" = String, Path, description = "消息ID")),
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn archive_message(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    user_id: Extension<UserID>,
+) -> Result<()> {
+    state
+        .service_factory()
+        .internal_message_service()
+        .archive(id, user_id.0.into())
+        .await?;
+
+    ApiResponse::<()>::ok()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/messages/internal/{id}",
+    tag = "internal_message",
+    summary = "删除站内消息",
+    params(("id" = String, Path, description = "消息ID")),
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn delete_message(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    user_id: Extension<UserID>,
+) -> Result<()> {
+    state
+        .service_factory()
+        .internal_message_service()
+        .delete(id, user_id.0.into())
+        .await?;
+
+    ApiResponse::<()>::ok()
+}
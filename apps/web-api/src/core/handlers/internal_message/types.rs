@@ -1,18 +1,61 @@
+use entities::errors::Error as EntityError;
+use entities::MessageCategory;
 use serde::{Deserialize, Serialize};
+use services::internal_message::InternalMessageQuery;
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct GetMessagesRequest {
     pub page: Option<i64>,
     pub page_size: Option<i64>,
     pub status: Option<String>,
+    pub category: Option<String>,
+    pub archived: Option<bool>,
 }
 
-#[derive(Debug, Serialize)]
+impl GetMessagesRequest {
+    pub fn into_query(self, recipient: String) -> Result<InternalMessageQuery, EntityError> {
+        Ok(InternalMessageQuery {
+            recipient,
+            status: self.status,
+            category: match self.category {
+                Some(c) => Some(MessageCategory::from_str(&c)?),
+                None => None,
+            },
+            archived: self.archived.unwrap_or(false),
+            page: self.page.unwrap_or(1),
+            page_size: self.page_size.unwrap_or(20),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct MarkMessagesReadRequest {
+    #[validate(length(min = 1, max = 200, message = "消息ID数量必须在1-200之间"))]
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UnreadCountResponse {
+    pub count: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UpdatedCountResponse {
+    pub updated: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct InternalMessageResponse {
     pub id: String,
     pub subject: String,
     pub content: String,
     pub status: String,
+    pub category: String,
+    pub priority: String,
+    pub archived: bool,
     pub created_at: u64,
 }
 
@@ -23,6 +66,9 @@ impl From<services::internal_message::InternalMessageResponse> for InternalMessa
             subject: msg.subject,
             content: msg.content,
             status: format!("{:?}", msg.status),
+            category: format!("{:?}", msg.category),
+            priority: format!("{:?}", msg.priority),
+            archived: msg.archived,
             created_at: msg.created_at,
         }
     }
@@ -1,27 +1,517 @@
-use axum::{extract::State, Json};
+use axum::{extract::State, Extension, Json};
+use config::Config;
 
-use super::types::{AuthRequest, AuthResponse};
+use super::types::{
+    AuthRequest, AuthResponse, LoginResponse, LogoutRequest, OidcAuthorizeResponse, OidcCallbackRequest,
+    RefreshRequest, TwoFactorActivationResponse, TwoFactorChallenge, TwoFactorEnrollRequest,
+    TwoFactorVerifyRequest,
+};
 use crate::app_state::AppState;
 use crate::core::errors::{Error, Result};
-use crate::core::response::ApiResponse;
+use crate::core::extract::ValidatedJson;
+use crate::core::response::{ApiResponse, Empty};
+use crate::core::schema::{AccessToken, ClientIp, RequestId};
 use crate::jwt::Engine;
-use database::repositories::AdminRepository;
-use services::admin::IAdminRepository;
+use crate::login_guard::Lockout;
+use crate::oidc::OidcClient;
+use crate::session::OidcLoginState;
+use database::repositories::{AdminRepository, OperationLogRepository};
+use entities::Admin;
+use services::admin::{IAdminRepository, SsoLoginParams, TwoFactorEnrollment};
+use services::operation_log::{CreateLogParams, OperationLogService};
 
-pub async fn login(State(state): State<AppState>, Json(request): Json<AuthRequest>) -> Result<AuthResponse> {
+#[utoipa::path(
+    post,
+    path = "/login",
+    tag = "auth",
+    summary = "账号密码登录",
+    request_body = AuthRequest,
+    security(()),
+    responses((status = 200, description = "OK", body = ApiResponse<LoginResponse>))
+)]
+pub async fn login(
+    State(state): State<AppState>,
+    Extension(ClientIp(ip)): Extension<ClientIp>,
+    Extension(request_id): Extension<RequestId>,
+    Json(request): Json<AuthRequest>,
+) -> Result<LoginResponse> {
     let config = state.config().await?;
-    let app_secret = config.app.secret;
-    let jwt_engine = Engine::new(app_secret.clone())?;
+    let guard = state.login_guard();
+
+    if let Some(lockout) = guard.check(&request.account, &ip).await? {
+        let description = lockout_message(lockout);
+        record_security_event(
+            &state,
+            &request.account,
+            "锁定期间尝试登录",
+            &description,
+            &ip,
+            &request_id.0,
+        )
+        .await;
+        return Err(Error::TooManyRequests(lockout_message(lockout)));
+    }
 
     let admin_repo = AdminRepository::new(state.db().clone());
     let user = admin_repo.find_by_account(&request.account).await?;
 
-    if let Some(user) = user {
-        if user.secret.is_match(&request.password) {
-            let token = jwt_engine.create_token(user)?;
-            return ApiResponse::ok_with_data(AuthResponse { token });
+    if let Some(mut user) = user.filter(|user| user.secret.is_match(&request.password)) {
+        if !user.can_login() {
+            return Err(Error::PermissionDenied("账号已被禁用".to_string()));
+        }
+
+        // 旧的 MD5 哈希在登录成功后升级为 Argon2id, 升级失败不影响本次登录
+        if user.secret.needs_rehash() {
+            match user.secret.rehash(&request.password) {
+                Ok(()) => {
+                    if let Err(e) = admin_repo.update(&user).await {
+                        log::warn!("升级用户 {} 的密码哈希失败: {}", user.secret.account, e);
+                    }
+                }
+                Err(e) => log::warn!("重新计算用户 {} 的密码哈希失败: {}", user.secret.account, e),
+            }
+        }
+
+        let response = complete_login(&state, &config, user).await?;
+        return ApiResponse::ok_with_data(response);
+    }
+
+    record_failed_attempt(&state, &config, &request.account, &ip, &request_id.0, "登录失败").await?;
+
+    Err(Error::Unauthorized("用户名或密码错误".to_string()))
+}
+
+/// 发起单点登录, 返回身份提供方的授权地址
+///
+/// 前端跳转到该地址, 用户在身份提供方登录后回调到配置的`redirect_uri`
+#[utoipa::path(
+    get,
+    path = "/auth/oidc/authorize",
+    tag = "auth",
+    summary = "发起单点登录",
+    security(()),
+    responses((status = 200, description = "OK", body = ApiResponse<OidcAuthorizeResponse>))
+)]
+pub async fn oidc_authorize(State(state): State<AppState>) -> Result<OidcAuthorizeResponse> {
+    let config = state.config().await?;
+    let client = OidcClient::new(config.oidc.clone())?;
+
+    let metadata = client.discover().await?;
+    let request = client.authorization_request(&metadata)?;
+
+    state
+        .sessions()
+        .save_oidc_state(
+            &request.state,
+            &OidcLoginState {
+                nonce: request.nonce,
+                code_verifier: request.code_verifier,
+            },
+            config.oidc.state_ttl,
+        )
+        .await?;
+
+    ApiResponse::ok_with_data(OidcAuthorizeResponse {
+        authorization_url: request.url,
+    })
+}
+
+/// 完成单点登录: 使用回调中的授权码换取并校验 ID 令牌, 然后按密码登录相同的流程签发令牌
+#[utoipa::path(
+    post,
+    path = "/auth/oidc/callback",
+    tag = "auth",
+    summary = "完成单点登录",
+    request_body = OidcCallbackRequest,
+    security(()),
+    responses((status = 200, description = "OK", body = ApiResponse<LoginResponse>))
+)]
+pub async fn oidc_callback(
+    State(state): State<AppState>,
+    Extension(ClientIp(ip)): Extension<ClientIp>,
+    Extension(request_id): Extension<RequestId>,
+    Json(request): Json<OidcCallbackRequest>,
+) -> Result<LoginResponse> {
+    let config = state.config().await?;
+    let client = OidcClient::new(config.oidc.clone())?.with_jwks_cache(state.oidc_keys().clone());
+
+    let login = state
+        .sessions()
+        .consume_oidc_state(&request.state)
+        .await?
+        .ok_or_else(|| Error::Unauthorized("登录请求无效或已过期".to_string()))?;
+
+    let metadata = client.discover().await?;
+    let id_token = client
+        .exchange_code(&metadata, &request.code, &login.code_verifier)
+        .await?;
+    let identity = client.verify_id_token(&metadata, &id_token, &login.nonce).await?;
+
+    let sso = state
+        .service_factory()
+        .admin_service()
+        .sso_login(SsoLoginParams {
+            role_names: config.oidc.map_roles(&identity.groups),
+            issuer: identity.issuer,
+            subject: identity.subject,
+            email: identity.email,
+            email_verified: identity.email_verified,
+            name: identity.name,
+            provision: config.oidc.jit_provisioning,
+            sync_role: config.oidc.sync_role_on_login,
+            link_by_email: config.oidc.link_by_email,
+        })
+        .await?;
+
+    if sso.role_changed {
+        state.rbac().assign_user(&sso.admin).await?;
+    }
+
+    let user = sso.admin;
+    if !user.can_login() {
+        return Err(Error::PermissionDenied("账号已被禁用".to_string()));
+    }
+
+    if let Some(lockout) = state.login_guard().check(&user.secret.account, &ip).await? {
+        return Err(Error::TooManyRequests(lockout_message(lockout)));
+    }
+
+    if sso.provisioned {
+        let description = format!("首次单点登录, 角色 {}", user.role_names.join(", "));
+        record_security_event(
+            &state,
+            &user.secret.account,
+            "自动创建管理员",
+            &description,
+            &ip,
+            &request_id.0,
+        )
+        .await;
+    }
+
+    let response = complete_login(&state, &config, user).await?;
+    ApiResponse::ok_with_data(response)
+}
+
+/// 登录第二步: 使用预登录令牌和验证码(或恢复码)完成登录
+#[utoipa::path(
+    post,
+    path = "/auth/2fa/verify",
+    tag = "auth",
+    summary = "登录时验证双因素认证",
+    request_body = TwoFactorVerifyRequest,
+    security(()),
+    responses((status = 200, description = "OK", body = ApiResponse<AuthResponse>))
+)]
+pub async fn verify_two_factor(
+    State(state): State<AppState>,
+    Extension(ClientIp(ip)): Extension<ClientIp>,
+    Extension(request_id): Extension<RequestId>,
+    ValidatedJson(request): ValidatedJson<TwoFactorVerifyRequest>,
+) -> Result<AuthResponse> {
+    let config = state.config().await?;
+    let admin_service = state.service_factory().admin_service();
+
+    let user = pre_auth_admin(&state, &request.pre_auth_token).await?;
+    let account = user.secret.account.clone();
+
+    if let Some(lockout) = state.login_guard().check(&account, &ip).await? {
+        return Err(Error::TooManyRequests(lockout_message(lockout)));
+    }
+
+    match admin_service
+        .verify_two_factor(&user.base.id, &request.code)
+        .await
+    {
+        Ok(user) => {
+            state
+                .sessions()
+                .revoke_pre_auth_token(&request.pre_auth_token)
+                .await?;
+            state.login_guard().record_success(&account).await?;
+
+            let tokens = issue_tokens(&state, user).await?;
+            ApiResponse::ok_with_data(tokens)
+        }
+        Err(e) => {
+            state
+                .sessions()
+                .record_pre_auth_failure(&request.pre_auth_token, config.auth.pre_auth_token_ttl)
+                .await?;
+            record_failed_attempt(&state, &config, &account, &ip, &request_id.0, "双因素认证失败").await?;
+
+            Err(e.into())
         }
     }
+}
+
+/// 角色要求双因素认证但尚未绑定时, 使用预登录令牌开始绑定
+#[utoipa::path(
+    post,
+    path = "/auth/2fa/enroll",
+    tag = "auth",
+    summary = "登录时开始绑定双因素认证",
+    request_body = TwoFactorEnrollRequest,
+    security(()),
+    responses((status = 200, description = "OK", body = ApiResponse<TwoFactorEnrollment>))
+)]
+pub async fn enroll_two_factor(
+    State(state): State<AppState>,
+    Json(request): Json<TwoFactorEnrollRequest>,
+) -> Result<TwoFactorEnrollment> {
+    let config = state.config().await?;
+    let user = pre_auth_admin(&state, &request.pre_auth_token).await?;
+
+    let enrollment = state
+        .service_factory()
+        .admin_service()
+        .begin_two_factor_enrollment(&user.base.id, &config.auth.totp_issuer)
+        .await?;
+
+    ApiResponse::ok_with_data(enrollment)
+}
+
+/// 使用预登录令牌完成双因素认证绑定并登录
+#[utoipa::path(
+    post,
+    path = "/auth/2fa/activate",
+    tag = "auth",
+    summary = "登录时完成双因素认证绑定",
+    request_body = TwoFactorVerifyRequest,
+    security(()),
+    responses((status = 200, description = "OK", body = ApiResponse<TwoFactorActivationResponse>))
+)]
+pub async fn activate_two_factor(
+    State(state): State<AppState>,
+    ValidatedJson(request): ValidatedJson<TwoFactorVerifyRequest>,
+) -> Result<TwoFactorActivationResponse> {
+    let config = state.config().await?;
+    let admin_service = state.service_factory().admin_service();
+    let user = pre_auth_admin(&state, &request.pre_auth_token).await?;
 
-    Err(Error::BadRequest("用户名或密码错误".to_string()))
+    let recovery_codes = match admin_service
+        .activate_two_factor(&user.base.id, &request.code)
+        .await
+    {
+        Ok(recovery_codes) => recovery_codes,
+        Err(e) => {
+            state
+                .sessions()
+                .record_pre_auth_failure(&request.pre_auth_token, config.auth.pre_auth_token_ttl)
+                .await?;
+            return Err(e.into());
+        }
+    };
+
+    state
+        .sessions()
+        .revoke_pre_auth_token(&request.pre_auth_token)
+        .await?;
+    state.login_guard().record_success(&user.secret.account).await?;
+
+    let user = admin_service.get_admin(&user.base.id).await?;
+    let tokens = issue_tokens(&state, user).await?;
+
+    ApiResponse::ok_with_data(TwoFactorActivationResponse {
+        recovery_codes,
+        tokens,
+    })
+}
+
+/// 使用刷新令牌换取新的访问令牌和刷新令牌, 旧的刷新令牌随即失效
+#[utoipa::path(
+    post,
+    path = "/auth/refresh",
+    tag = "auth",
+    summary = "刷新访问令牌",
+    request_body = RefreshRequest,
+    security(()),
+    responses((status = 200, description = "OK", body = ApiResponse<AuthResponse>))
+)]
+pub async fn refresh_token(
+    State(state): State<AppState>,
+    Json(request): Json<RefreshRequest>,
+) -> Result<AuthResponse> {
+    let session = state
+        .sessions()
+        .consume_refresh_token(&request.refresh_token)
+        .await?
+        .ok_or_else(|| Error::Unauthorized("刷新令牌无效或已过期".to_string()))?;
+
+    // 重新读取管理员信息, 角色变更在刷新后生效
+    let admin_repo = AdminRepository::new(state.db().clone());
+    let user = admin_repo
+        .find_by_id(&session.admin_id)
+        .await?
+        .ok_or_else(|| Error::Unauthorized("用户不存在".to_string()))?;
+
+    if !user.accepts_token(session.issued_at) {
+        return Err(Error::Unauthorized("刷新令牌已失效".to_string()));
+    }
+
+    let tokens = issue_tokens(&state, user).await?;
+    ApiResponse::ok_with_data(tokens)
+}
+
+/// 退出登录, 吊销当前访问令牌和传入的刷新令牌
+#[utoipa::path(
+    post,
+    path = "/logout",
+    tag = "auth",
+    summary = "退出登录",
+    request_body(content = Option<LogoutRequest>),
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn logout(
+    State(state): State<AppState>,
+    Extension(access_token): Extension<AccessToken>,
+    request: Option<Json<LogoutRequest>>,
+) -> Result<()> {
+    let sessions = state.sessions();
+
+    sessions
+        .revoke_token(&access_token.jti, access_token.expires_at)
+        .await?;
+
+    if let Some(refresh_token) = request.and_then(|Json(request)| request.refresh_token) {
+        sessions.revoke_refresh_token(&refresh_token).await?;
+    }
+
+    ApiResponse::<()>::ok()
+}
+
+async fn issue_tokens(state: &AppState, user: Admin) -> std::result::Result<AuthResponse, Error> {
+    let config = state.config().await?;
+    let jwt_engine = Engine::new(config.app.secret)?;
+
+    let refresh_token = state
+        .sessions()
+        .issue_refresh_token(&user.base.id, config.auth.refresh_token_ttl)
+        .await?;
+    let token = jwt_engine.create_token(user, config.auth.access_token_ttl)?;
+
+    Ok(AuthResponse {
+        token,
+        refresh_token,
+        expires_in: config.auth.access_token_ttl,
+    })
+}
+
+/// 身份验证通过后完成登录
+///
+/// 启用了双因素认证, 或角色要求启用但尚未绑定时, 先签发预登录令牌; 否则直接签发令牌
+async fn complete_login(
+    state: &AppState,
+    config: &Config,
+    user: Admin,
+) -> std::result::Result<LoginResponse, Error> {
+    let enrollment_required = !user.two_factor.enabled
+        && state
+            .service_factory()
+            .admin_service()
+            .requires_two_factor(&user)
+            .await?;
+
+    if user.two_factor.enabled || enrollment_required {
+        let pre_auth_token = state
+            .sessions()
+            .issue_pre_auth_token(&user.base.id, config.auth.pre_auth_token_ttl)
+            .await?;
+
+        return Ok(LoginResponse::TwoFactorRequired(TwoFactorChallenge {
+            two_factor_required: true,
+            enrollment_required,
+            pre_auth_token,
+            expires_in: config.auth.pre_auth_token_ttl,
+        }));
+    }
+
+    state.login_guard().record_success(&user.secret.account).await?;
+
+    let tokens = issue_tokens(state, user).await?;
+    Ok(LoginResponse::Authenticated(tokens))
+}
+
+/// 读取预登录令牌对应的管理员, 令牌无效或账号已被禁用时返回未授权
+async fn pre_auth_admin(state: &AppState, pre_auth_token: &str) -> std::result::Result<Admin, Error> {
+    let admin_id = state
+        .sessions()
+        .find_pre_auth_token(pre_auth_token)
+        .await?
+        .ok_or_else(|| Error::Unauthorized("预登录令牌无效或已过期".to_string()))?;
+
+    let user = AdminRepository::new(state.db().clone())
+        .find_by_id(&admin_id)
+        .await?
+        .filter(|user| user.can_login())
+        .ok_or_else(|| Error::Unauthorized("账号不存在或已被禁用".to_string()))?;
+
+    Ok(user)
+}
+
+/// 记录一次失败的登录尝试: 累计失败次数、写入安全事件并延迟响应
+async fn record_failed_attempt(
+    state: &AppState,
+    config: &Config,
+    account: &str,
+    ip: &str,
+    request_id: &str,
+    action: &str,
+) -> std::result::Result<(), Error> {
+    let outcome = state
+        .login_guard()
+        .record_failure(account, ip, &config.auth)
+        .await?;
+
+    let description = format!("统计窗口内连续失败{}次", outcome.failures);
+    record_security_event(state, account, action, &description, ip, request_id).await;
+
+    if let Some(lockout) = outcome.locked {
+        let action = match lockout {
+            Lockout::Account => "账号锁定",
+            Lockout::Ip => "IP锁定",
+        };
+        let description = format!("锁定{}秒", config.auth.lockout_duration);
+        record_security_event(state, account, action, &description, ip, request_id).await;
+    }
+
+    // 逐次递增的延迟, 降低暴力破解的速度
+    tokio::time::sleep(outcome.delay).await;
+
+    Ok(())
+}
+
+fn lockout_message(lockout: Lockout) -> String {
+    match lockout {
+        Lockout::Account => "登录失败次数过多, 账号已被临时锁定, 请稍后再试".to_string(),
+        Lockout::Ip => "登录失败次数过多, 请稍后再试".to_string(),
+    }
+}
+
+/// 写入登录相关的安全事件, 写入失败不影响登录流程
+async fn record_security_event(
+    state: &AppState,
+    account: &str,
+    action: &str,
+    description: &str,
+    ip: &str,
+    request_id: &str,
+) {
+    let service = OperationLogService::new(OperationLogRepository::new(state.db().clone()));
+    let params = CreateLogParams {
+        operator: account.to_string(),
+        module: "安全".to_string(),
+        action: action.to_string(),
+        target_id: account.to_string(),
+        description: format!("{}: {}", action, description),
+        request_path: "/login".to_string(),
+        request_method: "POST".to_string(),
+        request_body: None,
+        ip_address: ip.to_string(),
+        request_id: request_id.to_string(),
+    };
+
+    if let Err(e) = service.create_log(params).await {
+        log::error!("Failed to create security event: {}", e);
+    }
 }
@@ -1,4 +1,6 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
 
 /// 认证请求结构体
 ///
@@ -8,7 +10,7 @@ use serde::{Deserialize, Serialize};
 ///
 /// * `account` - 用户账号
 /// * `password` - 用户密码
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct AuthRequest {
     pub account: String,
     pub password: String,
@@ -20,25 +22,117 @@ pub struct AuthRequest {
 ///
 /// # 字段
 ///
-/// * `token` - JWT认证令牌
-#[derive(Serialize, Deserialize)]
+/// * `token` - JWT访问令牌
+/// * `refresh_token` - 刷新令牌, 只能使用一次
+/// * `expires_in` - 访问令牌有效期(秒)
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct AuthResponse {
     pub token: String,
+    pub refresh_token: String,
+    pub expires_in: u64,
 }
 
-/// 用户信息结构体
+/// 需要双因素认证时的登录响应
 ///
-/// 用于向客户端返回用户基本信息
+/// # 字段
+///
+/// * `two_factor_required` - 固定为true, 便于客户端区分登录结果
+/// * `enrollment_required` - 角色要求双因素认证但尚未绑定, 需要先完成绑定
+/// * `pre_auth_token` - 预登录令牌, 只能用于完成双因素认证
+/// * `expires_in` - 预登录令牌有效期(秒)
+#[derive(Serialize, ToSchema)]
+pub struct TwoFactorChallenge {
+    pub two_factor_required: bool,
+    pub enrollment_required: bool,
+    pub pre_auth_token: String,
+    pub expires_in: u64,
+}
+
+/// 登录响应
+///
+/// 未启用双因素认证时直接返回令牌, 否则返回预登录令牌
+#[derive(Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum LoginResponse {
+    Authenticated(AuthResponse),
+    TwoFactorRequired(TwoFactorChallenge),
+}
+
+/// 双因素认证请求结构体
+///
+/// # 字段
+///
+/// * `pre_auth_token` - 登录时返回的预登录令牌
+/// * `code` - 认证器应用生成的验证码或恢复码
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct TwoFactorVerifyRequest {
+    #[validate(length(min = 1, message = "预登录令牌不能为空"))]
+    pub pre_auth_token: String,
+    #[validate(length(min = 1, max = 64, message = "验证码长度必须在1-64个字符之间"))]
+    pub code: String,
+}
+
+/// 登录时绑定双因素认证的请求结构体
+///
+/// # 字段
+///
+/// * `pre_auth_token` - 登录时返回的预登录令牌
+#[derive(Deserialize, ToSchema)]
+pub struct TwoFactorEnrollRequest {
+    pub pre_auth_token: String,
+}
+
+/// 登录时完成双因素认证绑定的响应
+///
+/// # 字段
+///
+/// * `recovery_codes` - 恢复码, 只返回这一次
+/// * 其余字段同`AuthResponse`
+#[derive(Serialize, ToSchema)]
+pub struct TwoFactorActivationResponse {
+    pub recovery_codes: Vec<String>,
+    #[serde(flatten)]
+    pub tokens: AuthResponse,
+}
+
+/// 刷新令牌请求结构体
+///
+/// # 字段
+///
+/// * `refresh_token` - 登录或上次刷新时返回的刷新令牌
+#[derive(Deserialize, ToSchema)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+/// 退出登录请求结构体
+///
+/// # 字段
+///
+/// * `refresh_token` - 需要一并作废的刷新令牌
+#[derive(Deserialize, ToSchema)]
+pub struct LogoutRequest {
+    pub refresh_token: Option<String>,
+}
+
+/// 发起单点登录的响应
+///
+/// # 字段
+///
+/// * `authorization_url` - 身份提供方的授权地址, 前端跳转到该地址完成登录
+#[derive(Serialize, ToSchema)]
+pub struct OidcAuthorizeResponse {
+    pub authorization_url: String,
+}
+
+/// 单点登录回调请求
 ///
 /// # 字段
 ///
-/// * `user_id` - 用户ID(序列化时重命名为userid以配合antd pro)
-/// * `name` - 用户名称
-/// * `avatar` - 用户头像URL
-#[derive(Serialize, Deserialize)]
-pub struct UserInfo {
-    #[serde(rename = "userid")] // 为了配合antd pro
-    pub user_id: String,
-    pub name: String,
-    pub avatar: String,
+/// * `code` - 身份提供方回调时携带的授权码
+/// * `state` - 身份提供方回调时携带的 state
+#[derive(Deserialize, ToSchema)]
+pub struct OidcCallbackRequest {
+    pub code: String,
+    pub state: String,
 }
@@ -0,0 +1,255 @@
+use axum::extract::{Extension, State};
+
+use crate::{
+    app_state::AppState,
+    core::{
+        errors::Result,
+        extract::ValidatedJson,
+        response::{ApiResponse, Empty},
+        schema::UserID,
+    },
+};
+
+use entities::RouteItem;
+use services::admin::TwoFactorEnrollment;
+
+use super::types::{
+    ChangePasswordRequest, NotificationPreferencesResponse, RecoveryCodesResponse, TwoFactorCodeRequest,
+    UpdateNotificationPreferencesRequest, UpdateProfileRequest, UserInfo,
+};
+
+/// 获取当前登录用户的信息
+#[utoipa::path(
+    get,
+    path = "/me",
+    tag = "me",
+    summary = "获取当前用户信息",
+    responses((status = 200, description = "OK", body = ApiResponse<UserInfo>))
+)]
+pub async fn get_me(State(state): State<AppState>, user_id: Extension<UserID>) -> Result<UserInfo> {
+    let id: String = user_id.0.into();
+    let admin = state.service_factory().admin_service().get_admin(&id).await?;
+
+    ApiResponse::ok_with_data(admin.into())
+}
+
+/// 修改自己的密码
+///
+/// 修改成功后当前用户已登录的会话全部失效, 需要重新登录
+#[utoipa::path(
+    put,
+    path = "/me/password",
+    tag = "me",
+    summary = "修改密码",
+    request_body = ChangePasswordRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn change_password(
+    State(state): State<AppState>,
+    user_id: Extension<UserID>,
+    ValidatedJson(req): ValidatedJson<ChangePasswordRequest>,
+) -> Result<()> {
+    let config = state.config().await?;
+    let id: String = user_id.0.into();
+
+    state
+        .service_factory()
+        .admin_service()
+        .with_password_policy(config.password_policy)
+        .change_password(req.into_params(id.clone()))
+        .await?;
+
+    state
+        .sessions()
+        .revoke_admin(&id, config.auth.refresh_token_ttl)
+        .await?;
+
+    ApiResponse::<()>::ok()
+}
+
+/// 修改自己的个人资料
+#[utoipa::path(
+    put,
+    path = "/me/profile",
+    tag = "me",
+    summary = "更新个人资料",
+    request_body = UpdateProfileRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<UserInfo>))
+)]
+pub async fn update_profile(
+    State(state): State<AppState>,
+    user_id: Extension<UserID>,
+    ValidatedJson(req): ValidatedJson<UpdateProfileRequest>,
+) -> Result<UserInfo> {
+    let admin = state
+        .service_factory()
+        .admin_service()
+        .update_profile(req.into_params(user_id.0.into()))
+        .await?;
+
+    ApiResponse::ok_with_data(admin.into())
+}
+
+/// 获取当前用户角色拥有的权限, 用于前端生成菜单
+#[utoipa::path(
+    get,
+    path = "/me/permissions",
+    tag = "me",
+    summary = "获取当前用户的权限",
+    responses((status = 200, description = "OK", body = ApiResponse<Vec<RouteItem>>))
+)]
+pub async fn get_permissions(
+    State(state): State<AppState>,
+    user_id: Extension<UserID>,
+) -> Result<Vec<RouteItem>> {
+    let id: String = user_id.0.into();
+    let permissions = state
+        .service_factory()
+        .admin_service()
+        .get_permissions(&id)
+        .await?;
+
+    ApiResponse::ok_with_data(permissions)
+}
+
+#[utoipa::path(
+    get,
+    path = "/me/notification-preferences",
+    tag = "me",
+    summary = "获取通知偏好",
+    responses((status = 200, description = "OK", body = ApiResponse<NotificationPreferencesResponse>))
+)]
+pub async fn get_notification_preferences(
+    State(state): State<AppState>,
+    user_id: Extension<UserID>,
+) -> Result<NotificationPreferencesResponse> {
+    let id: String = user_id.0.into();
+    let preferences = state
+        .service_factory()
+        .admin_service()
+        .get_notification_preferences(&id)
+        .await?;
+
+    ApiResponse::ok_with_data(preferences.into())
+}
+
+#[utoipa::path(
+    put,
+    path = "/me/notification-preferences",
+    tag = "me",
+    summary = "更新通知偏好",
+    request_body = UpdateNotificationPreferencesRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn update_notification_preferences(
+    State(state): State<AppState>,
+    user_id: Extension<UserID>,
+    ValidatedJson(req): ValidatedJson<UpdateNotificationPreferencesRequest>,
+) -> Result<()> {
+    let params = req.into_params(user_id.0.into())?;
+    state
+        .service_factory()
+        .admin_service()
+        .update_notification_preferences(params)
+        .await?;
+
+    ApiResponse::<()>::ok()
+}
+
+/// 开始绑定双因素认证
+#[utoipa::path(
+    post,
+    path = "/me/2fa/enroll",
+    operation_id = "me_enroll_two_factor",
+    tag = "me",
+    summary = "开始绑定双因素认证",
+    responses((status = 200, description = "OK", body = ApiResponse<TwoFactorEnrollment>))
+)]
+pub async fn enroll_two_factor(
+    State(state): State<AppState>,
+    user_id: Extension<UserID>,
+) -> Result<TwoFactorEnrollment> {
+    let config = state.config().await?;
+    let id: String = user_id.0.into();
+
+    let enrollment = state
+        .service_factory()
+        .admin_service()
+        .begin_two_factor_enrollment(&id, &config.auth.totp_issuer)
+        .await?;
+
+    ApiResponse::ok_with_data(enrollment)
+}
+
+/// 验证绑定时的验证码并启用双因素认证
+#[utoipa::path(
+    post,
+    path = "/me/2fa/activate",
+    operation_id = "me_activate_two_factor",
+    tag = "me",
+    summary = "完成双因素认证绑定",
+    request_body = TwoFactorCodeRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<RecoveryCodesResponse>))
+)]
+pub async fn activate_two_factor(
+    State(state): State<AppState>,
+    user_id: Extension<UserID>,
+    ValidatedJson(req): ValidatedJson<TwoFactorCodeRequest>,
+) -> Result<RecoveryCodesResponse> {
+    let id: String = user_id.0.into();
+    let recovery_codes = state
+        .service_factory()
+        .admin_service()
+        .activate_two_factor(&id, &req.code)
+        .await?;
+
+    ApiResponse::ok_with_data(RecoveryCodesResponse { recovery_codes })
+}
+
+/// 重新生成恢复码
+#[utoipa::path(
+    post,
+    path = "/me/2fa/recovery-codes",
+    tag = "me",
+    summary = "重新生成恢复码",
+    request_body = TwoFactorCodeRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<RecoveryCodesResponse>))
+)]
+pub async fn regenerate_recovery_codes(
+    State(state): State<AppState>,
+    user_id: Extension<UserID>,
+    ValidatedJson(req): ValidatedJson<TwoFactorCodeRequest>,
+) -> Result<RecoveryCodesResponse> {
+    let id: String = user_id.0.into();
+    let recovery_codes = state
+        .service_factory()
+        .admin_service()
+        .regenerate_recovery_codes(&id, &req.code)
+        .await?;
+
+    ApiResponse::ok_with_data(RecoveryCodesResponse { recovery_codes })
+}
+
+/// 关闭双因素认证
+#[utoipa::path(
+    post,
+    path = "/me/2fa/disable",
+    tag = "me",
+    summary = "关闭双因素认证",
+    request_body = TwoFactorCodeRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn disable_two_factor(
+    State(state): State<AppState>,
+    user_id: Extension<UserID>,
+    ValidatedJson(req): ValidatedJson<TwoFactorCodeRequest>,
+) -> Result<()> {
+    let id: String = user_id.0.into();
+    state
+        .service_factory()
+        .admin_service()
+        .disable_two_factor(&id, &req.code)
+        .await?;
+
+    ApiResponse::<()>::ok()
+}
@@ -0,0 +1,4 @@
+mod me_handlers;
+mod types;
+
+pub use me_handlers::*;
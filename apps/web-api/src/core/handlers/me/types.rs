@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use entities::errors::Error as EntityError;
+use entities::{Admin, MessageCategory, MessageChannel, NotificationPreferences, QuietHours};
+use serde::{Deserialize, Serialize};
+use services::admin::{ChangePasswordParams, UpdateNotificationPreferencesParams, UpdateProfileParams};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct QuietHoursPayload {
+    pub start: String,
+    pub end: String,
+    pub timezone: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateNotificationPreferencesRequest {
+    /// 消息分类 -> 开启的渠道
+    #[serde(default)]
+    pub channels: HashMap<String, Vec<String>>,
+    #[validate(email(message = "邮箱格式不正确"))]
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub quiet_hours: Option<QuietHoursPayload>,
+}
+
+impl UpdateNotificationPreferencesRequest {
+    pub fn into_params(self, id: String) -> Result<UpdateNotificationPreferencesParams, EntityError> {
+        let mut channels = HashMap::new();
+        for (category, names) in self.channels {
+            let names = names
+                .iter()
+                .map(|name| MessageChannel::from_str(name))
+                .collect::<Result<Vec<_>, _>>()?;
+            channels.insert(MessageCategory::from_str(&category)?, names);
+        }
+
+        let quiet_hours = match self.quiet_hours {
+            Some(q) => Some(QuietHours::new(q.start, q.end, q.timezone)?),
+            None => None,
+        };
+
+        Ok(UpdateNotificationPreferencesParams {
+            id,
+            preferences: NotificationPreferences {
+                channels,
+                email: self.email,
+                phone: self.phone,
+                quiet_hours,
+            },
+        })
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotificationPreferencesResponse {
+    pub channels: HashMap<String, Vec<String>>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub quiet_hours: Option<QuietHoursPayload>,
+}
+
+impl From<NotificationPreferences> for NotificationPreferencesResponse {
+    fn from(preferences: NotificationPreferences) -> Self {
+        Self {
+            channels: preferences
+                .channels
+                .into_iter()
+                .map(|(category, channels)| {
+                    (
+                        category.to_string(),
+                        channels.iter().map(|c| c.to_string()).collect(),
+                    )
+                })
+                .collect(),
+            email: preferences.email,
+            phone: preferences.phone,
+            quiet_hours: preferences.quiet_hours.map(|q| QuietHoursPayload {
+                start: q.start,
+                end: q.end,
+                timezone: q.timezone,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct TwoFactorCodeRequest {
+    /// 认证器应用生成的验证码, 重新生成恢复码或关闭时也可以使用恢复码
+    #[validate(length(min = 1, max = 64, message = "验证码长度必须在1-64个字符之间"))]
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+/// 用户信息结构体
+///
+/// 用于向客户端返回当前登录用户的基本信息
+///
+/// # 字段
+///
+/// * `user_id` - 用户ID(序列化时重命名为userid以配合antd pro)
+/// * `account` - 用户账号
+/// * `name` - 用户名称
+/// * `avatar` - 用户头像URL
+/// * `age` - 年龄
+/// * `role_names` - 角色名称列表
+/// * `two_factor_enabled` - 是否已启用双因素认证
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserInfo {
+    #[serde(rename = "userid")] // 为了配合antd pro
+    pub user_id: String,
+    pub account: String,
+    pub name: String,
+    pub avatar: String,
+    pub age: u8,
+    pub role_names: Vec<String>,
+    pub two_factor_enabled: bool,
+}
+
+impl From<Admin> for UserInfo {
+    fn from(admin: Admin) -> Self {
+        Self {
+            user_id: admin.base.id,
+            account: admin.secret.account,
+            name: admin.name,
+            avatar: admin.avatar,
+            age: admin.age,
+            role_names: admin.role_names,
+            two_factor_enabled: admin.two_factor.enabled,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ChangePasswordRequest {
+    #[validate(length(min = 1, message = "旧密码不能为空"))]
+    pub old_password: String,
+    /// 新密码的强度由密码策略校验
+    #[validate(length(min = 1, message = "新密码不能为空"))]
+    pub new_password: String,
+}
+
+impl ChangePasswordRequest {
+    pub fn into_params(self, id: String) -> ChangePasswordParams {
+        ChangePasswordParams {
+            id,
+            old_password: self.old_password,
+            new_password: self.new_password,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateProfileRequest {
+    #[validate(length(min = 1, max = 32, message = "名称长度必须在1-32个字符之间"))]
+    pub name: Option<String>,
+    #[validate(length(max = 512, message = "头像地址不能超过512个字符"))]
+    pub avatar: Option<String>,
+    #[validate(range(min = 1, max = 150, message = "年龄必须在1-150之间"))]
+    pub age: Option<u8>,
+}
+
+impl UpdateProfileRequest {
+    pub fn into_params(self, id: String) -> UpdateProfileParams {
+        UpdateProfileParams {
+            id,
+            name: self.name,
+            avatar: self.avatar,
+            age: self.age,
+        }
+    }
+}
@@ -1,32 +1,56 @@
-use axum::{
-    extract::{Path, Query, State},
-    Json,
-};
+use axum::extract::{Extension, Path, State};
 use log::info;
 
 use crate::{
     app_state::AppState,
-    core::{errors::Result, response::ApiResponse},
+    core::{
+        errors::Result,
+        extract::{ValidatedJson, ValidatedQuery},
+        response::{ApiResponse, Empty},
+        schema::RequestId,
+    },
 };
 
 use super::types::{MessageQueryRequest, MessageResponse, SendMessageRequest};
 
-pub async fn send_message(State(state): State<AppState>, Json(req): Json<SendMessageRequest>) -> Result<()> {
+#[utoipa::path(
+    post,
+    path = "/messages",
+    tag = "message",
+    summary = "发送消息",
+    request_body = SendMessageRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn send_message(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    ValidatedJson(req): ValidatedJson<SendMessageRequest>,
+) -> Result<()> {
     info!("Sending message to {}: {}", req.recipient, req.subject);
 
-    let params = req.into_params()?;
-    state
-        .service_factory()
-        .notify_service()
-        .new_message(params)
-        .await?;
+    let service = state.service_factory().notify_service();
+    if req.is_for_admin() {
+        service
+            .notify_admin(req.into_notify_params(request_id.0)?)
+            .await?;
+    } else {
+        service.new_message(req.into_params(request_id.0)?).await?;
+    }
 
     ApiResponse::<()>::ok()
 }
 
+#[utoipa::path(
+    get,
+    path = "/messages",
+    tag = "message",
+    summary = "获取消息列表",
+    params(MessageQueryRequest),
+    responses((status = 200, description = "OK", body = ApiResponse<Vec<MessageResponse>>))
+)]
 pub async fn get_message_list(
     State(state): State<AppState>,
-    Query(query): Query<MessageQueryRequest>,
+    ValidatedQuery(query): ValidatedQuery<MessageQueryRequest>,
 ) -> Result<Vec<MessageResponse>> {
     let query = query.into_query()?;
     let messages = state
@@ -46,12 +70,22 @@ pub async fn get_message_list(
                 content: m.content,
                 status: format!("{:?}", m.status),
                 error: m.error,
+                response_status: m.response_status,
+                response_body: m.response_body,
                 created_at: m.base.created_at,
             })
             .collect(),
     )
 }
 
+#[utoipa::path(
+    post,
+    path = "/messages/{id}/retry",
+    tag = "message",
+    summary = "重试发送消息",
+    params(("id" = String, Path, description = "消息ID")),
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
 pub async fn retry_message(State(state): State<AppState>, Path(id): Path<String>) -> Result<()> {
     state.service_factory().notify_service().retry_by_id(&id).await?;
 
@@ -1,37 +1,70 @@
 use entities::errors::Error as EntityError;
-use entities::MessageChannel;
+use entities::{MessageCategory, MessageChannel};
 use serde::{Deserialize, Serialize};
-use services::notification::{MessageQuery, SendMessageParams};
+use services::notification::{MessageQuery, NotifyAdminParams, SendMessageParams};
+use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct SendMessageRequest {
+    /// 接收者, 未指定渠道时为管理员ID
     #[validate(length(min = 1))]
     pub recipient: String,
     #[validate(length(min = 1))]
     pub subject: String,
     #[validate(length(min = 1))]
     pub content: String,
-    pub channel: String,
+    /// 发送渠道, 省略时按接收管理员的通知偏好选择渠道
+    #[validate(length(min = 1))]
+    pub channel: Option<String>,
+    pub category: Option<String>,
 }
 
 impl SendMessageRequest {
-    pub fn into_params(self) -> Result<SendMessageParams, EntityError> {
+    /// 是否发送给管理员, 由管理员的通知偏好决定渠道和投递时间
+    pub fn is_for_admin(&self) -> bool {
+        self.channel.is_none()
+    }
+
+    pub fn into_params(self, request_id: String) -> Result<SendMessageParams, EntityError> {
         Ok(SendMessageParams {
-            channel: MessageChannel::from_str(&self.channel)?,
+            channel: MessageChannel::from_str(self.channel.as_deref().unwrap_or_default())?,
+            category: self.category()?,
             recipient: self.recipient,
             subject: self.subject,
             content: self.content,
+            request_id: Some(request_id),
+        })
+    }
+
+    pub fn into_notify_params(self, request_id: String) -> Result<NotifyAdminParams, EntityError> {
+        Ok(NotifyAdminParams {
+            category: self.category()?,
+            admin_id: self.recipient,
+            subject: self.subject,
+            content: self.content,
+            request_id: Some(request_id),
         })
     }
+
+    fn category(&self) -> Result<MessageCategory, EntityError> {
+        match &self.category {
+            Some(c) => MessageCategory::from_str(c),
+            None => Ok(MessageCategory::default()),
+        }
+    }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct MessageQueryRequest {
     pub channel: Option<String>,
     pub recipient: Option<String>,
     pub status: Option<String>,
+    pub broadcast_id: Option<String>,
+    #[validate(range(min = 1, message = "页码必须大于0"))]
     pub page: Option<i64>,
+    #[validate(range(min = 1, max = 100, message = "每页数量必须在1-100之间"))]
     pub page_size: Option<i64>,
 }
 
@@ -44,13 +77,14 @@ impl MessageQueryRequest {
             },
             recipient: self.recipient,
             status: self.status,
+            broadcast_id: self.broadcast_id,
             page: self.page.unwrap_or(1),
             page_size: self.page_size.unwrap_or(20),
         })
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct MessageResponse {
     pub id: String,
     pub channel: String,
@@ -59,5 +93,7 @@ pub struct MessageResponse {
     pub content: String,
     pub status: String,
     pub error: Option<String>,
+    pub response_status: Option<u16>,
+    pub response_body: Option<String>,
     pub created_at: u64,
 }
@@ -1,6 +1,11 @@
 pub mod admin;
+pub mod api_key;
+pub mod broadcast;
 pub mod internal_message;
 pub mod login;
+pub mod me;
 pub mod message;
+pub mod product;
 pub mod role;
 pub mod upload;
+pub mod webhook;
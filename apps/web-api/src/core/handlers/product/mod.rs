@@ -0,0 +1,4 @@
+mod product_handlers;
+mod types;
+
+pub use product_handlers::*;
@@ -0,0 +1,218 @@
+use axum::extract::{Extension, Path, State};
+use services::{
+    errors::{Error as ServiceError, Resource},
+    role::{DataFilter, PRODUCT_RESOURCE},
+};
+
+use crate::{
+    app_state::AppState,
+    core::{
+        errors::{Error, Result},
+        extract::{ValidatedJson, ValidatedQuery},
+        response::{ApiResponse, Empty, ListResponse},
+        schema::{Subject, UserID},
+    },
+};
+
+use super::types::{
+    CreateProductRequest, CreateSkuRequest, ProductQueryRequest, ProductResponse, UpdateProductRequest,
+    UpdateSkuRequest,
+};
+
+/// 根据当前用户的角色计算商品的数据范围
+async fn product_scope(
+    state: &AppState,
+    subject: Subject,
+    user_id: &UserID,
+) -> std::result::Result<DataFilter, Error> {
+    let scope = state
+        .rbac()
+        .data_scope(subject.0, PRODUCT_RESOURCE.to_string())
+        .await?;
+
+    Ok(DataFilter::new(scope, &user_id.0))
+}
+
+#[utoipa::path(
+    post,
+    path = "/products",
+    tag = "product",
+    summary = "创建商品",
+    request_body = CreateProductRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn create_product(
+    State(state): State<AppState>,
+    Extension(subject): Extension<Subject>,
+    Extension(user_id): Extension<UserID>,
+    ValidatedJson(req): ValidatedJson<CreateProductRequest>,
+) -> Result<()> {
+    let filter = product_scope(&state, subject, &user_id).await?;
+    state
+        .service_factory()
+        .product_service()
+        .create_product(req.into_params(user_id.0), &filter)
+        .await?;
+
+    ApiResponse::<()>::ok()
+}
+
+#[utoipa::path(
+    get,
+    path = "/products",
+    tag = "product",
+    summary = "搜索商品",
+    params(ProductQueryRequest),
+    responses((status = 200, description = "OK", body = ApiResponse<ListResponse<ProductResponse>>))
+)]
+pub async fn search_products(
+    State(state): State<AppState>,
+    Extension(subject): Extension<Subject>,
+    Extension(user_id): Extension<UserID>,
+    ValidatedQuery(query): ValidatedQuery<ProductQueryRequest>,
+) -> Result<ListResponse<ProductResponse>> {
+    let filter = product_scope(&state, subject, &user_id).await?;
+    let (total, items) = state
+        .service_factory()
+        .product_service()
+        .search_products(&query.into(), &filter)
+        .await?;
+
+    ApiResponse::ok_with_data(ListResponse {
+        items: items.into_iter().map(ProductResponse::from).collect(),
+        total,
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/products/{id}",
+    tag = "product",
+    summary = "获取商品详情",
+    params(("id" = String, Path, description = "商品ID")),
+    responses((status = 200, description = "OK", body = ApiResponse<ProductResponse>))
+)]
+pub async fn get_product(
+    State(state): State<AppState>,
+    Extension(subject): Extension<Subject>,
+    Extension(user_id): Extension<UserID>,
+    Path(id): Path<String>,
+) -> Result<ProductResponse> {
+    let filter = product_scope(&state, subject, &user_id).await?;
+    let product = state
+        .service_factory()
+        .product_service()
+        .get_product_detail(&id, &filter)
+        .await?
+        .ok_or(ServiceError::NotFound(Resource::Product))?;
+
+    ApiResponse::ok_with_data(product.into())
+}
+
+#[utoipa::path(
+    put,
+    path = "/products/{id}",
+    tag = "product",
+    summary = "更新商品",
+    params(("id" = String, Path, description = "商品ID")),
+    request_body = UpdateProductRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn update_product(
+    State(state): State<AppState>,
+    Extension(subject): Extension<Subject>,
+    Extension(user_id): Extension<UserID>,
+    Path(id): Path<String>,
+    ValidatedJson(req): ValidatedJson<UpdateProductRequest>,
+) -> Result<()> {
+    let filter = product_scope(&state, subject, &user_id).await?;
+    state
+        .service_factory()
+        .product_service()
+        .update_product(req.into_params(id), &filter)
+        .await?;
+
+    ApiResponse::<()>::ok()
+}
+
+#[utoipa::path(
+    post,
+    path = "/products/{id}/skus",
+    tag = "product",
+    summary = "创建 SKU",
+    params(("id" = String, Path, description = "商品ID")),
+    request_body = CreateSkuRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn create_sku(
+    State(state): State<AppState>,
+    Extension(subject): Extension<Subject>,
+    Extension(user_id): Extension<UserID>,
+    Path(id): Path<String>,
+    ValidatedJson(req): ValidatedJson<CreateSkuRequest>,
+) -> Result<()> {
+    let filter = product_scope(&state, subject, &user_id).await?;
+    state
+        .service_factory()
+        .product_service()
+        .create_sku(req.into_params(id), &filter)
+        .await?;
+
+    ApiResponse::<()>::ok()
+}
+
+#[utoipa::path(
+    put,
+    path = "/products/{id}/skus/{sku_id}",
+    tag = "product",
+    summary = "更新 SKU",
+    params(
+        ("id" = String, Path, description = "商品ID"),
+        ("sku_id" = String, Path, description = "SKU ID")
+    ),
+    request_body = UpdateSkuRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn update_sku(
+    State(state): State<AppState>,
+    Extension(subject): Extension<Subject>,
+    Extension(user_id): Extension<UserID>,
+    Path((id, sku_id)): Path<(String, String)>,
+    ValidatedJson(req): ValidatedJson<UpdateSkuRequest>,
+) -> Result<()> {
+    let filter = product_scope(&state, subject, &user_id).await?;
+    state
+        .service_factory()
+        .product_service()
+        .update_sku(req.into_params(id, sku_id), &filter)
+        .await?;
+
+    ApiResponse::<()>::ok()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/products/{id}/skus/{sku_id}",
+    tag = "product",
+    summary = "删除 SKU",
+    params(
+        ("id" = String, Path, description = "商品ID"),
+        ("sku_id" = String, Path, description = "SKU ID")
+    ),
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn delete_sku(
+    State(state): State<AppState>,
+    Extension(subject): Extension<Subject>,
+    Extension(user_id): Extension<UserID>,
+    Path((id, sku_id)): Path<(String, String)>,
+) -> Result<()> {
+    let filter = product_scope(&state, subject, &user_id).await?;
+    state
+        .service_factory()
+        .product_service()
+        .delete_sku(&id, &sku_id, &filter)
+        .await?;
+
+    ApiResponse::<()>::ok()
+}
@@ -1,29 +1,47 @@
+use entities::product::{ProductStatus, SkuSpec, SkuStatus};
 use serde::{Deserialize, Serialize};
-use validator::Validate;
-use entities::product::{
-    Product, SKU, Category, Brand, Supplier,
-    ProductStatus, SkuStatus, CategoryStatus, BrandStatus, SupplierStatus,
+use services::product::{
+    CreateProductParams, CreateSkuParams, ProductDetail, ProductSearchParams, SkuDetail, UpdateProductParams,
+    UpdateSkuParams,
 };
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
 
-// Product DTOs
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateProductRequest {
-    #[validate(length(min = 1, max = 100))]
+    #[validate(length(min = 1, max = 100, message = "商品名称长度必须在1-100个字符之间"))]
     pub name: String,
+    #[serde(default)]
     pub description: String,
     pub brand_id: String,
     pub category_id: String,
     pub supplier_id: String,
     pub main_image: String,
+    #[serde(default)]
     pub images: Vec<String>,
+    #[serde(default)]
     pub is_featured: bool,
-    pub seo_title: Option<String>,
-    pub seo_keywords: Option<String>,
-    pub seo_description: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+impl CreateProductRequest {
+    pub fn into_params(self, created_by: String) -> CreateProductParams {
+        CreateProductParams {
+            name: self.name,
+            description: self.description,
+            brand_id: self.brand_id,
+            category_id: self.category_id,
+            supplier_id: self.supplier_id,
+            main_image: self.main_image,
+            images: self.images,
+            is_featured: self.is_featured,
+            created_by,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateProductRequest {
+    #[validate(length(min = 1, max = 100, message = "商品名称长度必须在1-100个字符之间"))]
     pub name: Option<String>,
     pub description: Option<String>,
     pub brand_id: Option<String>,
@@ -34,12 +52,58 @@ pub struct UpdateProductRequest {
     pub status: Option<ProductStatus>,
     pub sort_order: Option<i32>,
     pub is_featured: Option<bool>,
-    pub seo_title: Option<String>,
-    pub seo_keywords: Option<String>,
-    pub seo_description: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+impl UpdateProductRequest {
+    pub fn into_params(self, id: String) -> UpdateProductParams {
+        UpdateProductParams {
+            id,
+            name: self.name,
+            description: self.description,
+            brand_id: self.brand_id,
+            category_id: self.category_id,
+            supplier_id: self.supplier_id,
+            main_image: self.main_image,
+            images: self.images,
+            status: self.status,
+            sort_order: self.sort_order,
+            is_featured: self.is_featured,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ProductQueryRequest {
+    pub keyword: Option<String>,
+    pub category_id: Option<String>,
+    pub brand_id: Option<String>,
+    pub supplier_id: Option<String>,
+    pub is_featured: Option<bool>,
+    #[validate(range(min = 1, message = "页码必须大于0"))]
+    pub page: Option<u32>,
+    #[validate(range(min = 1, max = 100, message = "每页数量必须在1-100之间"))]
+    pub page_size: Option<u32>,
+}
+
+impl From<ProductQueryRequest> for ProductSearchParams {
+    fn from(req: ProductQueryRequest) -> Self {
+        Self {
+            keyword: req.keyword,
+            category_id: req.category_id,
+            brand_id: req.brand_id,
+            supplier_id: req.supplier_id,
+            status: None,
+            is_featured: req.is_featured,
+            price_range: None,
+            sort_by: None,
+            page: req.page,
+            page_size: req.page_size,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ProductResponse {
     pub id: String,
     pub name: String,
@@ -49,111 +113,121 @@ pub struct ProductResponse {
     pub supplier_id: String,
     pub main_image: String,
     pub images: Vec<String>,
-    pub status: String,
+    pub status: ProductStatus,
     pub sort_order: i32,
     pub is_featured: bool,
-    pub seo_title: Option<String>,
-    pub seo_keywords: Option<String>,
-    pub seo_description: Option<String>,
+    pub created_by: String,
     pub created_at: u64,
     pub skus: Vec<SkuResponse>,
 }
 
-// SKU DTOs
-#[derive(Debug, Deserialize, Validate)]
-pub struct CreateSkuRequest {
-    pub product_id: String,
-    #[validate(length(min = 1))]
-    pub sku_code: String,
-    pub name: String,
-    pub price: f64,
-    pub original_price: Option<f64>,
-    pub stock: i32,
-    pub specs: Vec<SkuSpec>,
-    pub image: Option<String>,
+impl From<ProductDetail> for ProductResponse {
+    fn from(product: ProductDetail) -> Self {
+        Self {
+            id: product.id,
+            name: product.name,
+            description: product.description,
+            brand_id: product.brand_id,
+            category_id: product.category_id,
+            supplier_id: product.supplier_id,
+            main_image: product.main_image,
+            images: product.images,
+            status: product.status,
+            sort_order: product.sort_order,
+            is_featured: product.is_featured,
+            created_by: product.created_by,
+            created_at: product.created_at,
+            skus: product.skus.into_iter().map(SkuResponse::from).collect(),
+        }
+    }
 }
 
-#[derive(Debug, Serialize)]
-pub struct SkuResponse {
-    pub id: String,
-    pub product_id: String,
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateSkuRequest {
+    #[validate(length(min = 1, message = "SKU 编码不能为空"))]
     pub sku_code: String,
     pub name: String,
+    #[validate(range(min = 0.0, message = "价格不能小于0"))]
     pub price: f64,
     pub original_price: Option<f64>,
+    #[validate(range(min = 0, message = "库存不能小于0"))]
     pub stock: i32,
+    #[serde(default)]
     pub specs: Vec<SkuSpec>,
     pub image: Option<String>,
-    pub status: String,
-    pub created_at: u64,
 }
 
-// Category DTOs
-#[derive(Debug, Deserialize, Validate)]
-pub struct CreateCategoryRequest {
-    #[validate(length(min = 1, max = 50))]
-    pub name: String,
-    pub parent_id: Option<String>,
-    pub description: Option<String>,
-    pub image: Option<String>,
+impl CreateSkuRequest {
+    pub fn into_params(self, product_id: String) -> CreateSkuParams {
+        CreateSkuParams {
+            product_id,
+            sku_code: self.sku_code,
+            name: self.name,
+            price: self.price,
+            original_price: self.original_price,
+            stock: self.stock,
+            specs: self.specs,
+            image: self.image,
+        }
+    }
 }
 
-#[derive(Debug, Serialize)]
-pub struct CategoryResponse {
-    pub id: String,
-    pub name: String,
-    pub parent_id: Option<String>,
-    pub level: i32,
-    pub path: String,
-    pub description: Option<String>,
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateSkuRequest {
+    pub name: Option<String>,
+    #[validate(range(min = 0.0, message = "价格不能小于0"))]
+    pub price: Option<f64>,
+    pub original_price: Option<f64>,
+    #[validate(range(min = 0, message = "库存不能小于0"))]
+    pub stock: Option<i32>,
+    pub specs: Option<Vec<SkuSpec>>,
     pub image: Option<String>,
-    pub sort_order: i32,
-    pub status: String,
-    pub created_at: u64,
+    pub status: Option<SkuStatus>,
 }
 
-// Brand DTOs
-#[derive(Debug, Deserialize, Validate)]
-pub struct CreateBrandRequest {
-    #[validate(length(min = 1, max = 50))]
-    pub name: String,
-    pub logo: String,
-    pub description: Option<String>,
-    pub website: Option<String>,
+impl UpdateSkuRequest {
+    pub fn into_params(self, product_id: String, id: String) -> UpdateSkuParams {
+        UpdateSkuParams {
+            product_id,
+            id,
+            name: self.name,
+            price: self.price,
+            original_price: self.original_price,
+            stock: self.stock,
+            specs: self.specs,
+            image: self.image,
+            status: self.status,
+        }
+    }
 }
 
-#[derive(Debug, Serialize)]
-pub struct BrandResponse {
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SkuResponse {
     pub id: String,
+    pub sku_code: String,
     pub name: String,
-    pub logo: String,
-    pub description: Option<String>,
-    pub website: Option<String>,
-    pub sort_order: i32,
-    pub status: String,
+    pub price: f64,
+    pub original_price: Option<f64>,
+    pub stock: i32,
+    pub specs: Vec<SkuSpec>,
+    pub image: Option<String>,
+    pub status: SkuStatus,
     pub created_at: u64,
 }
 
-// Supplier DTOs
-#[derive(Debug, Deserialize, Validate)]
-pub struct CreateSupplierRequest {
-    #[validate(length(min = 1, max = 100))]
-    pub name: String,
-    pub contact_person: String,
-    #[validate(length(min = 1))]
-    pub contact_phone: String,
-    pub contact_email: Option<String>,
-    pub address: Option<String>,
+impl From<SkuDetail> for SkuResponse {
+    fn from(sku: SkuDetail) -> Self {
+        Self {
+            id: sku.id,
+            sku_code: sku.sku_code,
+            name: sku.name,
+            price: sku.price,
+            original_price: sku.original_price,
+            stock: sku.stock,
+            specs: sku.specs,
+            image: sku.image,
+            status: sku.status,
+            created_at: sku.created_at,
+        }
+    }
 }
-
-#[derive(Debug, Serialize)]
-pub struct SupplierResponse {
-    pub id: String,
-    pub name: String,
-    pub contact_person: String,
-    pub contact_phone: String,
-    pub contact_email: Option<String>,
-    pub address: Option<String>,
-    pub status: String,
-    pub created_at: u64,
-}
\ No newline at end of file
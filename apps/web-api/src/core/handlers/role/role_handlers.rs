@@ -1,28 +1,52 @@
-use axum::{
-    extract::{Path, State},
-    Json,
-};
+use std::sync::Arc;
+
+use axum::extract::{Extension, Path, State};
+use entities::RouteItem;
+use services::role::PermissionCatalog;
 
 use crate::{
     app_state::AppState,
-    core::{errors::Result, response::ApiResponse},
+    core::{
+        errors::Result,
+        extract::ValidatedJson,
+        response::{ApiResponse, Empty},
+    },
 };
 
 use super::types::{CreateRoleRequest, RoleItem, UpdateRoleRequest};
 
-pub async fn create_role(State(state): State<AppState>, Json(req): Json<CreateRoleRequest>) -> Result<()> {
-    state
+#[utoipa::path(
+    post,
+    path = "/roles",
+    tag = "role",
+    summary = "创建角色",
+    request_body = CreateRoleRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn create_role(
+    State(state): State<AppState>,
+    Extension(catalog): Extension<Arc<PermissionCatalog>>,
+    ValidatedJson(req): ValidatedJson<CreateRoleRequest>,
+) -> Result<()> {
+    let role = state
         .service_factory()
         .role_service()
+        .with_permission_catalog(catalog)
         .create_role(req.into())
         .await?;
 
-    // 重新加载RBAC策略
-    state.rbac().reset().await?;
+    state.rbac().upsert_role(role).await?;
 
     ApiResponse::<()>::ok()
 }
 
+#[utoipa::path(
+    get,
+    path = "/roles",
+    tag = "role",
+    summary = "获取角色列表",
+    responses((status = 200, description = "OK", body = ApiResponse<Vec<RoleItem>>))
+)]
 pub async fn get_role_list(State(state): State<AppState>) -> Result<Vec<RoleItem>> {
     let roles = state.service_factory().role_service().get_role_list().await?;
 
@@ -31,28 +55,68 @@ pub async fn get_role_list(State(state): State<AppState>) -> Result<Vec<RoleItem
     ApiResponse::ok_with_data(items)
 }
 
+#[utoipa::path(
+    put,
+    path = "/roles/{id}",
+    tag = "role",
+    summary = "更新角色",
+    params(("id" = String, Path, description = "角色ID")),
+    request_body = UpdateRoleRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
 pub async fn update_role(
     State(state): State<AppState>,
+    Extension(catalog): Extension<Arc<PermissionCatalog>>,
     Path(id): Path<String>,
-    Json(req): Json<UpdateRoleRequest>,
+    ValidatedJson(req): ValidatedJson<UpdateRoleRequest>,
 ) -> Result<()> {
-    state
+    let role_service = state
         .service_factory()
         .role_service()
-        .update_role(req.to_params(id))
-        .await?;
+        .with_permission_catalog(catalog);
 
-    // 重新加载RBAC策略
-    state.rbac().reset().await?;
+    // 改名时需要移除旧名称的策略
+    let previous = match req.name {
+        Some(_) => role_service.get_role_by_id(id.clone()).await?,
+        None => None,
+    };
+
+    let role = role_service.update_role(req.into_params(id)).await?;
+
+    if let Some(previous) = previous.filter(|previous| previous.name != role.name) {
+        state.rbac().remove_role(previous.name).await?;
+    }
+    state.rbac().upsert_role(role).await?;
 
     ApiResponse::<()>::ok()
 }
 
+#[utoipa::path(
+    delete,
+    path = "/roles/{id}",
+    tag = "role",
+    summary = "删除角色",
+    params(("id" = String, Path, description = "角色ID")),
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
 pub async fn delete_role(State(state): State<AppState>, Path(id): Path<String>) -> Result<()> {
-    state.service_factory().role_service().delete_role(id).await?;
+    let role = state.service_factory().role_service().delete_role(id).await?;
 
-    // 重新加载RBAC策略
-    state.rbac().reset().await?;
+    state.rbac().remove_role(role.name).await?;
 
     ApiResponse::<()>::ok()
 }
+
+/// 获取权限目录, 即可以授予角色的全部接口
+#[utoipa::path(
+    get,
+    path = "/permissions",
+    tag = "role",
+    summary = "获取可分配的权限列表",
+    responses((status = 200, description = "OK", body = ApiResponse<Vec<RouteItem>>))
+)]
+pub async fn get_permission_catalog(
+    Extension(catalog): Extension<Arc<PermissionCatalog>>,
+) -> Result<Vec<RouteItem>> {
+    ApiResponse::ok_with_data(catalog.items().to_vec())
+}
@@ -1,13 +1,20 @@
-use entities::{Role, RouteItem};
+use entities::{DataScope, Role, RouteItem};
 use serde::{Deserialize, Serialize};
 use services::role::{CreateRoleParams, UpdateRoleParams};
+use utoipa::ToSchema;
 use validator::Validate;
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateRoleRequest {
     #[validate(length(min = 2, max = 32, message = "角色名称长度必须在2-32个字符之间"))]
     pub name: String,
     pub permissions: Vec<RouteItem>,
+    #[serde(default)]
+    pub parents: Vec<String>,
+    #[serde(default)]
+    pub data_scopes: Vec<DataScope>,
+    #[serde(default)]
+    pub require_two_factor: bool,
 }
 
 impl From<CreateRoleRequest> for CreateRoleParams {
@@ -15,31 +22,44 @@ impl From<CreateRoleRequest> for CreateRoleParams {
         Self {
             name: req.name,
             permissions: req.permissions,
+            parents: req.parents,
+            data_scopes: req.data_scopes,
+            require_two_factor: req.require_two_factor,
         }
     }
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateRoleRequest {
+    #[validate(length(min = 2, max = 32, message = "角色名称长度必须在2-32个字符之间"))]
     pub name: Option<String>,
     pub permissions: Option<Vec<RouteItem>>,
+    pub parents: Option<Vec<String>>,
+    pub data_scopes: Option<Vec<DataScope>>,
+    pub require_two_factor: Option<bool>,
 }
 
 impl UpdateRoleRequest {
-    pub fn to_params(self, id: String) -> UpdateRoleParams {
+    pub fn into_params(self, id: String) -> UpdateRoleParams {
         UpdateRoleParams {
             id,
             name: self.name,
             permissions: self.permissions,
+            parents: self.parents,
+            data_scopes: self.data_scopes,
+            require_two_factor: self.require_two_factor,
         }
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct RoleItem {
     pub id: String,
     pub name: String,
     pub permissions: Vec<RouteItem>,
+    pub parents: Vec<String>,
+    pub data_scopes: Vec<DataScope>,
+    pub require_two_factor: bool,
     pub created_at: u64,
 }
 
@@ -49,6 +69,9 @@ impl From<Role> for RoleItem {
             id: role.base.id,
             name: role.name,
             permissions: role.permissions,
+            parents: role.parents,
+            data_scopes: role.data_scopes,
+            require_two_factor: role.require_two_factor,
             created_at: role.base.created_at,
         }
     }
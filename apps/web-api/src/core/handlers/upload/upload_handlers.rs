@@ -21,6 +21,14 @@ use super::types::UploadResponse;
 /// # 返回
 ///
 /// 返回上传后的文件URL
+#[utoipa::path(
+    post,
+    path = "/upload",
+    tag = "upload",
+    summary = "上传文件",
+    request_body(content = Vec<u8>, content_type = "multipart/form-data", description = "multipart 表单, 读取第一个文件字段"),
+    responses((status = 200, description = "OK", body = ApiResponse<UploadResponse>))
+)]
 pub async fn upload_file(State(state): State<AppState>, mut multipart: Multipart) -> Result<UploadResponse> {
     let config = state.config().await?;
     let storage = LocalStorage::new(&config.get_upload_path())
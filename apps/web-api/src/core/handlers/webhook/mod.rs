@@ -0,0 +1,4 @@
+mod types;
+mod webhook_handlers;
+
+pub use webhook_handlers::*;
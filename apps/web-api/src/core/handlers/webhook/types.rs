@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use services::webhook::{CreateWebhookParams, UpdateWebhookParams, WebhookDetail, WebhookSecret};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateWebhookRequest {
+    #[validate(length(min = 1, max = 64, message = "名称长度必须在1-64个字符之间"))]
+    pub name: String,
+    #[validate(url(message = "端点地址格式不正确"))]
+    pub url: String,
+}
+
+impl CreateWebhookRequest {
+    pub fn into_params(self, created_by: String) -> CreateWebhookParams {
+        CreateWebhookParams {
+            name: self.name,
+            url: self.url,
+            created_by,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateWebhookRequest {
+    #[validate(length(min = 1, max = 64, message = "名称长度必须在1-64个字符之间"))]
+    pub name: Option<String>,
+    #[validate(url(message = "端点地址格式不正确"))]
+    pub url: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+impl UpdateWebhookRequest {
+    pub fn into_params(self, id: String) -> UpdateWebhookParams {
+        UpdateWebhookParams {
+            id,
+            name: self.name,
+            url: self.url,
+            is_active: self.is_active,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookResponse {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub is_active: bool,
+    pub created_by: String,
+    pub created_at: u64,
+}
+
+impl From<WebhookDetail> for WebhookResponse {
+    fn from(detail: WebhookDetail) -> Self {
+        Self {
+            id: detail.id,
+            name: detail.name,
+            url: detail.url,
+            is_active: detail.is_active,
+            created_by: detail.created_by,
+            created_at: detail.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookSecretResponse {
+    pub id: String,
+    pub secret: String,
+}
+
+impl From<WebhookSecret> for WebhookSecretResponse {
+    fn from(secret: WebhookSecret) -> Self {
+        Self {
+            id: secret.id,
+            secret: secret.secret,
+        }
+    }
+}
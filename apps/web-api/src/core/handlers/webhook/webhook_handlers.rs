@@ -0,0 +1,114 @@
+use axum::extract::{Extension, Path, State};
+
+use crate::{
+    app_state::AppState,
+    core::{
+        errors::Result,
+        extract::ValidatedJson,
+        response::{ApiResponse, Empty},
+        schema::UserID,
+    },
+};
+
+use super::types::{CreateWebhookRequest, UpdateWebhookRequest, WebhookResponse, WebhookSecretResponse};
+
+#[utoipa::path(
+    post,
+    path = "/webhooks",
+    tag = "webhook",
+    summary = "创建 Webhook",
+    request_body = CreateWebhookRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<WebhookSecretResponse>))
+)]
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    user_id: Extension<UserID>,
+    ValidatedJson(req): ValidatedJson<CreateWebhookRequest>,
+) -> Result<WebhookSecretResponse> {
+    let secret = state
+        .service_factory()
+        .webhook_service()
+        .create_webhook(req.into_params(user_id.0.into()))
+        .await?;
+
+    ApiResponse::ok_with_data(secret.into())
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks",
+    tag = "webhook",
+    summary = "获取 Webhook 列表",
+    responses((status = 200, description = "OK", body = ApiResponse<Vec<WebhookResponse>>))
+)]
+pub async fn get_webhook_list(State(state): State<AppState>) -> Result<Vec<WebhookResponse>> {
+    let webhooks = state
+        .service_factory()
+        .webhook_service()
+        .get_webhook_list()
+        .await?;
+
+    ApiResponse::ok_with_data(webhooks.into_iter().map(WebhookResponse::from).collect())
+}
+
+#[utoipa::path(
+    put,
+    path = "/webhooks/{id}",
+    tag = "webhook",
+    summary = "更新 Webhook",
+    params(("id" = String, Path, description = "端点ID")),
+    request_body = UpdateWebhookRequest,
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn update_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    ValidatedJson(req): ValidatedJson<UpdateWebhookRequest>,
+) -> Result<()> {
+    state
+        .service_factory()
+        .webhook_service()
+        .update_webhook(req.into_params(id))
+        .await?;
+
+    ApiResponse::<()>::ok()
+}
+
+#[utoipa::path(
+    post,
+    path = "/webhooks/{id}/rotate-secret",
+    tag = "webhook",
+    summary = "重新生成 Webhook 签名密钥",
+    params(("id" = String, Path, description = "端点ID")),
+    responses((status = 200, description = "OK", body = ApiResponse<WebhookSecretResponse>))
+)]
+pub async fn rotate_webhook_secret(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<WebhookSecretResponse> {
+    let secret = state
+        .service_factory()
+        .webhook_service()
+        .rotate_secret(id)
+        .await?;
+
+    ApiResponse::ok_with_data(secret.into())
+}
+
+#[utoipa::path(
+    delete,
+    path = "/webhooks/{id}",
+    tag = "webhook",
+    summary = "删除 Webhook",
+    params(("id" = String, Path, description = "端点ID")),
+    responses((status = 200, description = "OK", body = ApiResponse<Empty>))
+)]
+pub async fn delete_webhook(State(state): State<AppState>, Path(id): Path<String>) -> Result<()> {
+    state
+        .service_factory()
+        .webhook_service()
+        .delete_webhook(id)
+        .await?;
+
+    ApiResponse::<()>::ok()
+}
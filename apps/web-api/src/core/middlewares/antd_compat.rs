@@ -0,0 +1,30 @@
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::{app_state::AppState, core::response::ApiEnvelope};
+
+/// antd-pro 兼容中间件
+///
+/// # 功能
+/// - 配置 `app.antd_compat` 启用时, 把 `ApiResponse` 格式响应的 HTTP 状态码改为 200
+/// - 错误仍然通过响应体中的 `status`、`errorCode` 和 `success` 字段表示
+///
+/// # 参数
+/// - state: 应用状态
+/// - request: HTTP请求
+/// - next: 下一个处理器
+pub async fn antd_compat(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+
+    if response.extensions().get::<ApiEnvelope>().is_some()
+        && state.config().await.is_ok_and(|config| config.app.antd_compat)
+    {
+        *response.status_mut() = StatusCode::OK;
+    }
+
+    response
+}
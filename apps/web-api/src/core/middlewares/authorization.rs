@@ -4,19 +4,26 @@ use axum::{
     response::{IntoResponse, Response},
 };
 
+use database::repositories::AdminRepository;
+use entities::Admin;
+use services::admin::IAdminRepository;
+
 use crate::{app_state::AppState, jwt::Engine};
 
 use super::super::{
     response::ApiResponse,
-    schema::{Account, UserID},
+    schema::{AccessToken, Account, Principal, Subject, UserID},
 };
 
 /// 授权中间件
 ///
 /// # 功能
-/// - 验证请求头中的JWT令牌
+/// - 验证请求头中的JWT令牌或API密钥(`Authorization: ApiKey <key_id>.<secret>`)
+/// - 拒绝已禁用、已删除或已过期的API密钥
+/// - 拒绝已过期或已吊销的令牌(退出登录、修改密码等)
+/// - 拒绝已禁用、已删除的管理员以及早于`tokens_valid_after`签发的令牌
 /// - 从令牌中提取用户ID和账号信息
-/// - 将用户信息注入到请求扩展中
+/// - 将用户信息、认证主体和 RBAC 主体注入到请求扩展中
 ///
 /// # 参数
 /// - state: 应用状态
@@ -44,22 +51,115 @@ pub async fn authorization(State(state): State<AppState>, mut request: Request,
 
     let token = match request.headers().get("Authorization") {
         Some(token) => {
-            if let Err(_) = token.to_str() {
+            if token.to_str().is_err() {
                 return ApiResponse::<()>::unauthorized().into_response();
             }
 
-            let token = token.to_str().unwrap(); // the unwrap is safe
-            token.trim_start_matches("Bearer ")
+            token.to_str().unwrap().to_string() // the unwrap is safe
         }
         None => return ApiResponse::<()>::unauthorized().into_response(),
     };
 
-    match jwt_engine.verify_token(token) {
-        Ok(payload) => {
-            request.extensions_mut().insert(UserID(payload.id));
-            request.extensions_mut().insert(Account(payload.account));
-            next.run(request).await
-        }
+    if let Some(api_key) = token.strip_prefix("ApiKey ") {
+        let api_key = match state
+            .service_factory()
+            .api_key_service()
+            .authenticate(api_key)
+            .await
+        {
+            Ok(Some(api_key)) => api_key,
+            Ok(None) => return ApiResponse::<()>::unauthorized().into_response(),
+            Err(err) => {
+                return ApiResponse::<()>::system_error(format!("Failed to check api key: {}", err))
+                    .into_response()
+            }
+        };
+
+        request.extensions_mut().insert(Principal::ApiKey);
+        request.extensions_mut().insert(Subject(api_key.subject()));
+        request.extensions_mut().insert(Account(api_key.subject()));
+        request.extensions_mut().insert(UserID(api_key.base.id));
+        return next.run(request).await;
+    }
+
+    let token = token.trim_start_matches("Bearer ");
+
+    let payload = match jwt_engine.verify_token(token) {
+        Ok(payload) => payload,
         Err(_) => return ApiResponse::<()>::unauthorized().into_response(),
+    };
+
+    match state.sessions().is_token_revoked(&payload).await {
+        Ok(false) => {}
+        Ok(true) => return ApiResponse::<()>::unauthorized().into_response(),
+        Err(err) => {
+            return ApiResponse::<()>::system_error(format!("Failed to check token: {}", err)).into_response()
+        }
+    }
+
+    // 每次请求读取管理员状态, 禁用账号在下一次请求时生效
+    let admin_repo = AdminRepository::new(state.db().clone());
+    match admin_repo.find_by_id(&payload.id).await {
+        Ok(Some(user)) if user.accepts_token(payload.issued_at) => {}
+        Ok(_) => return ApiResponse::<()>::unauthorized().into_response(),
+        Err(err) => {
+            return ApiResponse::<()>::system_error(format!("Failed to load admin: {}", err)).into_response()
+        }
+    }
+
+    request.extensions_mut().insert(Principal::Admin);
+    request
+        .extensions_mut()
+        .insert(Subject(Admin::subject_of(&payload.account)));
+    request.extensions_mut().insert(AccessToken {
+        jti: payload.jti,
+        expires_at: payload.expires_at,
+    });
+    request.extensions_mut().insert(UserID(payload.id));
+    request.extensions_mut().insert(Account(payload.account));
+    next.run(request).await
+}
+
+/// 管理员专用中间件
+///
+/// # 功能
+/// - 只允许使用访问令牌登录的管理员访问, 例如个人信息、退出登录和站内消息
+/// - API密钥只能访问受RBAC保护的接口, 访问其他接口时返回权限拒绝
+///
+/// # 参数
+/// - request: HTTP请求
+/// - next: 下一个处理器
+pub async fn admin_only(request: Request, next: Next) -> Response {
+    match request.extensions().get::<Principal>() {
+        Some(Principal::Admin) => next.run(request).await,
+        Some(Principal::ApiKey) => ApiResponse::<()>::permission_denied().into_response(),
+        None => ApiResponse::<()>::unauthorized().into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    async fn status(principal: Option<Principal>) -> StatusCode {
+        let app = Router::new()
+            .route("/me", get(|| async { "ok" }))
+            .route_layer(middleware::from_fn(admin_only));
+
+        let mut request = Request::get("/me").body(Body::empty()).unwrap();
+        if let Some(principal) = principal {
+            request.extensions_mut().insert(principal);
+        }
+
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_admin_only_rejects_api_keys() {
+        assert_eq!(status(Some(Principal::Admin)).await, StatusCode::OK);
+        assert_eq!(status(Some(Principal::ApiKey)).await, StatusCode::FORBIDDEN);
+        assert_eq!(status(None).await, StatusCode::UNAUTHORIZED);
     }
 }
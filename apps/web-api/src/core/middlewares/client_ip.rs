@@ -0,0 +1,193 @@
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header::FORWARDED, HeaderMap, HeaderName},
+    middleware::Next,
+    response::Response,
+};
+use ipnet::IpNet;
+use log::warn;
+
+use crate::{app_state::AppState, core::schema::ClientIp};
+
+static X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+
+/// 客户端IP中间件
+///
+/// # 功能
+/// - 直接连接的地址不是受信任的代理时, 以该地址作为客户端IP
+/// - 来自受信任代理的请求按 `X-Forwarded-For` (没有时按 `Forwarded`) 从右向左查找,
+///   第一个不是受信任代理的地址为客户端IP
+/// - 将客户端IP注入到请求扩展中, 登录防护和操作日志都使用该地址
+///
+/// # 参数
+/// - state: 应用状态, 读取 `app.trusted_proxies` 配置
+/// - request: HTTP请求
+/// - next: 下一个处理器
+pub async fn client_ip(State(state): State<AppState>, mut request: Request, next: Next) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+
+    let trusted_proxies = match state.config().await {
+        Ok(config) => parse_trusted_proxies(&config.app.trusted_proxies),
+        Err(_) => vec![],
+    };
+
+    let ip = peer
+        .map(|peer| resolve(peer, request.headers(), &trusted_proxies).to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    request.extensions_mut().insert(ClientIp(ip));
+
+    next.run(request).await
+}
+
+fn parse_trusted_proxies(proxies: &[String]) -> Vec<IpNet> {
+    proxies
+        .iter()
+        .filter_map(|proxy| {
+            let net = proxy
+                .parse::<IpNet>()
+                .or_else(|_| proxy.parse::<IpAddr>().map(IpNet::from));
+            if net.is_err() {
+                warn!("Ignored invalid trusted proxy: {}", proxy);
+            }
+            net.ok()
+        })
+        .collect()
+}
+
+/// 按代理链解析客户端IP
+fn resolve(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[IpNet]) -> IpAddr {
+    let is_trusted = |ip: &IpAddr| trusted_proxies.iter().any(|net| net.contains(ip));
+    if !is_trusted(&peer) {
+        return peer;
+    }
+
+    let mut chain = forwarded_for(headers);
+    if chain.is_empty() {
+        chain = forwarded(headers);
+    }
+
+    // 代理依次把上一跳的地址追加到末尾, 最右侧的不受信任地址是可信链路之外的第一跳
+    let mut client = peer;
+    for ip in chain.into_iter().rev() {
+        client = ip;
+        if !is_trusted(&ip) {
+            break;
+        }
+    }
+
+    client
+}
+
+/// 解析 `X-Forwarded-For`, 多个请求头按出现顺序拼接
+fn forwarded_for(headers: &HeaderMap) -> Vec<IpAddr> {
+    headers
+        .get_all(&X_FORWARDED_FOR)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(parse_node)
+        .collect()
+}
+
+/// 解析 `Forwarded` (RFC 7239) 中每个节点的 `for` 参数
+fn forwarded(headers: &HeaderMap) -> Vec<IpAddr> {
+    headers
+        .get_all(FORWARDED)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (key, value) = pair.split_once('=')?;
+                key.trim()
+                    .eq_ignore_ascii_case("for")
+                    .then(|| parse_node(value))?
+            })
+        })
+        .collect()
+}
+
+/// 解析单个节点地址, 支持带引号、方括号和端口的写法
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    if let Ok(ip) = node.parse::<IpAddr>() {
+        return Some(ip);
+    }
+    if let Ok(addr) = node.parse::<SocketAddr>() {
+        return Some(addr.ip());
+    }
+
+    node.strip_prefix('[')
+        .and_then(|node| node.strip_suffix(']'))
+        .and_then(|node| node.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    fn header_map(name: &HeaderName, values: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append(name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_untrusted_peer_ignores_forwarded_headers() {
+        let trusted = parse_trusted_proxies(&["10.0.0.0/8".to_string()]);
+        let headers = header_map(&X_FORWARDED_FOR, &["1.2.3.4"]);
+
+        assert_eq!(resolve(ip("8.8.8.8"), &headers, &trusted), ip("8.8.8.8"));
+        assert_eq!(resolve(ip("10.0.0.1"), &headers, &[]), ip("10.0.0.1"));
+    }
+
+    #[test]
+    fn test_x_forwarded_for() {
+        let trusted = parse_trusted_proxies(&["10.0.0.0/8".to_string(), "192.168.1.1".to_string()]);
+
+        // 客户端伪造的最左侧地址被忽略
+        let headers = header_map(&X_FORWARDED_FOR, &["6.6.6.6, 1.2.3.4", "192.168.1.1"]);
+        assert_eq!(resolve(ip("10.0.0.1"), &headers, &trusted), ip("1.2.3.4"));
+
+        // 整条链路都是受信任代理时取最左侧的地址
+        let headers = header_map(&X_FORWARDED_FOR, &["10.0.0.2"]);
+        assert_eq!(resolve(ip("10.0.0.1"), &headers, &trusted), ip("10.0.0.2"));
+
+        assert_eq!(
+            resolve(ip("10.0.0.1"), &HeaderMap::new(), &trusted),
+            ip("10.0.0.1")
+        );
+    }
+
+    #[test]
+    fn test_forwarded() {
+        let trusted = parse_trusted_proxies(&["10.0.0.1".to_string()]);
+        let headers = header_map(
+            &FORWARDED,
+            &["for=192.0.2.60;proto=http, For=\"[2001:db8:cafe::17]:4711\""],
+        );
+
+        assert_eq!(
+            resolve(ip("10.0.0.1"), &headers, &trusted),
+            ip("2001:db8:cafe::17")
+        );
+    }
+
+    #[test]
+    fn test_invalid_trusted_proxy_is_ignored() {
+        let trusted = parse_trusted_proxies(&["proxy".to_string(), "::1".to_string()]);
+        assert_eq!(trusted, vec!["::1/128".parse::<IpNet>().unwrap()]);
+    }
+}
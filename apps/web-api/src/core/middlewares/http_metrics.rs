@@ -0,0 +1,79 @@
+use std::time::Instant;
+
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use metrics::{counter, histogram};
+
+/// 没有匹配到路由的请求使用的路由标签
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// HTTP 指标中间件
+///
+/// # 功能
+/// - 按方法、路由和状态码统计请求数
+/// - 按方法和路由记录请求耗时
+///
+/// 路由标签使用注册时的路由模板(例如 `/admins/:id`), 避免每个ID产生一组指标
+///
+/// # 参数
+/// - request: HTTP请求
+/// - next: 下一个处理器
+pub async fn http_metrics(request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+
+    counter!(
+        "http_requests_total",
+        "method" => method.clone(),
+        "route" => route.clone(),
+        "status" => response.status().as_u16().to_string()
+    )
+    .increment(1);
+    histogram!("http_request_duration_seconds", "method" => method, "route" => route)
+        .record(started.elapsed().as_secs_f64());
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_http_metrics() {
+        let recorder = libs::metrics::builder().unwrap().build_recorder();
+        let handle = recorder.handle();
+
+        let app = Router::new()
+            .route("/admins/:id", get(|| async { "ok" }))
+            .layer(middleware::from_fn(http_metrics));
+
+        // 记录器只在当前线程生效, 使用单线程运行时处理请求
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        metrics::with_local_recorder(&recorder, || {
+            runtime.block_on(async {
+                for uri in ["/admins/1", "/admins/2", "/missing"] {
+                    let request = Request::get(uri).body(Body::empty()).unwrap();
+                    app.clone().oneshot(request).await.unwrap();
+                }
+            })
+        });
+
+        let output = handle.render();
+        assert!(output.contains(r#"http_requests_total{method="GET",route="/admins/:id",status="200"} 2"#));
+        assert!(output.contains(r#"http_requests_total{method="GET",route="unmatched",status="404"} 1"#));
+        assert!(output.contains(r#"http_request_duration_seconds_bucket{method="GET",route="/admins/:id""#));
+    }
+}
@@ -1,7 +1,15 @@
+mod antd_compat;
 mod authorization;
+mod client_ip;
+mod http_metrics;
 mod operation_log;
 mod rbac;
+mod request_id;
 
+pub use antd_compat::*;
 pub use authorization::*;
+pub use client_ip::*;
+pub use http_metrics::*;
 pub use operation_log::*;
 pub use rbac::*;
+pub use request_id::*;
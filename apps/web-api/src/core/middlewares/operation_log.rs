@@ -2,7 +2,7 @@
 //!
 //! 此模块提供了一个操作日志中间件,用于记录系统中的所有操作行为。
 //! 主要功能包括:
-//! - 记录请求路径、方法、操作者、请求体和IP地址
+//! - 记录请求路径、方法、操作者、请求体、IP地址和请求ID
 //! - 异步处理日志记录,不影响主请求流程
 //! - 根据请求路径和方法自动提取操作模块、动作和目标ID
 
@@ -14,9 +14,12 @@ use axum::{
 };
 use database::repositories::OperationLogRepository;
 use log::{error, info};
-use std::net::SocketAddr;
+use tracing::Instrument;
 
-use crate::{app_state::AppState, core::schema::Account};
+use crate::{
+    app_state::AppState,
+    core::schema::{Account, ClientIp, RequestId},
+};
 use services::operation_log::{CreateLogParams, OperationLogService};
 
 /// 操作日志中间件入口函数
@@ -40,6 +43,7 @@ struct RequestInfo {
     operator: String,
     body: Option<String>,
     ip: String,
+    request_id: String,
 }
 
 /// 提取请求信息
@@ -55,9 +59,14 @@ async fn extract_request_info(request: Request<Body>) -> (RequestInfo, Request<B
     let operator = extract_operator(&request);
     let ip = request
         .extensions()
-        .get::<SocketAddr>()
-        .map(|addr| addr.ip().to_string())
+        .get::<ClientIp>()
+        .map(|ip| ip.0.clone())
         .unwrap_or_else(|| "unknown".to_string());
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|request_id| request_id.0.clone())
+        .unwrap_or_default();
 
     let (parts, body) = request.into_parts();
     let bytes = to_bytes(body, usize::MAX).await.unwrap_or_default();
@@ -72,6 +81,7 @@ async fn extract_request_info(request: Request<Body>) -> (RequestInfo, Request<B
             operator,
             body: body_str,
             ip,
+            request_id,
         },
         next_request,
     )
@@ -92,7 +102,7 @@ fn extract_operator(request: &Request<Body>) -> String {
         .unwrap_or_else(|| "anonymous".to_string())
 }
 
-/// 异步处理日志记录任务
+/// 异步处理日志记录任务, 任务沿用当前请求的 span
 ///
 /// # 参数
 /// * `state` - 应用状态
@@ -102,13 +112,16 @@ fn spawn_log_task(state: AppState, request_info: RequestInfo) {
     let path = request_info.path.clone();
     let operator = request_info.operator.clone();
 
-    tokio::spawn(async move {
-        if let Err(e) = create_operation_log(state, request_info).await {
-            error!("Failed to create operation log: {}", e);
-        } else {
-            info!("Operation logged: {} {} by {}", method, path, operator);
+    tokio::spawn(
+        async move {
+            if let Err(e) = create_operation_log(state, request_info).await {
+                error!("Failed to create operation log: {}", e);
+            } else {
+                info!("Operation logged: {} {} by {}", method, path, operator);
+            }
         }
-    });
+        .in_current_span(),
+    );
 }
 
 /// 创建操作日志
@@ -133,6 +146,7 @@ async fn create_operation_log(state: AppState, request_info: RequestInfo) -> Res
         request_method: request_info.method,
         request_body: request_info.body,
         ip_address: request_info.ip,
+        request_id: request_info.request_id,
     };
 
     service.create_log(params).await.map_err(|e| e.to_string())
@@ -151,9 +165,12 @@ fn extract_operation_info(path: &str, method: &str) -> (String, String, String)
 
     match parts.as_slice() {
         ["admins", id] => extract_admin_info(method, id),
+        ["admins", id, action] => extract_admin_action_info(action, id),
         ["admins"] => extract_admins_info(method),
         ["roles", id] => extract_role_info(method, id),
         ["roles"] => extract_roles_info(method),
+        ["api-keys", id] => extract_api_key_info(method, id),
+        ["api-keys"] => extract_api_keys_info(method),
         _ => ("其他".to_string(), method.to_string(), "".to_string()),
     }
 }
@@ -172,6 +189,22 @@ fn extract_admin_info(method: &str, id: &str) -> (String, String, String) {
     )
 }
 
+/// 提取管理员子操作信息
+fn extract_admin_action_info(action: &str, id: &str) -> (String, String, String) {
+    (
+        "管理员".to_string(),
+        match action {
+            "role" => "修改角色",
+            "enable" => "启用",
+            "disable" => "禁用",
+            "unlock" => "解锁",
+            _ => action,
+        }
+        .to_string(),
+        id.to_string(),
+    )
+}
+
 /// 提取管理员列表相关操作信息
 fn extract_admins_info(method: &str) -> (String, String, String) {
     (
@@ -213,3 +246,31 @@ fn extract_roles_info(method: &str) -> (String, String, String) {
         "".to_string(),
     )
 }
+
+/// 提取 API 密钥相关操作信息
+fn extract_api_key_info(method: &str, id: &str) -> (String, String, String) {
+    (
+        "API密钥".to_string(),
+        match method {
+            "PUT" => "更新",
+            "DELETE" => "删除",
+            _ => method,
+        }
+        .to_string(),
+        id.to_string(),
+    )
+}
+
+/// 提取 API 密钥列表相关操作信息
+fn extract_api_keys_info(method: &str) -> (String, String, String) {
+    (
+        "API密钥".to_string(),
+        match method {
+            "POST" => "创建",
+            "GET" => "查询",
+            _ => method,
+        }
+        .to_string(),
+        "".to_string(),
+    )
+}
@@ -1,18 +1,24 @@
+use std::time::Instant;
+
 use axum::{
-    extract::{Request, State},
+    extract::{MatchedPath, Request, State},
     middleware::Next,
     response::{IntoResponse, Response},
 };
+use log::info;
+use metrics::{counter, histogram};
 
 use crate::app_state::AppState;
 
-use super::super::{response::ApiResponse, schema::Account};
+use super::super::{response::ApiResponse, schema::Subject};
 
 /// RBAC权限控制中间件
 ///
 /// # 功能
-/// - 从请求扩展中获取用户账号
+/// - 从请求扩展中获取 RBAC 主体
 /// - 检查用户是否有权限访问当前路径和方法
+/// - 拒绝访问时记录拒绝的原因, 便于排查角色配置
+/// - 记录权限检查耗时, 按方法和路由统计拒绝次数
 ///
 /// # 参数
 /// - state: 应用状态
@@ -24,18 +30,20 @@ use super::super::{response::ApiResponse, schema::Account};
 /// - 如果无权限,返回权限拒绝错误
 /// - 如果检查过程出错,返回系统错误
 pub async fn rbac(State(state): State<AppState>, request: Request, next: Next) -> Response {
-    let account = match request.extensions().get::<Account>() {
-        Some(account) => account.to_owned(),
+    let subject = match request.extensions().get::<Subject>() {
+        Some(subject) => subject.to_owned(),
         None => return ApiResponse::<()>::unauthorized().into_response(),
     };
 
     let method = request.method().as_str().to_uppercase();
     let path = request.uri().path().to_string();
 
+    let started = Instant::now();
     let is_permission = state
         .rbac()
-        .check_permission(account.0, method.to_string(), path)
+        .check_permission(subject.0.clone(), method.to_string(), path.clone())
         .await;
+    histogram!("rbac_check_duration_seconds").record(started.elapsed().as_secs_f64());
 
     match is_permission {
         Ok(is_ok) => {
@@ -46,5 +54,23 @@ pub async fn rbac(State(state): State<AppState>, request: Request, next: Next) -
         Err(err) => return ApiResponse::<()>::system_error(err.to_string()).into_response(),
     }
 
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|route| route.as_str().to_string())
+        .unwrap_or_else(|| path.clone());
+    counter!("rbac_denials_total", "method" => method.clone(), "route" => route).increment(1);
+
+    if let Ok(explanation) = state
+        .rbac()
+        .explain(subject.0.clone(), method.clone(), path.clone())
+        .await
+    {
+        info!(
+            "用户 {} 访问 {} {} 被拒绝: {}",
+            subject.0, method, path, explanation.reason
+        );
+    }
+
     ApiResponse::<()>::permission_denied().into_response()
 }
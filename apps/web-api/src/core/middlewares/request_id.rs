@@ -0,0 +1,120 @@
+use std::time::Instant;
+
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use log::info;
+use tracing::{info_span, Instrument};
+
+use crate::core::schema::RequestId;
+
+/// 请求ID所在的请求头和响应头
+pub static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// 客户端传入的请求ID的最大长度
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// 请求ID中间件
+///
+/// # 功能
+/// - 使用客户端传入的 `X-Request-Id`, 没有或格式不正确时生成新的请求ID
+/// - 将请求ID注入到请求扩展中, 并在响应头中返回
+/// - 在包含请求ID、方法和路径的 span 中处理请求, 请求期间的日志都会带上这些字段
+/// - 请求结束时记录响应状态码和耗时
+///
+/// # 参数
+/// - request: HTTP请求
+/// - next: 下一个处理器
+pub async fn request_id(mut request: Request, next: Next) -> Response {
+    let request_id = match request
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| is_valid_request_id(value))
+    {
+        Some(value) => value.to_string(),
+        None => libs::next_id().await,
+    };
+
+    request.extensions_mut().insert(RequestId(request_id.clone()));
+
+    let span = info_span!(
+        "request",
+        request_id = %request_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+
+    async move {
+        let started = Instant::now();
+        let mut response = next.run(request).await;
+
+        info!(
+            "{} in {}ms",
+            response.status().as_u16(),
+            started.elapsed().as_millis()
+        );
+
+        if let Ok(value) = HeaderValue::from_str(&request_id) {
+            response.headers_mut().insert(REQUEST_ID_HEADER.clone(), value);
+        }
+
+        response
+    }
+    .instrument(span)
+    .await
+}
+
+/// 只接受长度有限的可见字符, 避免客户端写入超长或带换行的内容污染日志
+fn is_valid_request_id(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_REQUEST_ID_LENGTH
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::get, Extension, Router};
+    use tower::ServiceExt;
+
+    async fn echo(Extension(request_id): Extension<RequestId>) -> String {
+        request_id.0
+    }
+
+    #[tokio::test]
+    async fn test_request_id() {
+        let app = Router::new()
+            .route("/", get(echo))
+            .layer(middleware::from_fn(request_id));
+
+        let request = Request::get("/")
+            .header(&REQUEST_ID_HEADER, "abc-123")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.headers()[&REQUEST_ID_HEADER], "abc-123");
+
+        let request = Request::get("/")
+            .header(&REQUEST_ID_HEADER, "bad id")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let generated = response.headers()[&REQUEST_ID_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert_ne!(generated, "bad id");
+        assert!(is_valid_request_id(&generated));
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, generated.as_bytes());
+    }
+}
@@ -1,6 +1,9 @@
+mod catalog;
 mod errors;
+mod extract;
 pub mod handlers;
 mod middlewares;
+mod openapi;
 mod response;
 pub mod routes;
 mod schema;
@@ -0,0 +1,249 @@
+use log::warn;
+use serde_json::json;
+use services::role::PermissionCatalog;
+use utoipa::{
+    openapi::{
+        extensions::ExtensionsBuilder,
+        security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme},
+        ContentBuilder, OpenApi as OpenApiDoc, PathItem, Ref, ResponseBuilder,
+    },
+    Modify, OpenApi,
+};
+
+use super::{
+    errors::{ErrorCode, FieldError, ValidationErrorBody},
+    handlers,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Admin API", description = "后台管理接口, 所有响应都使用 `ApiResponse` 格式"),
+    paths(
+        handlers::login::login,
+        handlers::login::refresh_token,
+        handlers::login::oidc_authorize,
+        handlers::login::oidc_callback,
+        handlers::login::verify_two_factor,
+        handlers::login::enroll_two_factor,
+        handlers::login::activate_two_factor,
+        handlers::login::logout,
+        handlers::admin::create_admin,
+        handlers::admin::get_admin_list,
+        handlers::admin::update_admin,
+        handlers::admin::delete_admin,
+        handlers::admin::update_admin_role,
+        handlers::admin::get_admin_permissions,
+        handlers::admin::explain_admin_permission,
+        handlers::admin::enable_admin,
+        handlers::admin::disable_admin,
+        handlers::admin::unlock_admin,
+        handlers::api_key::create_api_key,
+        handlers::api_key::get_api_key_list,
+        handlers::api_key::update_api_key,
+        handlers::api_key::delete_api_key,
+        handlers::role::create_role,
+        handlers::role::get_role_list,
+        handlers::role::update_role,
+        handlers::role::delete_role,
+        handlers::role::get_permission_catalog,
+        handlers::message::send_message,
+        handlers::message::get_message_list,
+        handlers::message::retry_message,
+        handlers::broadcast::create_broadcast,
+        handlers::broadcast::get_broadcast_list,
+        handlers::broadcast::get_broadcast,
+        handlers::product::create_product,
+        handlers::product::search_products,
+        handlers::product::get_product,
+        handlers::product::update_product,
+        handlers::product::create_sku,
+        handlers::product::update_sku,
+        handlers::product::delete_sku,
+        handlers::webhook::create_webhook,
+        handlers::webhook::get_webhook_list,
+        handlers::webhook::update_webhook,
+        handlers::webhook::delete_webhook,
+        handlers::webhook::rotate_webhook_secret,
+        handlers::me::get_me,
+        handlers::me::change_password,
+        handlers::me::update_profile,
+        handlers::me::get_permissions,
+        handlers::me::enroll_two_factor,
+        handlers::me::activate_two_factor,
+        handlers::me::regenerate_recovery_codes,
+        handlers::me::disable_two_factor,
+        handlers::me::get_notification_preferences,
+        handlers::me::update_notification_preferences,
+        handlers::internal_message::get_my_messages,
+        handlers::internal_message::get_unread_count,
+        handlers::internal_message::mark_messages_as_read,
+        handlers::internal_message::mark_all_messages_as_read,
+        handlers::internal_message::mark_message_as_read,
+        handlers::internal_message::archive_message,
+        handlers::internal_message::delete_message,
+        handlers::upload::upload_file,
+    ),
+    components(schemas(ErrorCode, FieldError, ValidationErrorBody)),
+    modifiers(&SecuritySchemes, &ErrorResponse),
+    security(("bearer" = [])),
+    tags(
+        (name = "auth", description = "登录与令牌"),
+        (name = "me", description = "当前用户"),
+        (name = "admin", description = "管理员"),
+        (name = "api_key", description = "API 密钥"),
+        (name = "role", description = "角色与权限"),
+        (name = "message", description = "消息"),
+        (name = "broadcast", description = "广播"),
+        (name = "product", description = "商品"),
+        (name = "webhook", description = "Webhook"),
+        (name = "internal_message", description = "站内消息"),
+        (name = "upload", description = "文件上传"),
+    )
+)]
+struct ApiDoc;
+
+/// 登录令牌和 API 密钥两种认证方式, API 密钥只能访问受权限控制的接口
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut OpenApiDoc) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                "Authorization",
+                "ApiKey <key_id>.<secret>",
+            ))),
+        );
+    }
+}
+
+/// 给每个接口加上错误响应, 错误代码见 `ErrorCode`
+struct ErrorResponse;
+
+impl Modify for ErrorResponse {
+    fn modify(&self, openapi: &mut OpenApiDoc) {
+        let response = ResponseBuilder::new()
+            .description("请求失败, `errorCode` 给出错误代码, 参数校验失败时 `data` 为 `ValidationErrorBody`")
+            .content(
+                "application/json",
+                ContentBuilder::new()
+                    .schema(Some(Ref::from_schema_name("ApiResponse_Empty")))
+                    .build(),
+            )
+            .build();
+
+        for item in openapi.paths.paths.values_mut() {
+            for operation in operations(item) {
+                operation
+                    .responses
+                    .responses
+                    .insert("default".to_string(), response.clone().into());
+            }
+        }
+    }
+}
+
+fn operations(item: &mut PathItem) -> impl Iterator<Item = &mut utoipa::openapi::path::Operation> {
+    [
+        item.get.as_mut(),
+        item.post.as_mut(),
+        item.put.as_mut(),
+        item.delete.as_mut(),
+        item.patch.as_mut(),
+    ]
+    .into_iter()
+    .flatten()
+}
+
+/// 生成 OpenAPI 文档
+///
+/// 受权限控制的接口从权限目录中读取所属模块, 记录在 `x-rbac-module` 扩展字段中,
+/// 角色只能授予带有该字段的接口; 只有这些接口接受 API 密钥
+///
+/// # 参数
+///
+/// * `catalog` - 注册路由时生成的权限目录
+pub fn document(catalog: &PermissionCatalog) -> OpenApiDoc {
+    let mut openapi = ApiDoc::openapi();
+
+    for route in catalog.items() {
+        let operation = openapi
+            .paths
+            .paths
+            .get_mut(&openapi_path(&route.path))
+            .and_then(|item| match route.method.as_str() {
+                "GET" => item.get.as_mut(),
+                "POST" => item.post.as_mut(),
+                "PUT" => item.put.as_mut(),
+                "DELETE" => item.delete.as_mut(),
+                "PATCH" => item.patch.as_mut(),
+                _ => None,
+            });
+
+        let Some(operation) = operation else {
+            warn!("接口 {} {} 没有出现在 OpenAPI 文档中", route.method, route.path);
+            continue;
+        };
+
+        let extensions = ExtensionsBuilder::new()
+            .add("x-rbac-module", json!(route.module))
+            .add("x-rbac-description", json!(route.description))
+            .build();
+        operation
+            .extensions
+            .get_or_insert_with(Default::default)
+            .merge(extensions);
+        operation.security = Some(vec![
+            SecurityRequirement::new("bearer", Vec::<String>::new()),
+            SecurityRequirement::new("api_key", Vec::<String>::new()),
+        ]);
+    }
+
+    openapi
+}
+
+/// 把路由路径中的 `:id` 参数转换为 OpenAPI 的 `{id}` 形式
+fn openapi_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(name) => format!("{{{}}}", name),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_covers_catalog() {
+        let mut catalog = PermissionCatalog::new();
+        catalog.add("admin", "PUT", "/admins/:id/role", "修改管理员角色");
+        catalog.add("webhook", "POST", "/webhooks", "创建 Webhook");
+
+        let openapi = document(&catalog);
+        let value = serde_json::to_value(&openapi).unwrap();
+
+        let operation = &value["paths"]["/admins/{id}/role"]["put"];
+        assert_eq!(operation["x-rbac-module"], "admin");
+        assert!(operation["responses"]["default"].is_object());
+        assert_eq!(value["paths"]["/webhooks"]["post"]["x-rbac-module"], "webhook");
+        assert!(value["paths"]["/login"]["post"].get("x-rbac-module").is_none());
+        assert_eq!(operation["security"][1]["api_key"], json!([]));
+        assert!(value["paths"]["/me"]["get"].get("security").is_none());
+        assert!(value["components"]["securitySchemes"]["bearer"].is_object());
+    }
+}
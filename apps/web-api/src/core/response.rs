@@ -1,29 +1,44 @@
-use axum::{response::IntoResponse, Json};
+use axum::{http::StatusCode, response::IntoResponse, Json};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-use super::errors::Result;
+use super::errors::{ErrorCode, Result};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct ListResponse<T> {
     pub items: Vec<T>,
+    pub total: u64,
 }
 
-#[derive(Debug, Serialize)]
+/// 所有接口统一使用的响应格式
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ApiResponse<T> {
     pub status: u16,
     #[serde(rename = "errorMessage")]
     pub message: String,
+    #[serde(rename = "errorCode", skip_serializing_if = "Option::is_none")]
+    pub code: Option<ErrorCode>,
     pub data: Option<T>,
     pub success: bool,
 }
 
+/// 接口文档中表示没有数据, 对应 `ApiResponse<()>`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Empty;
+
+/// 标记响应使用了 `ApiResponse` 格式, 兼容模式只改写这类响应的状态码
+#[derive(Debug, Clone, Copy)]
+pub struct ApiEnvelope;
+
 impl<T> IntoResponse for ApiResponse<T>
 where
     T: Serialize,
 {
     fn into_response(self) -> axum::response::Response {
-        let json = Json(self);
-        json.into_response()
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = (status, Json(self)).into_response();
+        response.extensions_mut().insert(ApiEnvelope);
+        response
     }
 }
 
@@ -32,6 +47,7 @@ impl<T> ApiResponse<T> {
         Ok(ApiResponse {
             status: 200,
             message: "OK".to_string(),
+            code: None,
             data: None,
             success: true,
         })
@@ -41,6 +57,7 @@ impl<T> ApiResponse<T> {
         Ok(ApiResponse {
             status: 200,
             message: "OK".to_string(),
+            code: None,
             data: Some(data),
             success: true,
         })
@@ -50,6 +67,7 @@ impl<T> ApiResponse<T> {
         Ok(ApiResponse {
             status: 401,
             message: "Unauthorized".to_string(),
+            code: Some(ErrorCode::Unauthorized),
             data: None,
             success: false,
         })
@@ -59,6 +77,7 @@ impl<T> ApiResponse<T> {
         Ok(ApiResponse {
             status: 500,
             message: format!("System error: {}", message),
+            code: Some(ErrorCode::InternalError),
             data: None,
             success: false,
         })
@@ -68,6 +87,7 @@ impl<T> ApiResponse<T> {
         Ok(ApiResponse {
             status: 403,
             message: "Permission denied".to_string(),
+            code: Some(ErrorCode::PermissionDenied),
             data: None,
             success: false,
         })
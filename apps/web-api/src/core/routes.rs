@@ -1,27 +1,32 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use axum::{
+    http::Method,
     middleware,
     routing::{delete, get, post, put},
-    Router,
+    Extension, Router,
 };
+use services::role::PermissionCatalog;
 use tower::ServiceBuilder;
 use tower_http::{
     cors::{Any, CorsLayer},
     timeout::TimeoutLayer,
 };
+use utoipa_swagger_ui::SwaggerUi;
 
 use crate::app_state::AppState;
 
+use super::catalog::CatalogRouter;
 use super::handlers;
 use super::handlers::upload;
 use super::middlewares;
+use super::openapi;
 
 /// Creates the main application router with all the routes configured.
 ///
 /// This function sets up all the routes for the application, including authentication,
 /// organization management, project management, and more. It also configures middleware
-/// for timeout and CORS settings.
+/// for timeout and CORS settings, request ids and HTTP metrics.
 ///
 /// # Arguments
 ///
@@ -31,11 +36,30 @@ use super::middlewares;
 ///
 /// Returns a `Router` with all the routes and middleware configured.
 pub fn create(app_state: AppState) -> Router {
+    let (rbac_routes, catalog) = rbac_routes(app_state.clone());
+    let openapi = openapi::document(&catalog);
+
     // build our application with a single route
-    let app = Router::new()
+    Router::new()
         .route("/login", post(handlers::login::login))
-        .nest("/", secret_routes(app_state.clone()))
+        .route("/auth/refresh", post(handlers::login::refresh_token))
+        .route("/auth/oidc/authorize", get(handlers::login::oidc_authorize))
+        .route("/auth/oidc/callback", post(handlers::login::oidc_callback))
+        .route("/auth/2fa/verify", post(handlers::login::verify_two_factor))
+        .route("/auth/2fa/enroll", post(handlers::login::enroll_two_factor))
+        .route("/auth/2fa/activate", post(handlers::login::activate_two_factor))
+        .nest("/", secret_routes(app_state.clone(), rbac_routes))
+        .merge(SwaggerUi::new("/swagger-ui").url("/openapi.json", openapi))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            middlewares::antd_compat,
+        ))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            middlewares::client_ip,
+        ))
         .with_state(app_state)
+        .layer(Extension(Arc::new(catalog)))
         .layer(
             ServiceBuilder::new()
                 .layer(TimeoutLayer::new(Duration::from_secs(30)))
@@ -43,59 +67,368 @@ pub fn create(app_state: AppState) -> Router {
                     CorsLayer::new()
                         .allow_origin(Any)
                         .allow_methods(Any)
-                        .allow_headers(Any),
+                        .allow_headers(Any)
+                        .expose_headers([middlewares::REQUEST_ID_HEADER.clone()]),
                 ),
-        );
-    app
+        )
+        .layer(middleware::from_fn(middlewares::http_metrics))
+        .layer(middleware::from_fn(middlewares::request_id))
 }
 
-fn rbac_routes(state: AppState) -> Router<AppState> {
-    Router::new()
-        .route("/admins", post(handlers::admin::create_admin))
-        .route("/admins", get(handlers::admin::get_admin_list))
-        .route("/admins/:id", put(handlers::admin::update_admin))
-        .route("/admins/:id", delete(handlers::admin::delete_admin))
-        .route("/admins/:id/role", put(handlers::admin::update_admin_role))
-        .route("/roles", post(handlers::role::create_role))
-        .route("/roles", get(handlers::role::get_role_list))
-        .route("/roles/:id", put(handlers::role::update_role))
-        .route("/roles/:id", delete(handlers::role::delete_role))
-        .route("/messages", post(handlers::message::send_message))
-        .route("/messages", get(handlers::message::get_message_list))
-        .route("/messages/:id/retry", post(handlers::message::retry_message))
-        .route_layer(middleware::from_fn_with_state(state.clone(), middlewares::rbac))
+/// Defines routes protected by RBAC and records them in the permission catalog.
+///
+/// # Arguments
+///
+/// * `state` - The application state containing shared resources and configurations.
+///
+/// # Returns
+///
+/// Returns the `Router` with the RBAC middleware applied, and the catalog of its routes.
+fn rbac_routes(state: AppState) -> (Router<AppState>, PermissionCatalog) {
+    let (router, catalog) = CatalogRouter::new()
+        .route(
+            Method::POST,
+            "/admins",
+            "admin",
+            "创建管理员",
+            handlers::admin::create_admin,
+        )
+        .route(
+            Method::GET,
+            "/admins",
+            "admin",
+            "获取管理员列表",
+            handlers::admin::get_admin_list,
+        )
+        .route(
+            Method::PUT,
+            "/admins/:id",
+            "admin",
+            "更新管理员",
+            handlers::admin::update_admin,
+        )
+        .route(
+            Method::DELETE,
+            "/admins/:id",
+            "admin",
+            "删除管理员",
+            handlers::admin::delete_admin,
+        )
+        .route(
+            Method::PUT,
+            "/admins/:id/role",
+            "admin",
+            "修改管理员角色",
+            handlers::admin::update_admin_role,
+        )
+        .route(
+            Method::GET,
+            "/admins/:id/permissions",
+            "admin",
+            "查看管理员的有效权限",
+            handlers::admin::get_admin_permissions,
+        )
+        .route(
+            Method::GET,
+            "/admins/:id/permissions/explain",
+            "admin",
+            "模拟检查管理员的接口权限",
+            handlers::admin::explain_admin_permission,
+        )
+        .route(
+            Method::PUT,
+            "/admins/:id/enable",
+            "admin",
+            "启用管理员",
+            handlers::admin::enable_admin,
+        )
+        .route(
+            Method::PUT,
+            "/admins/:id/disable",
+            "admin",
+            "禁用管理员",
+            handlers::admin::disable_admin,
+        )
+        .route(
+            Method::PUT,
+            "/admins/:id/unlock",
+            "admin",
+            "解除管理员登录锁定",
+            handlers::admin::unlock_admin,
+        )
+        .route(
+            Method::POST,
+            "/api-keys",
+            "api_key",
+            "创建 API 密钥",
+            handlers::api_key::create_api_key,
+        )
+        .route(
+            Method::GET,
+            "/api-keys",
+            "api_key",
+            "获取 API 密钥列表",
+            handlers::api_key::get_api_key_list,
+        )
+        .route(
+            Method::PUT,
+            "/api-keys/:id",
+            "api_key",
+            "更新 API 密钥",
+            handlers::api_key::update_api_key,
+        )
+        .route(
+            Method::DELETE,
+            "/api-keys/:id",
+            "api_key",
+            "删除 API 密钥",
+            handlers::api_key::delete_api_key,
+        )
+        .route(
+            Method::POST,
+            "/roles",
+            "role",
+            "创建角色",
+            handlers::role::create_role,
+        )
+        .route(
+            Method::GET,
+            "/roles",
+            "role",
+            "获取角色列表",
+            handlers::role::get_role_list,
+        )
+        .route(
+            Method::PUT,
+            "/roles/:id",
+            "role",
+            "更新角色",
+            handlers::role::update_role,
+        )
+        .route(
+            Method::DELETE,
+            "/roles/:id",
+            "role",
+            "删除角色",
+            handlers::role::delete_role,
+        )
+        .route(
+            Method::POST,
+            "/messages",
+            "message",
+            "发送消息",
+            handlers::message::send_message,
+        )
+        .route(
+            Method::GET,
+            "/messages",
+            "message",
+            "获取消息列表",
+            handlers::message::get_message_list,
+        )
+        .route(
+            Method::POST,
+            "/messages/:id/retry",
+            "message",
+            "重试发送消息",
+            handlers::message::retry_message,
+        )
+        .route(
+            Method::POST,
+            "/messages/broadcasts",
+            "broadcast",
+            "创建广播",
+            handlers::broadcast::create_broadcast,
+        )
+        .route(
+            Method::GET,
+            "/messages/broadcasts",
+            "broadcast",
+            "获取广播列表",
+            handlers::broadcast::get_broadcast_list,
+        )
+        .route(
+            Method::GET,
+            "/messages/broadcasts/:id",
+            "broadcast",
+            "获取广播详情",
+            handlers::broadcast::get_broadcast,
+        )
+        .route(
+            Method::POST,
+            "/products",
+            "product",
+            "创建商品",
+            handlers::product::create_product,
+        )
+        .route(
+            Method::GET,
+            "/products",
+            "product",
+            "搜索商品",
+            handlers::product::search_products,
+        )
+        .route(
+            Method::GET,
+            "/products/:id",
+            "product",
+            "获取商品详情",
+            handlers::product::get_product,
+        )
+        .route(
+            Method::PUT,
+            "/products/:id",
+            "product",
+            "更新商品",
+            handlers::product::update_product,
+        )
+        .route(
+            Method::POST,
+            "/products/:id/skus",
+            "product",
+            "创建 SKU",
+            handlers::product::create_sku,
+        )
+        .route(
+            Method::PUT,
+            "/products/:id/skus/:sku_id",
+            "product",
+            "更新 SKU",
+            handlers::product::update_sku,
+        )
+        .route(
+            Method::DELETE,
+            "/products/:id/skus/:sku_id",
+            "product",
+            "删除 SKU",
+            handlers::product::delete_sku,
+        )
+        .route(
+            Method::POST,
+            "/webhooks",
+            "webhook",
+            "创建 Webhook",
+            handlers::webhook::create_webhook,
+        )
+        .route(
+            Method::GET,
+            "/webhooks",
+            "webhook",
+            "获取 Webhook 列表",
+            handlers::webhook::get_webhook_list,
+        )
+        .route(
+            Method::PUT,
+            "/webhooks/:id",
+            "webhook",
+            "更新 Webhook",
+            handlers::webhook::update_webhook,
+        )
+        .route(
+            Method::DELETE,
+            "/webhooks/:id",
+            "webhook",
+            "删除 Webhook",
+            handlers::webhook::delete_webhook,
+        )
+        .route(
+            Method::POST,
+            "/webhooks/:id/rotate-secret",
+            "webhook",
+            "重新生成 Webhook 签名密钥",
+            handlers::webhook::rotate_webhook_secret,
+        )
+        .into_parts();
+
+    let router = router.route_layer(middleware::from_fn_with_state(state.clone(), middlewares::rbac));
+
+    (router, catalog)
 }
 
 /// Defines secret routes that require authorization.
 ///
 /// These routes are intended for authenticated users to access specific functionalities
 /// such as fetching user information, managing tasks, and handling qualifications.
+/// API keys are only accepted on the RBAC protected routes.
 ///
 /// # Arguments
 ///
 /// * `state` - The application state containing shared resources and configurations.
+/// * `rbac_routes` - The routes protected by RBAC, nested under the authorization middleware.
 ///
 /// # Returns
 ///
 /// Returns a `Router` configured with secret routes.
-fn secret_routes(state: AppState) -> Router<AppState> {
+fn secret_routes(state: AppState, rbac_routes: Router<AppState>) -> Router<AppState> {
+    Router::new()
+        .nest("/", rbac_routes)
+        .merge(admin_routes())
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            middlewares::operation_log,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            middlewares::authorization,
+        ))
+}
+
+/// Defines routes only available to admins signed in with an access token.
+///
+/// # Returns
+///
+/// Returns a `Router` rejecting requests authenticated with an API key.
+fn admin_routes() -> Router<AppState> {
     Router::new()
-        .nest("/", rbac_routes(state.clone()))
+        .route("/permissions", get(handlers::role::get_permission_catalog))
         .route("/upload", post(upload::upload_file))
+        .route("/logout", post(handlers::login::logout))
+        .route("/me", get(handlers::me::get_me))
+        .route("/me/password", put(handlers::me::change_password))
+        .route("/me/profile", put(handlers::me::update_profile))
+        .route("/me/permissions", get(handlers::me::get_permissions))
+        .route("/me/2fa/enroll", post(handlers::me::enroll_two_factor))
+        .route("/me/2fa/activate", post(handlers::me::activate_two_factor))
+        .route(
+            "/me/2fa/recovery-codes",
+            post(handlers::me::regenerate_recovery_codes),
+        )
+        .route("/me/2fa/disable", post(handlers::me::disable_two_factor))
+        .route(
+            "/me/notification-preferences",
+            get(handlers::me::get_notification_preferences),
+        )
+        .route(
+            "/me/notification-preferences",
+            put(handlers::me::update_notification_preferences),
+        )
         .route(
             "/messages/internal",
             get(handlers::internal_message::get_my_messages),
         )
+        .route(
+            "/messages/internal/unread-count",
+            get(handlers::internal_message::get_unread_count),
+        )
+        .route(
+            "/messages/internal/read",
+            post(handlers::internal_message::mark_messages_as_read),
+        )
+        .route(
+            "/messages/internal/read-all",
+            post(handlers::internal_message::mark_all_messages_as_read),
+        )
         .route(
             "/messages/internal/:id/read",
             post(handlers::internal_message::mark_message_as_read),
         )
-        .route_layer(middleware::from_fn_with_state(
-            state.clone(),
-            middlewares::operation_log,
-        ))
-        .route_layer(middleware::from_fn_with_state(
-            state.clone(),
-            middlewares::authorization,
-        ))
+        .route(
+            "/messages/internal/:id/archive",
+            post(handlers::internal_message::archive_message),
+        )
+        .route(
+            "/messages/internal/:id",
+            delete(handlers::internal_message::delete_message),
+        )
+        .route_layer(middleware::from_fn(middlewares::admin_only))
 }
@@ -8,6 +8,34 @@ pub struct UserID(pub String);
 #[derive(Debug, Clone, Deserialize)]
 pub struct Account(pub String);
 
+/// RBAC 主体包装类型, 管理员为 `user:<账号>`, API 密钥为 `apikey:<key_id>`
+#[derive(Debug, Clone)]
+pub struct Subject(pub String);
+
+/// 请求ID包装类型, 由请求ID中间件注入
+#[derive(Debug, Clone, Default)]
+pub struct RequestId(pub String);
+
+/// 客户端IP包装类型, 由客户端IP中间件按受信任代理解析后注入
+#[derive(Debug, Clone, Default)]
+pub struct ClientIp(pub String);
+
+/// 请求的认证主体, 由授权中间件注入
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Principal {
+    /// 使用访问令牌登录的管理员
+    Admin,
+    /// 使用API密钥访问的调用方, 只能访问受RBAC保护的接口
+    ApiKey,
+}
+
+/// 当前请求使用的访问令牌信息
+#[derive(Debug, Clone, Default)]
+pub struct AccessToken {
+    pub jti: String,
+    pub expires_at: i64,
+}
+
 impl From<UserID> for String {
     fn from(user_id: UserID) -> Self {
         user_id.0
@@ -18,6 +18,9 @@ pub enum Error {
 
     #[error("jwt token creation failed")]
     TokenCreationFailed,
+
+    #[error("jwt token expired")]
+    TokenExpired,
 }
 
 #[derive(Debug, Clone)]
@@ -29,20 +32,34 @@ pub struct TokenPayload {
     pub id: String,
     pub account: String,
     pub role: String,
+    /// 令牌唯一标识, 用于单个令牌的吊销
+    pub jti: String,
+    /// 签发时间(毫秒级时间戳)
+    pub issued_at: i64,
+    /// 过期时间(秒级时间戳)
+    pub expires_at: i64,
 }
 
 impl TokenPayload {
     pub fn new(id: String, account: String, role: String) -> Self {
-        Self { id, account, role }
+        Self {
+            id,
+            account,
+            role,
+            jti: String::new(),
+            issued_at: 0,
+            expires_at: 0,
+        }
     }
 }
 
-impl Into<BTreeMap<String, Value>> for TokenPayload {
-    fn into(self) -> BTreeMap<String, Value> {
+impl From<TokenPayload> for BTreeMap<String, Value> {
+    fn from(val: TokenPayload) -> Self {
         let mut out: BTreeMap<String, Value> = BTreeMap::new();
-        out.insert("id".to_string(), self.id.into());
-        out.insert("account".to_string(), self.account.into());
-        out.insert("role".to_string(), self.role.into());
+        out.insert("id".to_string(), val.id.into());
+        out.insert("account".to_string(), val.account.into());
+        out.insert("role".to_string(), val.role.into());
+        out.insert("iat_ms".to_string(), val.issued_at.into());
 
         out
     }
@@ -65,17 +82,20 @@ impl From<BTreeMap<String, Value>> for TokenPayload {
             _ => String::new(),
         };
 
-        TokenPayload { id, account, role }
+        let issued_at = payload_map
+            .get("iat_ms")
+            .and_then(Value::as_i64)
+            .unwrap_or_default();
+
+        let mut payload = TokenPayload::new(id, account, role);
+        payload.issued_at = issued_at;
+        payload
     }
 }
 
 impl From<Admin> for TokenPayload {
     fn from(user: Admin) -> Self {
-        Self {
-            id: user.base.id,
-            account: user.secret.account,
-            role: user.role_name,
-        }
+        Self::new(user.base.id, user.secret.account, user.role_names.join(","))
     }
 }
 
@@ -88,19 +108,23 @@ impl Engine {
         Ok(out)
     }
 
-    /// create a token str from a user id
+    /// create a token str from a user id, valid for `ttl` seconds
     ///
     /// # Errors
     ///
     /// This function will return an error if .
     /// * the token can not be created (sign failed)
-    pub fn create_token<T: Into<TokenPayload>>(&self, payload: T) -> Result<String, Error> {
-        let expiration = Utc::now().add(Duration::days(30)).timestamp();
-        let infomation = payload.into();
+    pub fn create_token<T: Into<TokenPayload>>(&self, payload: T, ttl: u64) -> Result<String, Error> {
+        let now = Utc::now();
+        let expiration = now.add(Duration::seconds(ttl as i64)).timestamp();
+        let mut infomation = payload.into();
+        infomation.issued_at = now.timestamp_millis();
 
         let mut claims = Claims::new(RegisteredClaims {
             subject: Some(infomation.id.clone()),
+            issued_at: Some(now.timestamp() as u64),
             expiration: Some(expiration as u64),
+            json_web_token_id: Some(libs::random_token(16)),
             ..Default::default()
         });
 
@@ -117,10 +141,74 @@ impl Engine {
     ///
     /// This function will return an error if .
     /// * the token is invalid
+    /// * the token has no expiration or is expired
     pub fn verify_token(&self, token: &str) -> Result<TokenPayload, Error> {
         let claims: Claims = token.verify_with_key(&self.key)?;
         let _uid = claims.registered.subject.ok_or(Error::TokenCreationFailed)?;
 
-        Ok(TokenPayload::from(claims.private))
+        let expiration = claims.registered.expiration.ok_or(Error::TokenExpired)?;
+        if expiration as i64 <= Utc::now().timestamp() {
+            return Err(Error::TokenExpired);
+        }
+
+        let mut payload = TokenPayload::from(claims.private);
+        payload.jti = claims.registered.json_web_token_id.unwrap_or_default();
+        payload.expires_at = claims.registered.expiration.unwrap_or_default() as i64;
+
+        Ok(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_token() {
+        let engine = Engine::new("secret".to_string()).unwrap();
+        let payload = TokenPayload::new("1".to_string(), "admin".to_string(), "admin".to_string());
+
+        let token = engine.create_token(payload, 60).unwrap();
+        let verified = engine.verify_token(&token).unwrap();
+        assert_eq!(verified.id, "1");
+        assert!(!verified.jti.is_empty());
+        assert!(verified.issued_at > 0);
+
+        let other = Engine::new("other".to_string()).unwrap();
+        assert!(other.verify_token(&token).is_err());
+    }
+
+    #[test]
+    fn test_verify_expired_token() {
+        let engine = Engine::new("secret".to_string()).unwrap();
+        let payload = TokenPayload::new("1".to_string(), "admin".to_string(), "admin".to_string());
+
+        let token = engine.create_token(payload, 0).unwrap();
+        assert!(matches!(engine.verify_token(&token), Err(Error::TokenExpired)));
+    }
+
+    #[test]
+    fn test_token_account_is_rbac_subject() {
+        use entities::{Admin, Secret};
+        use rbac::RBACUser;
+
+        let secret = Secret {
+            account: "alice".to_string(),
+            password: String::new(),
+        };
+        let admin = Admin::new(
+            "1".to_string(),
+            secret,
+            "Alice".to_string(),
+            vec!["admin".to_string()],
+        );
+
+        // 授权中间件按令牌中的 account 计算 RBAC 主体, 必须与加载策略时使用的主体相同
+        let engine = Engine::new("secret".to_string()).unwrap();
+        let token = engine.create_token(admin.clone(), 60).unwrap();
+        let verified = engine.verify_token(&token).unwrap();
+        assert_eq!(Admin::subject_of(&verified.account), RBACUser::subject(&admin));
+        assert_eq!(RBACUser::subject(&admin), "user:alice");
+        assert_ne!(verified.account, admin.name);
     }
 }
@@ -0,0 +1,215 @@
+use std::time::Duration;
+
+use config::AuthConfig;
+use libs::cache::{Cache, CacheError, CacheWithString};
+
+const ACCOUNT_FAILURES_PREFIX: &str = "auth:failures:account:";
+const IP_FAILURES_PREFIX: &str = "auth:failures:ip:";
+const ACCOUNT_LOCK_PREFIX: &str = "auth:lock:account:";
+const IP_LOCK_PREFIX: &str = "auth:lock:ip:";
+
+pub type Result<T> = std::result::Result<T, CacheError>;
+
+/// 登录被拒绝的原因
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Lockout {
+    /// 账号被锁定
+    Account,
+    /// 来源 IP 被锁定
+    Ip,
+}
+
+/// 一次登录失败后的处理结果
+///
+/// # 字段
+///
+/// * `failures` - 账号在统计窗口内的连续失败次数
+/// * `locked` - 本次失败触发的锁定
+/// * `delay` - 返回响应前需要等待的时间
+#[derive(Debug, Clone)]
+pub struct FailureOutcome {
+    pub failures: u64,
+    pub locked: Option<Lockout>,
+    pub delay: Duration,
+}
+
+/// 登录防暴力破解
+///
+/// 按账号和来源 IP 分别统计登录失败次数:
+/// - 每次失败后的响应延迟逐次递增
+/// - 达到上限后在锁定时长内拒绝登录
+/// - 登录成功后清除账号的失败次数
+#[derive(Clone)]
+pub struct LoginGuard<C: Cache> {
+    cache: C,
+}
+
+impl<C: Cache> LoginGuard<C> {
+    pub fn new(cache: C) -> Self {
+        Self { cache }
+    }
+
+    /// 检查账号或来源 IP 是否处于锁定状态
+    pub async fn check(&self, account: &str, ip: &str) -> Result<Option<Lockout>> {
+        if self.cache.exists(&key(ACCOUNT_LOCK_PREFIX, account)).await? {
+            return Ok(Some(Lockout::Account));
+        }
+
+        if self.cache.exists(&key(IP_LOCK_PREFIX, ip)).await? {
+            return Ok(Some(Lockout::Ip));
+        }
+
+        Ok(None)
+    }
+
+    /// 记录一次登录失败
+    pub async fn record_failure(
+        &self,
+        account: &str,
+        ip: &str,
+        config: &AuthConfig,
+    ) -> Result<FailureOutcome> {
+        let window = Some(config.failed_attempt_window);
+
+        let failures = self
+            .cache
+            .incr(&key(ACCOUNT_FAILURES_PREFIX, account), window)
+            .await?;
+        let ip_failures = self.cache.incr(&key(IP_FAILURES_PREFIX, ip), window).await?;
+
+        let mut locked = None;
+        if ip_failures >= config.max_failed_attempts_per_ip {
+            self.lock(IP_LOCK_PREFIX, IP_FAILURES_PREFIX, ip, config.lockout_duration)
+                .await?;
+            locked = Some(Lockout::Ip);
+        }
+
+        if failures >= config.max_failed_attempts {
+            self.lock(
+                ACCOUNT_LOCK_PREFIX,
+                ACCOUNT_FAILURES_PREFIX,
+                account,
+                config.lockout_duration,
+            )
+            .await?;
+            locked = Some(Lockout::Account);
+        }
+
+        Ok(FailureOutcome {
+            failures,
+            locked,
+            delay: failure_delay(failures, config),
+        })
+    }
+
+    /// 登录成功后清除账号的失败次数
+    pub async fn record_success(&self, account: &str) -> Result<()> {
+        self.cache.delete(&key(ACCOUNT_FAILURES_PREFIX, account)).await?;
+        Ok(())
+    }
+
+    /// 解除账号锁定
+    ///
+    /// # 返回值
+    ///
+    /// 账号处于锁定状态时返回true
+    pub async fn unlock(&self, account: &str) -> Result<bool> {
+        self.cache.delete(&key(ACCOUNT_FAILURES_PREFIX, account)).await?;
+        self.cache.delete(&key(ACCOUNT_LOCK_PREFIX, account)).await
+    }
+
+    async fn lock(&self, lock_prefix: &str, failures_prefix: &str, id: &str, duration: u64) -> Result<()> {
+        self.cache
+            .set(&key(lock_prefix, id), String::new(), Some(duration))
+            .await?;
+        self.cache.delete(&key(failures_prefix, id)).await?;
+        Ok(())
+    }
+}
+
+/// 第n次失败的响应延迟: `failure_delay_ms * 2^(n-1)`, 不超过`max_failure_delay_ms`
+fn failure_delay(failures: u64, config: &AuthConfig) -> Duration {
+    let exponent = failures.saturating_sub(1).min(16) as u32;
+    let delay = config.failure_delay_ms.saturating_mul(2u64.pow(exponent));
+
+    Duration::from_millis(delay.min(config.max_failure_delay_ms))
+}
+
+fn key(prefix: &str, id: &str) -> String {
+    format!("{}{}", prefix, id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libs::cache::memory::MemoryCache;
+
+    fn config() -> AuthConfig {
+        AuthConfig {
+            max_failed_attempts: 3,
+            max_failed_attempts_per_ip: 5,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lock_account_after_max_failures() {
+        let guard = LoginGuard::new(MemoryCache::new());
+        let config = config();
+
+        for failures in 1..3 {
+            let outcome = guard.record_failure("admin", "1.1.1.1", &config).await.unwrap();
+            assert_eq!(outcome.failures, failures);
+            assert!(outcome.locked.is_none());
+        }
+
+        let outcome = guard.record_failure("admin", "1.1.1.1", &config).await.unwrap();
+        assert_eq!(outcome.locked, Some(Lockout::Account));
+        assert_eq!(
+            guard.check("admin", "2.2.2.2").await.unwrap(),
+            Some(Lockout::Account)
+        );
+        assert_eq!(guard.check("other", "1.1.1.1").await.unwrap(), None);
+
+        assert!(guard.unlock("admin").await.unwrap());
+        assert_eq!(guard.check("admin", "1.1.1.1").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_lock_ip_across_accounts() {
+        let guard = LoginGuard::new(MemoryCache::new());
+        let config = config();
+
+        for i in 0..5 {
+            guard
+                .record_failure(&format!("user{}", i), "1.1.1.1", &config)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(guard.check("admin", "1.1.1.1").await.unwrap(), Some(Lockout::Ip));
+        assert_eq!(guard.check("admin", "2.2.2.2").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_success_resets_account_failures() {
+        let guard = LoginGuard::new(MemoryCache::new());
+        let config = config();
+
+        guard.record_failure("admin", "1.1.1.1", &config).await.unwrap();
+        guard.record_failure("admin", "1.1.1.1", &config).await.unwrap();
+        guard.record_success("admin").await.unwrap();
+
+        let outcome = guard.record_failure("admin", "1.1.1.1", &config).await.unwrap();
+        assert_eq!(outcome.failures, 1);
+    }
+
+    #[test]
+    fn test_failure_delay() {
+        let config = AuthConfig::default();
+        assert_eq!(failure_delay(1, &config), Duration::from_millis(500));
+        assert_eq!(failure_delay(2, &config), Duration::from_millis(1000));
+        assert_eq!(failure_delay(3, &config), Duration::from_millis(2000));
+        assert_eq!(failure_delay(10, &config), Duration::from_millis(5000));
+    }
+}
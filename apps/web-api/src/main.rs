@@ -1,19 +1,34 @@
 mod app_state;
 mod core;
 mod jwt;
+mod login_guard;
+mod oidc;
+mod session;
 
 use app_state::{AppState, DatabaseState};
-use config::SafeConfig;
+use clap::Parser;
+use config::{Args, Command, LogFormat, SafeConfig, SeedArgs};
+use container::ServiceFactory;
 use core::routes;
-use database::repositories::{AdminRepository, RoleRepository};
+use database::repositories::{AdminRepository, ApiKeyRepository, RoleRepository};
 use log::info;
 use rbac::ActorHandler;
+use services::admin::SeedSuperAdminParams;
+use std::{net::SocketAddr, time::Duration};
 
 #[tokio::main]
 async fn main() {
-    libs::logger::init();
+    let args = Args::parse();
+    let config = SafeConfig::from_parsed_args(&args)
+        .await
+        .expect("Failed to load config");
+
+    let log = config.get_config().await.expect("Failed to read config").log;
+    libs::logger::init(&log.level, log.format == LogFormat::Json);
 
-    let config = SafeConfig::from_args().await.expect("Failed to load config");
+    if let Some(Command::Seed(seed_args)) = args.command {
+        return seed(config, seed_args).await;
+    }
 
     info!(
         "Starting application with config: {}",
@@ -32,15 +47,82 @@ async fn start(cfg: SafeConfig) {
 
     let app_port = config.app.port;
 
-    let state = AppState::new(
-        DatabaseState::new(client, db.clone()),
-        cfg,
-        ActorHandler::new(RoleRepository::new(db.clone()), AdminRepository::new(db.clone())).await,
-    );
+    let migrated = AdminRepository::new(db.clone())
+        .migrate_role_names()
+        .await
+        .expect("Failed to migrate admin roles");
+    if migrated > 0 {
+        info!("Migrated role_name of {} admins to role_names", migrated);
+    }
+
+    let rbac = ActorHandler::new(
+        RoleRepository::new(db.clone()),
+        (
+            AdminRepository::new(db.clone()),
+            ApiKeyRepository::new(db.clone()),
+        ),
+    )
+    .await;
+    if config.rbac.reload_interval > 0 {
+        rbac.spawn_periodic_reload(Duration::from_secs(config.rbac.reload_interval));
+    }
+
+    database::cache::MongoCache::new(db.clone())
+        .create_indexes()
+        .await
+        .expect("Failed to create cache indexes");
+
+    // 监控指标在单独的地址上输出, 不经过 API 端口对外暴露
+    if config.metrics.enabled {
+        let addr = config
+            .metrics
+            .api_listen
+            .parse()
+            .expect("Invalid metrics listen address");
+        libs::metrics::serve(addr).expect("Failed to install metrics recorder");
+        info!("Serving metrics on {}", config.metrics.api_listen);
+    }
+
+    let state = AppState::new(DatabaseState::new(client, db.clone()), cfg, rbac);
 
     run_app(app_port, state).await
 }
 
+/// 初始化超级管理员角色和账号
+///
+/// 角色或账号已存在时跳过, 可以在部署脚本中重复执行。
+async fn seed(cfg: SafeConfig, args: SeedArgs) {
+    let config = cfg.get_config().await.unwrap();
+
+    let (_client, db) = database::mongodb::connect(&config.database.uri, &config.database.db_name)
+        .await
+        .expect("Failed to connect to database");
+
+    let report = ServiceFactory::new(db)
+        .admin_service()
+        .with_password_policy(config.password_policy)
+        .seed_super_admin(SeedSuperAdminParams {
+            account: args.account.clone(),
+            password: args.password,
+            name: args.name,
+            role_name: args.role.clone(),
+        })
+        .await
+        .expect("Failed to seed super admin");
+
+    if report.role_created {
+        info!("Created role {}", args.role);
+    } else {
+        info!("Role {} already exists, skipped", args.role);
+    }
+
+    if report.admin_created {
+        info!("Created admin {}", args.account);
+    } else {
+        info!("Admin {} already exists, skipped", args.account);
+    }
+}
+
 /// 启动应用程序并监听指定端口
 ///
 /// # 参数
@@ -63,5 +145,8 @@ async fn run_app(app_port: u16, state: AppState) {
         .await
         .expect("Failed to bind to port");
 
-    axum::serve(listener, app).await.expect("Failed to serve");
+    // 记录客户端地址, 供登录防护和操作日志使用
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .expect("Failed to serve");
 }
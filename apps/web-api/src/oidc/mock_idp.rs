@@ -0,0 +1,199 @@
+//! 测试用的本地身份提供方
+//!
+//! 提供服务发现、JWKS 和令牌端点, 使用固定的测试 RSA 密钥签发 ID 令牌。
+//! 测试中调用`authorize`模拟用户在身份提供方完成登录, 得到回调时的授权码。
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Form, Json, Router,
+};
+use config::OidcConfig;
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use reqwest::Url;
+use serde_json::{json, Value};
+
+use super::code_challenge;
+
+const CLIENT_ID: &str = "web-api";
+const CLIENT_SECRET: &str = "client-secret";
+const KEY_ID: &str = "test-key";
+const PRIVATE_KEY: &str = include_str!("test_key.pem");
+/// `test_key.pem` 的模数(base64url)
+const MODULUS: &str = "017R1OcvDv2ArtF_dhsySeA1_gksCYIREcRWit1walsrspYin6POu6J4ZuuZ6lDjd003wWNIvAoSMGs2hSh0pi2VWCMLVB26VcH_yYJOYd1zDsTbKtDAgcuSIVLNW-5EVXD6qBSplu6Ofd-Kvbrr4ZPsEsYnFb_U23byl6Ote67wwUnP7ByUzmq-Sd8xoS4SqgjOeGNFq8V6Fhr91bWunZyWCGpgsfmKw34jl3bCfgOBqVrif1_VsPOpesPizQjF8fk85ge_F9cAcJ4Xpmi42dVm8x0q3_fvgP2Qk-TrS9kxXM5eG_y7yekqasmsebX2JSzOW5I47gfEGvq3j09TcQ";
+
+/// 等待换取令牌的授权码
+struct PendingCode {
+    code_challenge: String,
+    claims: Value,
+}
+
+#[derive(Clone)]
+pub struct MockIdp {
+    issuer: String,
+    codes: Arc<Mutex<HashMap<String, PendingCode>>>,
+    jwks_requests: Arc<AtomicUsize>,
+}
+
+impl MockIdp {
+    /// 在随机端口启动身份提供方
+    pub async fn start() -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let idp = Self {
+            issuer: format!("http://{}", listener.local_addr().unwrap()),
+            codes: Arc::new(Mutex::new(HashMap::new())),
+            jwks_requests: Arc::new(AtomicUsize::new(0)),
+        };
+
+        let app = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/jwks", get(jwks))
+            .route("/token", post(token))
+            .with_state(idp.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        idp
+    }
+
+    pub fn issuer(&self) -> String {
+        self.issuer.clone()
+    }
+
+    /// JWKS 端点被请求的次数
+    pub fn jwks_requests(&self) -> usize {
+        self.jwks_requests.load(Ordering::SeqCst)
+    }
+
+    /// 指向该身份提供方的单点登录配置
+    pub fn config(&self) -> OidcConfig {
+        OidcConfig {
+            enabled: true,
+            issuer: self.issuer(),
+            client_id: CLIENT_ID.to_string(),
+            client_secret: CLIENT_SECRET.to_string(),
+            redirect_uri: "http://localhost:8000/sso/callback".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// 模拟用户在身份提供方完成登录, 返回授权码
+    ///
+    /// # 参数
+    ///
+    /// * `authorization_url` - 客户端生成的授权地址
+    /// * `claims` - 签发的 ID 令牌中的用户声明
+    pub fn authorize(&self, authorization_url: &str, mut claims: Value) -> String {
+        let url = Url::parse(authorization_url).unwrap();
+        let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        assert_eq!(params["client_id"], CLIENT_ID);
+        assert_eq!(params["code_challenge_method"], "S256");
+
+        claims["nonce"] = json!(params["nonce"]);
+
+        let code = libs::random_token(16);
+        self.codes.lock().unwrap().insert(
+            code.clone(),
+            PendingCode {
+                code_challenge: params["code_challenge"].clone(),
+                claims,
+            },
+        );
+
+        code
+    }
+
+    /// 签发 ID 令牌, 未指定的 iss、aud、iat 和 exp 使用默认值
+    pub fn sign(&self, claims: Value) -> String {
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(KEY_ID.to_string());
+        self.sign_with_header(header, claims)
+    }
+
+    /// 使用指定的请求头签发 ID 令牌, 例如模拟轮换后的 `kid`
+    pub fn sign_with_header(&self, header: Header, mut claims: Value) -> String {
+        let now = chrono::Utc::now().timestamp();
+        let defaults = [
+            ("iss", json!(self.issuer)),
+            ("aud", json!(CLIENT_ID)),
+            ("iat", json!(now)),
+            ("exp", json!(now + 300)),
+        ];
+        for (name, value) in defaults {
+            if claims.get(name).is_none() {
+                claims[name] = value;
+            }
+        }
+
+        let key = EncodingKey::from_rsa_pem(PRIVATE_KEY.as_bytes()).unwrap();
+
+        jsonwebtoken::encode(&header, &claims, &key).unwrap()
+    }
+}
+
+async fn discovery(State(idp): State<MockIdp>) -> Json<Value> {
+    Json(json!({
+        "issuer": idp.issuer,
+        "authorization_endpoint": format!("{}/authorize", idp.issuer),
+        "token_endpoint": format!("{}/token", idp.issuer),
+        "jwks_uri": format!("{}/jwks", idp.issuer),
+    }))
+}
+
+async fn jwks(State(idp): State<MockIdp>) -> Json<Value> {
+    idp.jwks_requests.fetch_add(1, Ordering::SeqCst);
+    Json(json!({
+        "keys": [{
+            "kty": "RSA",
+            "kid": KEY_ID,
+            "use": "sig",
+            "alg": "RS256",
+            "n": MODULUS,
+            "e": "AQAB",
+        }]
+    }))
+}
+
+async fn token(
+    State(idp): State<MockIdp>,
+    headers: HeaderMap,
+    Form(form): Form<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let expected = format!(
+        "Basic {}",
+        data_encoding::BASE64.encode(format!("{}:{}", CLIENT_ID, CLIENT_SECRET).as_bytes())
+    );
+    if headers.get("authorization").and_then(|v| v.to_str().ok()) != Some(expected.as_str()) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "invalid_client" })),
+        );
+    }
+
+    let pending = idp.codes.lock().unwrap().remove(&form["code"]);
+    let Some(pending) = pending else {
+        return (StatusCode::BAD_REQUEST, Json(json!({ "error": "invalid_grant" })));
+    };
+
+    if code_challenge(&form["code_verifier"]) != pending.code_challenge {
+        return (StatusCode::BAD_REQUEST, Json(json!({ "error": "invalid_grant" })));
+    }
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "access_token": libs::random_token(16),
+            "token_type": "Bearer",
+            "id_token": idp.sign(pending.claims),
+        })),
+    )
+}
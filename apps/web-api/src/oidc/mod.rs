@@ -0,0 +1,488 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use config::OidcConfig;
+use data_encoding::BASE64URL_NOPAD;
+use jsonwebtoken::{
+    jwk::{Jwk, JwkSet},
+    Algorithm, DecodingKey, Validation,
+};
+use reqwest::Url;
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+#[cfg(test)]
+pub mod mock_idp;
+
+/// ID 令牌允许使用的签名算法, 只接受非对称算法
+const ALLOWED_ALGORITHMS: [Algorithm; 6] = [
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::ES256,
+    Algorithm::ES384,
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("未启用单点登录")]
+    Disabled,
+
+    #[error("请求身份提供方失败: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("身份提供方配置错误: {0}")]
+    Discovery(String),
+
+    #[error("换取令牌失败: {0}")]
+    TokenExchange(String),
+
+    #[error("ID 令牌无效: {0}")]
+    InvalidIdToken(String),
+}
+
+impl From<jsonwebtoken::errors::Error> for Error {
+    fn from(err: jsonwebtoken::errors::Error) -> Self {
+        Error::InvalidIdToken(err.to_string())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// 服务发现得到的身份提供方配置
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+/// 跳转到身份提供方的登录请求
+///
+/// # 字段
+///
+/// * `url` - 身份提供方的授权地址
+/// * `state` - 防止跨站请求伪造的随机值
+/// * `nonce` - 绑定 ID 令牌的随机值, 防止令牌重放
+/// * `code_verifier` - PKCE 校验码, 换取令牌时使用
+#[derive(Debug, Clone)]
+pub struct AuthorizationRequest {
+    pub url: String,
+    pub state: String,
+    pub nonce: String,
+    pub code_verifier: String,
+}
+
+/// ID 令牌中的用户身份
+#[derive(Debug, Clone)]
+pub struct OidcIdentity {
+    pub issuer: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub name: Option<String>,
+    pub groups: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    iss: String,
+    sub: String,
+    nonce: Option<String>,
+    email: Option<String>,
+    email_verified: Option<bool>,
+    name: Option<String>,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+/// 身份提供方签名公钥的缓存, 在多次登录之间共享
+///
+/// 按 `kid` 查找公钥, 找不到时(例如身份提供方轮换了密钥)重新读取 JWKS;
+/// JWKS 地址变化时丢弃之前缓存的公钥
+#[derive(Clone, Default)]
+pub struct JwksCache {
+    inner: Arc<RwLock<CachedKeys>>,
+}
+
+#[derive(Default)]
+struct CachedKeys {
+    jwks_uri: String,
+    keys: HashMap<String, Jwk>,
+}
+
+impl JwksCache {
+    fn get(&self, jwks_uri: &str, kid: &str) -> Option<Jwk> {
+        let cached = self.inner.read().ok()?;
+        if cached.jwks_uri != jwks_uri {
+            return None;
+        }
+        cached.keys.get(kid).cloned()
+    }
+
+    fn replace(&self, jwks_uri: &str, jwks: &JwkSet) {
+        if let Ok(mut cached) = self.inner.write() {
+            cached.jwks_uri = jwks_uri.to_string();
+            cached.keys = jwks
+                .keys
+                .iter()
+                .filter_map(|jwk| Some((jwk.common.key_id.clone()?, jwk.clone())))
+                .collect();
+        }
+    }
+}
+
+/// OIDC 授权码模式客户端
+///
+/// 登录流程:
+/// 1. `discover` 读取身份提供方配置
+/// 2. `authorization_request` 生成跳转地址, 保存 state、nonce 和 PKCE 校验码
+/// 3. 身份提供方回调后, `exchange_code` 使用授权码换取 ID 令牌
+/// 4. `verify_id_token` 使用 JWKS 校验 ID 令牌的签名、签发方、受众、有效期和 nonce
+#[derive(Clone)]
+pub struct OidcClient {
+    config: OidcConfig,
+    http: reqwest::Client,
+    keys: JwksCache,
+}
+
+impl OidcClient {
+    pub fn new(config: OidcConfig) -> Result<Self> {
+        if !config.enabled {
+            return Err(Error::Disabled);
+        }
+
+        Ok(Self {
+            config,
+            http: reqwest::Client::new(),
+            keys: JwksCache::default(),
+        })
+    }
+
+    /// 使用共享的签名公钥缓存, 避免每次登录都读取 JWKS
+    pub fn with_jwks_cache(mut self, keys: JwksCache) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    /// 读取身份提供方的`/.well-known/openid-configuration`
+    pub async fn discover(&self) -> Result<ProviderMetadata> {
+        let url = format!(
+            "{}/.well-known/openid-configuration",
+            self.config.issuer.trim_end_matches('/')
+        );
+        let metadata: ProviderMetadata = self
+            .http
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if metadata.issuer.trim_end_matches('/') != self.config.issuer.trim_end_matches('/') {
+            return Err(Error::Discovery(format!("签发方不匹配: {}", metadata.issuer)));
+        }
+
+        Ok(metadata)
+    }
+
+    /// 生成跳转到身份提供方的登录请求
+    pub fn authorization_request(&self, metadata: &ProviderMetadata) -> Result<AuthorizationRequest> {
+        let state = libs::random_token(16);
+        let nonce = libs::random_token(16);
+        let code_verifier = libs::random_token(32);
+
+        let mut url = Url::parse(&metadata.authorization_endpoint)
+            .map_err(|e| Error::Discovery(format!("授权地址格式不正确: {}", e)))?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.config.client_id)
+            .append_pair("redirect_uri", &self.config.redirect_uri)
+            .append_pair("scope", &self.config.scopes.join(" "))
+            .append_pair("state", &state)
+            .append_pair("nonce", &nonce)
+            .append_pair("code_challenge", &code_challenge(&code_verifier))
+            .append_pair("code_challenge_method", "S256");
+
+        Ok(AuthorizationRequest {
+            url: url.to_string(),
+            state,
+            nonce,
+            code_verifier,
+        })
+    }
+
+    /// 使用授权码换取 ID 令牌
+    pub async fn exchange_code(
+        &self,
+        metadata: &ProviderMetadata,
+        code: &str,
+        code_verifier: &str,
+    ) -> Result<String> {
+        let response = self
+            .http
+            .post(&metadata.token_endpoint)
+            .basic_auth(&self.config.client_id, Some(&self.config.client_secret))
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", &self.config.redirect_uri),
+                ("code_verifier", code_verifier),
+            ])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::TokenExchange(format!("{} {}", status, body)));
+        }
+
+        let token: TokenResponse = response.json().await?;
+        token
+            .id_token
+            .ok_or_else(|| Error::TokenExchange("响应中没有 ID 令牌".to_string()))
+    }
+
+    /// 校验 ID 令牌并读取用户身份
+    ///
+    /// # 参数
+    ///
+    /// * `metadata` - 身份提供方配置
+    /// * `id_token` - ID 令牌
+    /// * `nonce` - 发起登录时生成的 nonce
+    pub async fn verify_id_token(
+        &self,
+        metadata: &ProviderMetadata,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<OidcIdentity> {
+        let header = jsonwebtoken::decode_header(id_token)?;
+        if !ALLOWED_ALGORITHMS.contains(&header.alg) {
+            return Err(Error::InvalidIdToken(format!(
+                "不支持的签名算法: {:?}",
+                header.alg
+            )));
+        }
+
+        let jwk = self.signing_key(metadata, header.kid.as_deref()).await?;
+        let key = DecodingKey::from_jwk(&jwk)?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&metadata.issuer]);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let claims = jsonwebtoken::decode::<IdTokenClaims>(id_token, &key, &validation)?.claims;
+
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(Error::InvalidIdToken("nonce 不匹配".to_string()));
+        }
+
+        let groups = match claims.extra.get(&self.config.groups_claim) {
+            Some(Value::Array(groups)) => groups
+                .iter()
+                .filter_map(|group| group.as_str().map(str::to_string))
+                .collect(),
+            Some(Value::String(group)) => vec![group.clone()],
+            _ => vec![],
+        };
+
+        Ok(OidcIdentity {
+            issuer: claims.iss,
+            subject: claims.sub,
+            email: claims.email,
+            email_verified: claims.email_verified.unwrap_or(false),
+            name: claims.name,
+            groups,
+        })
+    }
+
+    /// 查找 ID 令牌的签名公钥, 缓存中没有时重新读取 JWKS
+    ///
+    /// 没有 `kid` 的令牌总是重新读取, 使用 JWKS 中的第一个公钥
+    async fn signing_key(&self, metadata: &ProviderMetadata, kid: Option<&str>) -> Result<Jwk> {
+        if let Some(jwk) = kid.and_then(|kid| self.keys.get(&metadata.jwks_uri, kid)) {
+            return Ok(jwk);
+        }
+
+        let jwks: JwkSet = self
+            .http
+            .get(&metadata.jwks_uri)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        self.keys.replace(&metadata.jwks_uri, &jwks);
+
+        match kid {
+            Some(kid) => jwks.find(kid),
+            None => jwks.keys.first(),
+        }
+        .cloned()
+        .ok_or_else(|| Error::InvalidIdToken("找不到签名公钥".to_string()))
+    }
+}
+
+/// PKCE S256 校验码的摘要
+fn code_challenge(code_verifier: &str) -> String {
+    BASE64URL_NOPAD.encode(&Sha256::digest(code_verifier.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::mock_idp::MockIdp;
+    use super::*;
+
+    async fn client(idp: &MockIdp) -> (OidcClient, ProviderMetadata) {
+        let client = OidcClient::new(idp.config()).unwrap();
+        let metadata = client.discover().await.unwrap();
+        (client, metadata)
+    }
+
+    #[tokio::test]
+    async fn test_authorization_code_flow() {
+        let idp = MockIdp::start().await;
+        let (client, metadata) = client(&idp).await;
+
+        let request = client.authorization_request(&metadata).unwrap();
+        let code = idp.authorize(
+            &request.url,
+            json!({
+                "sub": "user-1",
+                "email": "alice@example.com",
+                "email_verified": true,
+                "name": "Alice",
+                "groups": ["engineering", "admins"],
+            }),
+        );
+
+        let id_token = client
+            .exchange_code(&metadata, &code, &request.code_verifier)
+            .await
+            .unwrap();
+        let identity = client
+            .verify_id_token(&metadata, &id_token, &request.nonce)
+            .await
+            .unwrap();
+
+        assert_eq!(identity.issuer, idp.issuer());
+        assert_eq!(identity.subject, "user-1");
+        assert_eq!(identity.email.as_deref(), Some("alice@example.com"));
+        assert!(identity.email_verified);
+        assert_eq!(identity.groups, vec!["engineering", "admins"]);
+
+        // 授权码只能使用一次
+        assert!(client
+            .exchange_code(&metadata, &code, &request.code_verifier)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reject_wrong_code_verifier() {
+        let idp = MockIdp::start().await;
+        let (client, metadata) = client(&idp).await;
+
+        let request = client.authorization_request(&metadata).unwrap();
+        let code = idp.authorize(&request.url, json!({ "sub": "user-1" }));
+
+        assert!(client
+            .exchange_code(&metadata, &code, "wrong-verifier")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reject_invalid_id_token() {
+        let idp = MockIdp::start().await;
+        let (client, metadata) = client(&idp).await;
+        let claims = json!({ "sub": "user-1", "nonce": "nonce" });
+
+        let valid = idp.sign(claims.clone());
+        assert!(client.verify_id_token(&metadata, &valid, "nonce").await.is_ok());
+        assert!(client.verify_id_token(&metadata, &valid, "other").await.is_err());
+
+        let mut wrong_audience = claims.clone();
+        wrong_audience["aud"] = json!("other-client");
+        let token = idp.sign(wrong_audience);
+        assert!(client.verify_id_token(&metadata, &token, "nonce").await.is_err());
+
+        let mut expired = claims;
+        expired["exp"] = json!(chrono::Utc::now().timestamp() - 3600);
+        let token = idp.sign(expired);
+        assert!(client.verify_id_token(&metadata, &token, "nonce").await.is_err());
+
+        // 使用客户端密钥签名的对称算法令牌不被接受
+        let header = jsonwebtoken::Header::new(Algorithm::HS256);
+        let token = jsonwebtoken::encode(
+            &header,
+            &json!({ "sub": "user-1", "nonce": "nonce", "iss": idp.issuer(), "aud": "web-api" }),
+            &jsonwebtoken::EncodingKey::from_secret(b"client-secret"),
+        )
+        .unwrap();
+        assert!(client.verify_id_token(&metadata, &token, "nonce").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_jwks_cache() {
+        let idp = MockIdp::start().await;
+        let (first, metadata) = client(&idp).await;
+        let keys = JwksCache::default();
+        let first = first.with_jwks_cache(keys.clone());
+        let token = idp.sign(json!({ "sub": "user-1", "nonce": "nonce" }));
+
+        assert!(first.verify_id_token(&metadata, &token, "nonce").await.is_ok());
+        assert_eq!(idp.jwks_requests(), 1);
+
+        // 缓存在客户端之间共享
+        let (other, _) = client(&idp).await;
+        let other = other.with_jwks_cache(keys);
+        assert!(other.verify_id_token(&metadata, &token, "nonce").await.is_ok());
+        assert_eq!(idp.jwks_requests(), 1);
+
+        // 未知的 kid 重新读取 JWKS
+        let mut header = jsonwebtoken::Header::new(Algorithm::RS256);
+        header.kid = Some("rotated-key".to_string());
+        let token = idp.sign_with_header(header, json!({ "sub": "user-1", "nonce": "nonce" }));
+        assert!(first.verify_id_token(&metadata, &token, "nonce").await.is_err());
+        assert_eq!(idp.jwks_requests(), 2);
+    }
+
+    #[test]
+    fn test_map_roles() {
+        let mut config = OidcConfig::default();
+        config.role_mappings = vec![
+            config::OidcRoleMapping {
+                group: "admins".to_string(),
+                role: "super_admin".to_string(),
+            },
+            config::OidcRoleMapping {
+                group: "engineering".to_string(),
+                role: "developer".to_string(),
+            },
+        ];
+
+        let groups = vec!["engineering".to_string(), "admins".to_string()];
+        assert_eq!(config.map_roles(&groups), vec!["super_admin", "developer"]);
+        assert!(config.map_roles(&[]).is_empty());
+
+        config.default_role = "viewer".to_string();
+        assert_eq!(config.map_roles(&[]), vec!["viewer"]);
+        assert_eq!(config.map_roles(&groups[..1]), vec!["developer"]);
+    }
+}
@@ -0,0 +1,329 @@
+use chrono::Utc;
+use libs::cache::{Cache, CacheError, CacheWithJson, CacheWithString};
+use serde::{Deserialize, Serialize};
+
+use crate::jwt::TokenPayload;
+
+const REFRESH_TOKEN_PREFIX: &str = "auth:refresh:";
+const REVOKED_TOKEN_PREFIX: &str = "auth:revoked:token:";
+const REVOKED_ADMIN_PREFIX: &str = "auth:revoked:admin:";
+const PRE_AUTH_TOKEN_PREFIX: &str = "auth:pre-auth:";
+const PRE_AUTH_FAILURES_PREFIX: &str = "auth:pre-auth:failures:";
+const OIDC_STATE_PREFIX: &str = "auth:oidc:state:";
+
+/// 单个预登录令牌允许的双因素认证失败次数
+const MAX_PRE_AUTH_FAILURES: u64 = 5;
+
+pub type Result<T> = std::result::Result<T, CacheError>;
+
+/// 服务端保存的刷新令牌信息
+///
+/// # 字段
+///
+/// * `admin_id` - 令牌所属的管理员ID
+/// * `issued_at` - 签发时间(毫秒级时间戳)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefreshSession {
+    pub admin_id: String,
+    pub issued_at: i64,
+}
+
+/// 发起单点登录时保存的校验信息
+///
+/// # 字段
+///
+/// * `nonce` - 绑定 ID 令牌的随机值
+/// * `code_verifier` - PKCE 校验码
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcLoginState {
+    pub nonce: String,
+    pub code_verifier: String,
+}
+
+/// 登录会话存储
+///
+/// 保存刷新令牌和令牌吊销列表:
+/// - 刷新令牌只能使用一次, 使用后换发新的刷新令牌
+/// - 退出登录时吊销当前访问令牌
+/// - 禁用管理员或修改密码时吊销该管理员此前签发的所有令牌
+#[derive(Clone)]
+pub struct SessionStore<C: Cache> {
+    cache: C,
+}
+
+impl<C: Cache> SessionStore<C> {
+    pub fn new(cache: C) -> Self {
+        Self { cache }
+    }
+
+    /// 签发刷新令牌
+    ///
+    /// # 参数
+    ///
+    /// * `admin_id` - 管理员ID
+    /// * `ttl` - 有效期(秒)
+    pub async fn issue_refresh_token(&self, admin_id: &str, ttl: u64) -> Result<String> {
+        let token = libs::random_token(32);
+        let session = RefreshSession {
+            admin_id: admin_id.to_string(),
+            issued_at: Utc::now().timestamp_millis(),
+        };
+
+        self.cache
+            .set_json(&refresh_key(&token), &session, Some(ttl))
+            .await?;
+
+        Ok(token)
+    }
+
+    /// 使用刷新令牌
+    ///
+    /// 令牌在读取后立即删除, 同一个刷新令牌只能成功使用一次。
+    /// 令牌不存在、已被使用或签发后管理员的令牌被整体吊销时返回None
+    pub async fn consume_refresh_token(&self, token: &str) -> Result<Option<RefreshSession>> {
+        let key = refresh_key(token);
+
+        let session: Option<RefreshSession> = self.cache.get_json(&key).await?;
+        let Some(session) = session else {
+            return Ok(None);
+        };
+
+        // 删除失败说明令牌已被并发的请求使用
+        if !self.cache.delete(&key).await? {
+            return Ok(None);
+        }
+
+        if self
+            .is_revoked_since(&session.admin_id, session.issued_at)
+            .await?
+        {
+            return Ok(None);
+        }
+
+        Ok(Some(session))
+    }
+
+    /// 签发预登录令牌
+    ///
+    /// 密码验证通过但还需要双因素认证时使用, 只能用于完成双因素认证
+    ///
+    /// # 参数
+    ///
+    /// * `admin_id` - 管理员ID
+    /// * `ttl` - 有效期(秒)
+    pub async fn issue_pre_auth_token(&self, admin_id: &str, ttl: u64) -> Result<String> {
+        let token = libs::random_token(32);
+        self.cache
+            .set(&pre_auth_key(&token), admin_id.to_string(), Some(ttl))
+            .await?;
+
+        Ok(token)
+    }
+
+    /// 查询预登录令牌对应的管理员ID
+    pub async fn find_pre_auth_token(&self, token: &str) -> Result<Option<String>> {
+        self.cache.get(&pre_auth_key(token)).await
+    }
+
+    /// 记录一次双因素认证失败, 失败次数达到上限后令牌失效
+    ///
+    /// # 返回值
+    ///
+    /// 令牌已失效时返回true
+    pub async fn record_pre_auth_failure(&self, token: &str, ttl: u64) -> Result<bool> {
+        let failures = self
+            .cache
+            .incr(&format!("{}{}", PRE_AUTH_FAILURES_PREFIX, token), Some(ttl))
+            .await?;
+
+        if failures >= MAX_PRE_AUTH_FAILURES {
+            self.revoke_pre_auth_token(token).await?;
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// 删除预登录令牌
+    pub async fn revoke_pre_auth_token(&self, token: &str) -> Result<()> {
+        self.cache.delete(&pre_auth_key(token)).await?;
+        self.cache
+            .delete(&format!("{}{}", PRE_AUTH_FAILURES_PREFIX, token))
+            .await?;
+        Ok(())
+    }
+
+    /// 保存单点登录请求, 以 state 为键
+    ///
+    /// # 参数
+    ///
+    /// * `state` - 跳转到身份提供方时携带的 state
+    /// * `login` - 回调时需要的校验信息
+    /// * `ttl` - 有效期(秒)
+    pub async fn save_oidc_state(&self, state: &str, login: &OidcLoginState, ttl: u64) -> Result<()> {
+        self.cache
+            .set_json(&oidc_state_key(state), login, Some(ttl))
+            .await
+    }
+
+    /// 读取并删除单点登录请求, 同一个 state 只能使用一次
+    pub async fn consume_oidc_state(&self, state: &str) -> Result<Option<OidcLoginState>> {
+        let key = oidc_state_key(state);
+
+        let login: Option<OidcLoginState> = self.cache.get_json(&key).await?;
+        if login.is_none() || !self.cache.delete(&key).await? {
+            return Ok(None);
+        }
+
+        Ok(login)
+    }
+
+    /// 删除刷新令牌
+    pub async fn revoke_refresh_token(&self, token: &str) -> Result<()> {
+        self.cache.delete(&refresh_key(token)).await?;
+        Ok(())
+    }
+
+    /// 吊销单个访问令牌, 记录保留到令牌过期为止
+    ///
+    /// # 参数
+    ///
+    /// * `jti` - 令牌唯一标识
+    /// * `expires_at` - 令牌过期时间(秒级时间戳)
+    pub async fn revoke_token(&self, jti: &str, expires_at: i64) -> Result<()> {
+        let ttl = (expires_at - Utc::now().timestamp()).max(1) as u64;
+        self.cache
+            .set(&revoked_token_key(jti), String::new(), Some(ttl))
+            .await
+    }
+
+    /// 吊销管理员此前签发的所有令牌
+    ///
+    /// # 参数
+    ///
+    /// * `admin_id` - 管理员ID
+    /// * `ttl` - 记录保留时间(秒), 不应短于刷新令牌的有效期
+    pub async fn revoke_admin(&self, admin_id: &str, ttl: u64) -> Result<()> {
+        self.cache
+            .set(
+                &revoked_admin_key(admin_id),
+                Utc::now().timestamp_millis().to_string(),
+                Some(ttl),
+            )
+            .await
+    }
+
+    /// 访问令牌是否已被吊销
+    pub async fn is_token_revoked(&self, payload: &TokenPayload) -> Result<bool> {
+        if self.cache.exists(&revoked_token_key(&payload.jti)).await? {
+            return Ok(true);
+        }
+
+        self.is_revoked_since(&payload.id, payload.issued_at).await
+    }
+
+    async fn is_revoked_since(&self, admin_id: &str, issued_at: i64) -> Result<bool> {
+        let key = revoked_admin_key(admin_id);
+        if !self.cache.exists(&key).await? {
+            return Ok(false);
+        }
+
+        let revoked_at = self
+            .cache
+            .get(&key)
+            .await?
+            .and_then(|value| value.parse::<i64>().ok())
+            .unwrap_or_default();
+
+        Ok(issued_at <= revoked_at)
+    }
+}
+
+fn refresh_key(token: &str) -> String {
+    format!("{}{}", REFRESH_TOKEN_PREFIX, token)
+}
+
+fn pre_auth_key(token: &str) -> String {
+    format!("{}{}", PRE_AUTH_TOKEN_PREFIX, token)
+}
+
+fn oidc_state_key(state: &str) -> String {
+    format!("{}{}", OIDC_STATE_PREFIX, state)
+}
+
+fn revoked_token_key(jti: &str) -> String {
+    format!("{}{}", REVOKED_TOKEN_PREFIX, jti)
+}
+
+fn revoked_admin_key(admin_id: &str) -> String {
+    format!("{}{}", REVOKED_ADMIN_PREFIX, admin_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libs::cache::memory::MemoryCache;
+
+    #[tokio::test]
+    async fn test_refresh_token_rotation() {
+        let store = SessionStore::new(MemoryCache::new());
+        let token = store.issue_refresh_token("1", 60).await.unwrap();
+
+        let session = store.consume_refresh_token(&token).await.unwrap().unwrap();
+        assert_eq!(session.admin_id, "1");
+
+        // 已使用的刷新令牌不能再次使用
+        assert!(store.consume_refresh_token(&token).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pre_auth_token_failures() {
+        let store = SessionStore::new(MemoryCache::new());
+        let token = store.issue_pre_auth_token("1", 60).await.unwrap();
+        assert_eq!(
+            store.find_pre_auth_token(&token).await.unwrap(),
+            Some("1".to_string())
+        );
+
+        for _ in 1..MAX_PRE_AUTH_FAILURES {
+            assert!(!store.record_pre_auth_failure(&token, 60).await.unwrap());
+        }
+        assert!(store.record_pre_auth_failure(&token, 60).await.unwrap());
+        assert_eq!(store.find_pre_auth_token(&token).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_token() {
+        let store = SessionStore::new(MemoryCache::new());
+        let mut payload = TokenPayload::new("1".to_string(), "admin".to_string(), "admin".to_string());
+        payload.jti = "jti".to_string();
+        payload.issued_at = Utc::now().timestamp_millis();
+        payload.expires_at = Utc::now().timestamp() + 60;
+
+        assert!(!store.is_token_revoked(&payload).await.unwrap());
+
+        store
+            .revoke_token(&payload.jti, payload.expires_at)
+            .await
+            .unwrap();
+        assert!(store.is_token_revoked(&payload).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_revoke_admin() {
+        let store = SessionStore::new(MemoryCache::new());
+        let token = store.issue_refresh_token("1", 60).await.unwrap();
+
+        let mut payload = TokenPayload::new("1".to_string(), "admin".to_string(), "admin".to_string());
+        payload.jti = "jti".to_string();
+        payload.issued_at = Utc::now().timestamp_millis() - 10;
+
+        store.revoke_admin("1", 60).await.unwrap();
+
+        assert!(store.is_token_revoked(&payload).await.unwrap());
+        assert!(store
+            .consume_refresh_token(&token)
+            .await
+            .is_ok_and(|s| s.is_none()));
+    }
+}
@@ -1,4 +1,4 @@
-use clap::{command, Parser};
+use clap::{Args as ClapArgs, Parser, Subcommand};
 
 use crate::nacos::NacosConfig;
 
@@ -28,6 +28,35 @@ pub struct Args {
     /// Nacos 数据ID
     #[arg(long)]
     nacos_data_id: Option<String>,
+
+    /// 子命令, 不指定时正常启动服务
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// 初始化超级管理员角色和账号, 已存在时跳过
+    Seed(SeedArgs),
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+pub struct SeedArgs {
+    /// 超级管理员账号
+    #[arg(long, env = "SEED_ADMIN_ACCOUNT")]
+    pub account: String,
+
+    /// 超级管理员密码
+    #[arg(long, env = "SEED_ADMIN_PASSWORD", hide_env_values = true)]
+    pub password: String,
+
+    /// 超级管理员名称
+    #[arg(long, env = "SEED_ADMIN_NAME", default_value = "超级管理员")]
+    pub name: String,
+
+    /// 超级管理员角色名称
+    #[arg(long, env = "SEED_ADMIN_ROLE", default_value = "super_admin")]
+    pub role: String,
 }
 
 impl Args {
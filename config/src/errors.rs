@@ -9,7 +9,13 @@ pub enum Error {
     Toml(#[from] toml::de::Error),
 
     #[error("Nacos error: {0}")]
-    Nacos(#[from] nacos_sdk::api::error::Error),
+    Nacos(Box<nacos_sdk::api::error::Error>),
+}
+
+impl From<nacos_sdk::api::error::Error> for Error {
+    fn from(err: nacos_sdk::api::error::Error) -> Self {
+        Error::Nacos(Box::new(err))
+    }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
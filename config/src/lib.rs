@@ -41,9 +41,12 @@
 //! * `Config`: 顶层配置容器
 //! * `AppConfig`: 应用程序特定设置
 //! * `DatabaseConfig`: 数据库连接设置
+//! * `PasswordPolicy`: 密码策略,未配置时使用默认值
+//! * `AuthConfig`: 登录令牌设置,未配置时使用默认值
+//! * `OidcConfig`: OIDC 单点登录设置,未配置时不启用
 
 use clap::Parser;
-use command::Args;
+use entities::PasswordPolicy;
 use nacos::NacosConfig;
 use nacos_watch::NacosConfigWatcher;
 use serde::Deserialize;
@@ -54,6 +57,7 @@ mod command;
 mod errors;
 mod nacos;
 mod nacos_watch;
+pub use command::{Args, Command, SeedArgs};
 pub use errors::*;
 pub use nacos::NacosConfigClient;
 
@@ -67,6 +71,24 @@ pub struct Config {
     pub app: AppConfig,
     /// 数据库特定配置
     pub database: DatabaseConfig,
+    /// 密码策略配置
+    #[serde(default)]
+    pub password_policy: PasswordPolicy,
+    /// 登录令牌配置
+    #[serde(default)]
+    pub auth: AuthConfig,
+    /// OIDC 单点登录配置
+    #[serde(default)]
+    pub oidc: OidcConfig,
+    /// 权限策略配置
+    #[serde(default)]
+    pub rbac: RbacConfig,
+    /// 日志配置
+    #[serde(default)]
+    pub log: LogConfig,
+    /// 监控指标配置
+    #[serde(default)]
+    pub metrics: MetricsConfig,
 }
 
 /// 应用程序特定的配置设置。
@@ -80,6 +102,14 @@ pub struct AppConfig {
     pub secret: String,
     /// 上传文件存储的基础路径
     pub upload_path: String,
+    /// 兼容 antd-pro 的响应格式, 启用后所有响应的 HTTP 状态码都是 200,
+    /// 错误只通过响应体中的 `status` 和 `errorCode` 字段表示
+    #[serde(default)]
+    pub antd_compat: bool,
+    /// 受信任的反向代理地址(IP 或 CIDR), 只有来自这些地址的请求才按
+    /// `X-Forwarded-For` / `Forwarded` 请求头解析客户端 IP
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
 }
 
 /// 数据库连接配置。
@@ -93,6 +123,213 @@ pub struct DatabaseConfig {
     pub db_name: String,
 }
 
+/// 登录令牌与登录防护配置。
+///
+/// 访问令牌有效期较短, 过期后使用刷新令牌换取新的令牌。
+/// 同一账号或同一 IP 在统计窗口内连续登录失败达到上限后临时锁定。
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct AuthConfig {
+    /// 访问令牌有效期(秒)
+    pub access_token_ttl: u64,
+    /// 刷新令牌有效期(秒)
+    pub refresh_token_ttl: u64,
+    /// 单个账号允许的连续登录失败次数
+    pub max_failed_attempts: u64,
+    /// 单个 IP 允许的登录失败次数
+    pub max_failed_attempts_per_ip: u64,
+    /// 登录失败次数的统计窗口(秒)
+    pub failed_attempt_window: u64,
+    /// 达到上限后的锁定时长(秒)
+    pub lockout_duration: u64,
+    /// 每次登录失败后递增的响应延迟(毫秒)
+    pub failure_delay_ms: u64,
+    /// 登录失败响应延迟的上限(毫秒)
+    pub max_failure_delay_ms: u64,
+    /// 双因素认证预登录令牌的有效期(秒)
+    pub pre_auth_token_ttl: u64,
+    /// 显示在认证器应用中的签发方名称
+    pub totp_issuer: String,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            access_token_ttl: 15 * 60,
+            refresh_token_ttl: 7 * 24 * 60 * 60,
+            max_failed_attempts: 5,
+            max_failed_attempts_per_ip: 20,
+            failed_attempt_window: 15 * 60,
+            lockout_duration: 15 * 60,
+            failure_delay_ms: 500,
+            max_failure_delay_ms: 5000,
+            pre_auth_token_ttl: 5 * 60,
+            totp_issuer: "Admin".to_string(),
+        }
+    }
+}
+
+/// 权限策略配置。
+///
+/// 角色和管理员变更时增量更新内存中的权限策略, 并定期从数据库全量加载作为一致性检查。
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct RbacConfig {
+    /// 全量加载权限策略的间隔(秒), 为 0 时不定期加载
+    pub reload_interval: u64,
+}
+
+impl Default for RbacConfig {
+    fn default() -> Self {
+        Self {
+            reload_interval: 5 * 60,
+        }
+    }
+}
+
+/// 日志输出格式。
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// 便于阅读的文本格式
+    #[default]
+    Text,
+    /// 每行一个 JSON 对象, 便于日志系统采集
+    Json,
+}
+
+/// 日志配置。
+///
+/// 设置了 `RUST_LOG` 环境变量时, 环境变量优先于`level`。
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct LogConfig {
+    /// 日志级别或过滤规则, 例如 `info` 或 `info,mongodb=warn`
+    pub level: String,
+    /// 日志输出格式
+    pub format: LogFormat,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            format: LogFormat::Text,
+        }
+    }
+}
+
+/// 监控指标配置。
+///
+/// 指标使用 Prometheus 文本格式输出, web-api 和调度器分别监听`api_listen`和
+/// `scheduler_listen`地址, 不与业务接口共用端口。
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct MetricsConfig {
+    /// 是否采集并输出指标
+    pub enabled: bool,
+    /// web-api 输出指标的监听地址
+    pub api_listen: String,
+    /// 调度器输出指标的监听地址
+    pub scheduler_listen: String,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            api_listen: "0.0.0.0:9101".to_string(),
+            scheduler_listen: "0.0.0.0:9100".to_string(),
+        }
+    }
+}
+
+/// OIDC 单点登录配置。
+///
+/// 使用授权码模式(PKCE)通过企业身份提供方登录。
+/// 身份提供方的用户组按`role_mappings`映射为角色, 所有匹配的映射都会生效。
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct OidcConfig {
+    /// 是否启用单点登录
+    pub enabled: bool,
+    /// 身份提供方的签发方地址, 用于服务发现和校验 ID 令牌
+    pub issuer: String,
+    /// 客户端ID
+    pub client_id: String,
+    /// 客户端密钥
+    pub client_secret: String,
+    /// 登录完成后身份提供方回调的前端地址
+    pub redirect_uri: String,
+    /// 请求的授权范围
+    pub scopes: Vec<String>,
+    /// ID 令牌中用户组所在的声明
+    pub groups_claim: String,
+    /// 用户组到角色的映射
+    pub role_mappings: Vec<OidcRoleMapping>,
+    /// 没有匹配的用户组时使用的角色, 为空时拒绝登录
+    pub default_role: String,
+    /// 是否在首次登录时自动创建管理员
+    pub jit_provisioning: bool,
+    /// 是否在每次登录时按用户组同步已有管理员的角色
+    pub sync_role_on_login: bool,
+    /// 是否在首次登录时使用已验证的邮箱关联同账号的本地管理员, 关闭时需要手动关联
+    pub link_by_email: bool,
+    /// 登录请求(state)的有效期(秒)
+    pub state_ttl: u64,
+}
+
+/// 用户组到角色的映射。
+#[derive(Debug, Deserialize, Clone)]
+pub struct OidcRoleMapping {
+    /// 身份提供方的用户组
+    pub group: String,
+    /// 对应的角色名称
+    pub role: String,
+}
+
+impl Default for OidcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            issuer: String::new(),
+            client_id: String::new(),
+            client_secret: String::new(),
+            redirect_uri: String::new(),
+            scopes: vec!["openid".to_string(), "profile".to_string(), "email".to_string()],
+            groups_claim: "groups".to_string(),
+            role_mappings: vec![],
+            default_role: String::new(),
+            jit_provisioning: false,
+            sync_role_on_login: true,
+            link_by_email: false,
+            state_ttl: 10 * 60,
+        }
+    }
+}
+
+impl OidcConfig {
+    /// 按用户组映射角色, 没有匹配时使用默认角色
+    ///
+    /// # 返回
+    ///
+    /// * `Vec<String>` - 去重后的角色名称, 没有匹配且未配置默认角色时为空
+    pub fn map_roles(&self, groups: &[String]) -> Vec<String> {
+        let mut roles: Vec<String> = vec![];
+        for mapping in &self.role_mappings {
+            if groups.contains(&mapping.group) && !roles.contains(&mapping.role) {
+                roles.push(mapping.role.clone());
+            }
+        }
+
+        if roles.is_empty() && !self.default_role.is_empty() {
+            roles.push(self.default_role.clone());
+        }
+
+        roles
+    }
+}
+
 impl Config {
     /// 从指定路径加载 TOML 文件配置。
     ///
@@ -140,6 +377,7 @@ impl Config {
     /// # 返回
     ///
     /// * `Result<Self>` - 解析后的配置或错误
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(content: &str) -> Result<Self> {
         let config = toml::from_str(content)?;
         Ok(config)
@@ -168,6 +406,7 @@ impl SafeConfig {
     /// * `nacos_namespace`: Nacos 命名空间
     /// * `nacos_group`: Nacos 配置组
     /// * `nacos_data_id`: Nacos 配置ID
+    /// * `command`: 子命令, 如 `seed`
     ///
     /// # 示例
     ///
@@ -181,14 +420,30 @@ impl SafeConfig {
     ///           --nacos-namespace="public" \
     ///           --nacos-group="DEFAULT_GROUP" \
     ///           --nacos-data-id="config.toml"
+    ///
+    /// # 初始化超级管理员
+    /// ./program --config-path=config.toml seed --account=admin --password=<password>
     /// ```
     ///
     /// # 返回
     ///
     /// * `Result<Self>` - 初始化的 SafeConfig 实例或错误
     pub async fn from_args() -> Result<Self> {
-        let args = Args::parse();
+        Self::from_parsed_args(&Args::parse()).await
+    }
 
+    /// 从已解析的命令行参数创建 SafeConfig 实例。
+    ///
+    /// 需要同时读取子命令时使用, 避免重复解析命令行参数。
+    ///
+    /// # 参数
+    ///
+    /// * `args` - 已解析的命令行参数
+    ///
+    /// # 返回
+    ///
+    /// * `Result<Self>` - 初始化的 SafeConfig 实例或错误
+    pub async fn from_parsed_args(args: &Args) -> Result<Self> {
         if args.is_enable_nacos() {
             return Self::from_nacos_with_watcher(args.to_nacos_config()).await;
         }
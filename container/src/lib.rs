@@ -1,6 +1,22 @@
-use database::repositories::{AdminRepository, InternalMessageRepository, MessageRepository, RoleRepository};
+use database::repositories::{
+    AdminRepository, ApiKeyRepository, BrandRepository, BroadcastRepository, CategoryRepository,
+    InternalMessageRepository, MessageRepository, ProductRepository, RoleRepository, SupplierRepository,
+    WebhookEndpointRepository,
+};
 use mongodb::Database;
-use services::{AdminService, InternalMessageService, NotificationService, RoleService};
+use services::{
+    AdminService, ApiKeyService, BroadcastService, InternalMessageService, NotificationService,
+    ProductService, RoleService, WebhookService,
+};
+
+/// 使用 MongoDB 仓储的通知服务
+pub type MongoNotificationService = NotificationService<
+    MessageRepository,
+    InternalMessageRepository,
+    BroadcastRepository,
+    AdminRepository,
+    WebhookEndpointRepository,
+>;
 
 #[derive(Clone)]
 pub struct ServiceFactory {
@@ -18,16 +34,56 @@ impl ServiceFactory {
         AdminService::new(admin_repo, role_repo)
     }
 
+    pub fn api_key_service(&self) -> ApiKeyService<ApiKeyRepository, RoleRepository> {
+        let api_key_repo = ApiKeyRepository::new(self.db.clone());
+        let role_repo = RoleRepository::new(self.db.clone());
+        ApiKeyService::new(api_key_repo, role_repo)
+    }
+
     pub fn role_service(&self) -> RoleService<RoleRepository, AdminRepository> {
         let role_repo = RoleRepository::new(self.db.clone());
         let admin_repo = AdminRepository::new(self.db.clone());
         RoleService::new(role_repo, admin_repo)
     }
 
-    pub fn notify_service(&self) -> NotificationService<MessageRepository, InternalMessageRepository> {
+    pub fn product_service(
+        &self,
+    ) -> ProductService<ProductRepository, BrandRepository, CategoryRepository, SupplierRepository> {
+        ProductService::new(
+            ProductRepository::new(self.db.clone()),
+            BrandRepository::new(self.db.clone()),
+            CategoryRepository::new(self.db.clone()),
+            SupplierRepository::new(self.db.clone()),
+        )
+    }
+
+    pub fn notify_service(&self) -> MongoNotificationService {
         let message_repo = MessageRepository::new(self.db.clone());
         let internal_message_repo = InternalMessageRepository::new(self.db.clone());
-        NotificationService::new(message_repo, internal_message_repo)
+        let broadcast_repo = BroadcastRepository::new(self.db.clone());
+        let admin_repo = AdminRepository::new(self.db.clone());
+        let webhook_repo = WebhookEndpointRepository::new(self.db.clone());
+        NotificationService::new(
+            message_repo,
+            internal_message_repo,
+            broadcast_repo,
+            admin_repo,
+            webhook_repo,
+        )
+    }
+
+    pub fn broadcast_service(
+        &self,
+    ) -> BroadcastService<BroadcastRepository, AdminRepository, MessageRepository> {
+        let broadcast_repo = BroadcastRepository::new(self.db.clone());
+        let admin_repo = AdminRepository::new(self.db.clone());
+        let message_repo = MessageRepository::new(self.db.clone());
+        BroadcastService::new(broadcast_repo, admin_repo, message_repo)
+    }
+
+    pub fn webhook_service(&self) -> WebhookService<WebhookEndpointRepository> {
+        let webhook_repo = WebhookEndpointRepository::new(self.db.clone());
+        WebhookService::new(webhook_repo)
     }
 
     pub fn internal_message_service(&self) -> InternalMessageService<InternalMessageRepository> {
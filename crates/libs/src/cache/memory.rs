@@ -1,7 +1,7 @@
-use super::{Cache, Result};
+use super::{Cache, CacheError, Result};
 use async_trait::async_trait;
 use dashmap::DashMap;
-use serde::Serialize;
+use metrics::counter;
 use std::{
     sync::Arc,
     time::{Duration, Instant},
@@ -18,6 +18,12 @@ pub struct MemoryCache {
     storage: Arc<DashMap<String, CacheEntry>>,
 }
 
+impl Default for MemoryCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MemoryCache {
     pub fn new() -> Self {
         Self {
@@ -37,7 +43,22 @@ impl MemoryCache {
 #[async_trait]
 impl Cache for MemoryCache {
     async fn get_raw(&self, key: &str) -> Result<Option<Vec<u8>>> {
-        Ok(self.storage.get(key).map(|entry| entry.data.clone()))
+        // clone避免死锁
+        let entry = self.storage.get(key).map(|entry| entry.clone());
+
+        let data = match entry {
+            Some(entry) if self.is_expired(&entry) => {
+                self.storage.remove(key);
+                None
+            }
+            Some(entry) => Some(entry.data),
+            None => None,
+        };
+
+        let result = if data.is_some() { "hit" } else { "miss" };
+        counter!("cache_requests_total", "cache" => "memory", "result" => result).increment(1);
+
+        Ok(data)
     }
 
     async fn set_raw(&self, key: &str, value: Vec<u8>, ttl: Option<u64>) -> Result<()> {
@@ -71,6 +92,28 @@ impl Cache for MemoryCache {
         }
     }
 
+    async fn incr(&self, key: &str, ttl: Option<u64>) -> Result<u64> {
+        let mut entry = self.storage.entry(key.to_string()).or_insert_with(|| CacheEntry {
+            data: b"0".to_vec(),
+            expires_at: ttl.map(|ttl| Instant::now() + Duration::from_secs(ttl)),
+        });
+
+        if self.is_expired(&entry) {
+            entry.data = b"0".to_vec();
+            entry.expires_at = ttl.map(|ttl| Instant::now() + Duration::from_secs(ttl));
+        }
+
+        let current = std::str::from_utf8(&entry.data)
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+            .ok_or_else(|| CacheError::DeserializationError("计数器不是数字".to_string()))?;
+
+        let next = current + 1;
+        entry.data = next.to_string().into_bytes();
+
+        Ok(next)
+    }
+
     async fn expire(&self, key: &str, ttl: Duration) -> Result<bool> {
         if let Some(mut entry) = self.storage.get_mut(key) {
             entry.expires_at = Some(Instant::now() + ttl);
@@ -83,7 +126,7 @@ impl Cache for MemoryCache {
 
 #[cfg(test)]
 mod tests {
-    use serde::Deserialize;
+    use serde::{Deserialize, Serialize};
 
     use crate::cache::{CacheWithJson, CacheWithString};
 
@@ -123,6 +166,7 @@ mod tests {
 
         // 检查是否已过期
         assert!(!cache.exists("key1").await.unwrap());
+        assert_eq!(cache.get_raw("key1").await.unwrap(), None);
     }
 
     #[tokio::test]
@@ -145,6 +189,22 @@ mod tests {
         assert!(!cache.exists("key1").await.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_memory_cache_incr() {
+        let cache = MemoryCache::new();
+
+        assert_eq!(cache.incr("counter", Some(1)).await.unwrap(), 1);
+        assert_eq!(cache.incr("counter", Some(1)).await.unwrap(), 2);
+        assert_eq!(cache.get("counter").await.unwrap(), Some("2".to_string()));
+
+        // 过期后重新计数
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert_eq!(cache.incr("counter", Some(1)).await.unwrap(), 1);
+
+        cache.set("text", "abc".to_string(), None).await.unwrap();
+        assert!(cache.incr("text", None).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_memory_cache_with_string() {
         let cache = MemoryCache::new();
@@ -70,6 +70,16 @@ pub trait Cache: Send + Sync + Clone {
     /// * `key` - The cache key to set expiration for
     /// * `ttl` - The time-to-live duration
     async fn expire(&self, key: &str, ttl: Duration) -> Result<bool>;
+
+    /// Atomically increments the counter stored at a key and returns the new value
+    ///
+    /// The counter is stored as a decimal string. A missing or expired key
+    /// starts from zero and gets the given TTL; the TTL of an existing key is kept.
+    ///
+    /// # Arguments
+    /// * `key` - The cache key of the counter
+    /// * `ttl` - Optional time-to-live in seconds, applied when the counter is created
+    async fn incr(&self, key: &str, ttl: Option<u64>) -> Result<u64>;
 }
 
 #[async_trait]
@@ -5,6 +5,24 @@ use tokio::sync::Mutex;
 static ID_GENERATOR: LazyLock<Mutex<SnowflakeIdGenerator>> =
     LazyLock::new(|| Mutex::new(SnowflakeIdGenerator::new(1, 1)));
 
+/// 当前进程的实例标识, 由主机名、进程号和启动时间组成
+static INSTANCE_ID: LazyLock<String> = LazyLock::new(|| {
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "localhost".to_string());
+    format!(
+        "{}-{}-{}",
+        host,
+        std::process::id(),
+        chrono::Local::now().timestamp_millis()
+    )
+});
+
 pub async fn next_id() -> String {
     ID_GENERATOR.lock().await.generate().to_string()
 }
+
+/// 获取当前进程的实例标识
+///
+/// 用于在多个实例之间区分任务或租约的持有者
+pub fn instance_id() -> &'static str {
+    &INSTANCE_ID
+}
@@ -1,5 +1,8 @@
 pub mod cache;
 mod id_gen;
 pub mod logger;
+pub mod metrics;
+mod random;
 
-pub use id_gen::next_id;
+pub use id_gen::{instance_id, next_id};
+pub use random::random_token;
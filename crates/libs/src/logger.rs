@@ -1,22 +1,44 @@
-use env_logger::{Builder, Target};
-use log::LevelFilter;
-use std::io::Write;
+use tracing_subscriber::{fmt::time::ChronoLocal, EnvFilter};
+
+/// 默认的日志过滤规则
+const DEFAULT_FILTER: &str = "info";
 
 /// 初始化日志系统
-pub fn init() {
-    Builder::new()
-        .format(|buf, record| {
-            writeln!(
-                buf,
-                "{} [{}] - {}",
-                chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
-                record.level(),
-                record.args()
-            )
-        })
-        .filter(None, LevelFilter::Info)
-        .target(Target::Stdout)
-        .init();
+///
+/// 设置了 `RUST_LOG` 环境变量时按环境变量过滤日志, 否则使用`filter`。
+/// 使用 `log` 宏输出的日志同样会带上所在 span 的字段, 例如请求ID。
+///
+/// # 参数
+///
+/// * `filter` - 日志级别或过滤规则, 例如 `info` 或 `info,mongodb=warn`
+/// * `json` - 是否每行输出一个 JSON 对象
+pub fn init(filter: &str, json: bool) {
+    let (env_filter, invalid) = match EnvFilter::try_from_default_env() {
+        Ok(env_filter) => (env_filter, None),
+        Err(_) => match EnvFilter::try_new(filter) {
+            Ok(env_filter) => (env_filter, None),
+            Err(err) => (EnvFilter::new(DEFAULT_FILTER), Some(err)),
+        },
+    };
+
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(env_filter)
+        .with_timer(ChronoLocal::new("%Y-%m-%d %H:%M:%S".to_string()));
+
+    if json {
+        builder.json().flatten_event(true).init();
+    } else {
+        builder.init();
+    }
+
+    if let Some(err) = invalid {
+        tracing::warn!(
+            "日志过滤规则 {} 无效, 使用默认规则 {}: {}",
+            filter,
+            DEFAULT_FILTER,
+            err
+        );
+    }
 
-    log::info!("Logger initialized");
+    tracing::info!("Logger initialized");
 }
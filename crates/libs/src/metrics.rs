@@ -0,0 +1,58 @@
+//! Prometheus 监控指标
+//!
+//! 各模块通过 `metrics` 宏记录指标, 本模块负责安装记录器并以 Prometheus 文本格式输出。
+//! 没有安装记录器时, 记录指标的宏不做任何事情。
+
+use std::net::SocketAddr;
+
+use metrics::{describe_counter, describe_histogram, Unit};
+use metrics_exporter_prometheus::{BuildError, Matcher, PrometheusBuilder};
+
+/// 耗时类指标(以 `_seconds` 结尾)的直方图分桶
+const DURATION_BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// 安装全局指标记录器, 并在指定地址上输出指标
+///
+/// web-api 和调度器都在单独的地址上输出指标, 不与业务接口共用端口。需要在 tokio 运行时中调用。
+///
+/// # 参数
+///
+/// * `addr` - 监听地址
+pub fn serve(addr: SocketAddr) -> Result<(), BuildError> {
+    builder()?.with_http_listener(addr).install()?;
+    describe();
+    Ok(())
+}
+
+/// 创建指标输出器的构建器, 耗时类指标使用直方图
+pub fn builder() -> Result<PrometheusBuilder, BuildError> {
+    PrometheusBuilder::new().set_buckets_for_metric(Matcher::Suffix("_seconds".to_string()), DURATION_BUCKETS)
+}
+
+/// 注册所有指标的说明
+fn describe() {
+    describe_counter!("http_requests_total", "HTTP 请求数, 按方法、路由和状态码区分");
+    describe_histogram!(
+        "http_request_duration_seconds",
+        Unit::Seconds,
+        "HTTP 请求耗时, 按方法和路由区分"
+    );
+    describe_histogram!("rbac_check_duration_seconds", Unit::Seconds, "权限检查耗时");
+    describe_counter!("rbac_denials_total", "被拒绝的请求数, 按方法和路由区分");
+    describe_histogram!(
+        "mongodb_command_duration_seconds",
+        Unit::Seconds,
+        "MongoDB 命令耗时, 按集合和命令区分"
+    );
+    describe_counter!("mongodb_command_failures_total", "执行失败的 MongoDB 命令数");
+    describe_counter!("messages_sent_total", "消息发送次数, 按渠道和结果区分");
+    describe_counter!("cache_requests_total", "缓存读取次数, 按是否命中区分");
+    describe_histogram!(
+        "scheduler_job_duration_seconds",
+        Unit::Seconds,
+        "定时任务每次执行的耗时"
+    );
+    describe_counter!("scheduler_job_failures_total", "执行失败的定时任务次数");
+}
@@ -0,0 +1,24 @@
+use rand::{rngs::OsRng, RngCore};
+
+/// 生成一个密码学安全的随机令牌
+///
+/// # 参数
+///
+/// * `bytes` - 随机字节数, 返回的十六进制字符串长度为其两倍
+pub fn random_token(bytes: usize) -> String {
+    let mut buf = vec![0u8; bytes];
+    OsRng.fill_bytes(&mut buf);
+    hex::encode(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_token() {
+        let token = random_token(16);
+        assert_eq!(token.len(), 32);
+        assert_ne!(token, random_token(16));
+    }
+}
@@ -2,12 +2,18 @@
 ///
 /// 该模块实现了一个基于 Actor 模式的 RBAC 权限检查系统。
 /// 通过 Actor 模式可以安全地处理并发的权限检查请求。
+use std::time::Duration;
+
+use log::error;
 use tokio::sync::{
     mpsc::{self, Receiver},
     oneshot,
 };
 
-use super::{RBACEnforcer, RBACRoleStore, RBACUserStore};
+use super::{
+    EffectivePermissions, EffectiveScope, Explanation, RBACEnforcer, RBACRole, RBACRoleStore, RBACUser,
+    RBACUserStore,
+};
 
 /// RBAC Actor 错误类型
 #[derive(Debug, thiserror::Error)]
@@ -58,8 +64,68 @@ pub enum Command {
         /// 用于返回检查结果的 channel
         respond_to: oneshot::Sender<bool>,
     },
+    /// 计算有效权限命令
+    EffectivePermissions {
+        /// 用户标识
+        user: String,
+        /// 用于返回有效权限的 channel
+        respond_to: oneshot::Sender<EffectivePermissions>,
+    },
+    /// 解释权限检查命令
+    Explain {
+        /// 用户标识
+        user: String,
+        /// HTTP 方法
+        method: String,
+        /// 请求路径
+        path: String,
+        /// 用于返回判定说明的 channel
+        respond_to: oneshot::Sender<Explanation>,
+    },
+    /// 计算数据范围命令
+    DataScope {
+        /// 用户标识
+        user: String,
+        /// 资源名称
+        resource: String,
+        /// 用于返回数据范围的 channel
+        respond_to: oneshot::Sender<EffectiveScope>,
+    },
+    /// 新增或替换角色命令
+    UpsertRole {
+        /// 角色
+        role: Box<dyn RBACRole>,
+        /// 用于返回更新结果的 channel
+        respond_to: oneshot::Sender<super::Result<()>>,
+    },
+    /// 移除角色命令
+    RemoveRole {
+        /// 角色名称
+        name: String,
+        /// 用于返回更新结果的 channel
+        respond_to: oneshot::Sender<super::Result<()>>,
+    },
+    /// 设置用户角色命令
+    AssignUser {
+        /// 用户标识
+        user: String,
+        /// 用户拥有的全部角色
+        roles: Vec<String>,
+        /// 用于返回更新结果的 channel
+        respond_to: oneshot::Sender<super::Result<()>>,
+    },
+    /// 移除用户角色命令
+    UnassignUser {
+        /// 用户标识
+        user: String,
+        /// 用于返回更新结果的 channel
+        respond_to: oneshot::Sender<super::Result<()>>,
+    },
     /// 重置权限策略命令
-    Reset,
+    Reset {
+        /// 用于返回加载结果的 channel
+        respond_to: oneshot::Sender<super::Result<()>>,
+    },
 }
 
 /// RBAC Actor 结构体
@@ -118,21 +184,82 @@ impl Actor {
                 let is_ok = self.enforcer.check_permission(&user, &method, &path).await?;
                 respond_to.send(is_ok).map_err(|err| err.to_string())?;
             }
-            Command::Reset => {
-                self.enforcer.load_policies().await?;
+            Command::EffectivePermissions { user, respond_to } => {
+                let permissions = self.enforcer.effective_permissions(&user);
+                respond_to
+                    .send(permissions)
+                    .map_err(|_| "Failed to send response".to_string())?;
+            }
+            Command::Explain {
+                user,
+                method,
+                path,
+                respond_to,
+            } => {
+                let explanation = self.enforcer.explain(&user, &method, &path).await?;
+                respond_to
+                    .send(explanation)
+                    .map_err(|_| "Failed to send response".to_string())?;
+            }
+            Command::DataScope {
+                user,
+                resource,
+                respond_to,
+            } => {
+                let scope = self.enforcer.data_scope(&user, &resource);
+                respond_to
+                    .send(scope)
+                    .map_err(|_| "Failed to send response".to_string())?;
+            }
+            Command::UpsertRole { role, respond_to } => {
+                let result = self.enforcer.upsert_role(role).await;
+                respond(respond_to, result)?;
+            }
+            Command::RemoveRole { name, respond_to } => {
+                let result = self.enforcer.remove_role(&name).await;
+                respond(respond_to, result)?;
+            }
+            Command::AssignUser {
+                user,
+                roles,
+                respond_to,
+            } => {
+                let result = self.enforcer.assign_user(&user, roles).await;
+                respond(respond_to, result)?;
+            }
+            Command::UnassignUser { user, respond_to } => {
+                let result = self.enforcer.unassign_user(&user).await;
+                respond(respond_to, result)?;
+            }
+            Command::Reset { respond_to } => {
+                let result = self.enforcer.load_policies().await;
+                respond(respond_to, result)?;
             }
         }
         Ok(())
     }
 }
 
+/// 记录更新策略失败的原因, 并把结果返回给调用方
+///
+/// 调用方已经不再等待结果时(例如定期重新加载), 失败仍然会记录到日志中
+fn respond(respond_to: oneshot::Sender<super::Result<()>>, result: super::Result<()>) -> Result<(), Error> {
+    if let Err(err) = &result {
+        error!("Failed to update RBAC policies: {}", err);
+    }
+
+    respond_to
+        .send(result)
+        .map_err(|_| Error::MessageError("Failed to send response".to_string()))
+}
+
 /// Actor 运行函数
 ///
 /// 持续监听并处理接收到的命令
 async fn run_actor(mut actor: Actor) {
     while let Some(command) = actor.receiver.recv().await {
         if let Err(err) = actor.handle_message(command).await {
-            println!("Failed to handle message: {}", err);
+            error!("Failed to handle RBAC command: {}", err);
         }
     }
 }
@@ -199,8 +326,222 @@ impl ActorHandler {
         Ok(is_ok)
     }
 
+    /// 计算有效权限
+    ///
+    /// # 参数
+    ///
+    /// * `user` - 用户标识
+    ///
+    /// # 返回值
+    ///
+    /// 返回用户直接拥有和继承的角色, 以及这些角色授予的权限
+    pub async fn effective_permissions(&self, user: String) -> Result<EffectivePermissions, Error> {
+        let (respond_to, response) = oneshot::channel();
+        self.sender
+            .send(Command::EffectivePermissions { user, respond_to })
+            .await?;
+
+        Ok(response.await?)
+    }
+
+    /// 解释权限检查的结果, 不影响实际的访问
+    ///
+    /// # 参数
+    ///
+    /// * `user` - 用户标识
+    /// * `method` - HTTP 方法
+    /// * `path` - 请求路径
+    ///
+    /// # 返回值
+    ///
+    /// 返回是否允许访问, 以及授予权限的角色和权限, 或者拒绝的原因
+    pub async fn explain(&self, user: String, method: String, path: String) -> Result<Explanation, Error> {
+        let (respond_to, response) = oneshot::channel();
+        self.sender
+            .send(Command::Explain {
+                user,
+                method,
+                path,
+                respond_to,
+            })
+            .await?;
+
+        Ok(response.await?)
+    }
+
+    /// 计算数据范围
+    ///
+    /// # 参数
+    ///
+    /// * `user` - 用户标识
+    /// * `resource` - 资源名称, 例如 `product`
+    ///
+    /// # 返回值
+    ///
+    /// 返回用户所有角色(包括继承的角色)对该资源数据范围的并集
+    pub async fn data_scope(&self, user: String, resource: String) -> Result<EffectiveScope, Error> {
+        let (respond_to, response) = oneshot::channel();
+        self.sender
+            .send(Command::DataScope {
+                user,
+                resource,
+                respond_to,
+            })
+            .await?;
+
+        Ok(response.await?)
+    }
+
+    /// 新增或替换角色的权限和继承关系
+    ///
+    /// # 参数
+    ///
+    /// * `role` - 角色
+    ///
+    /// # 返回值
+    ///
+    /// 更新失败时返回错误, 内存中的策略可能与存储不一致, 直到下一次全量加载
+    pub async fn upsert_role<R>(&self, role: R) -> Result<(), Error>
+    where
+        R: RBACRole + 'static,
+    {
+        let (respond_to, response) = oneshot::channel();
+        self.sender
+            .send(Command::UpsertRole {
+                role: Box::new(role),
+                respond_to,
+            })
+            .await?;
+
+        Ok(response.await??)
+    }
+
+    /// 移除角色
+    ///
+    /// # 参数
+    ///
+    /// * `name` - 角色名称
+    pub async fn remove_role(&self, name: String) -> Result<(), Error> {
+        let (respond_to, response) = oneshot::channel();
+        self.sender.send(Command::RemoveRole { name, respond_to }).await?;
+
+        Ok(response.await??)
+    }
+
+    /// 按用户当前的角色更新用户和角色的关联
+    ///
+    /// # 参数
+    ///
+    /// * `user` - 用户
+    pub async fn assign_user<U>(&self, user: &U) -> Result<(), Error>
+    where
+        U: RBACUser + Sync,
+    {
+        let (respond_to, response) = oneshot::channel();
+        self.sender
+            .send(Command::AssignUser {
+                user: user.subject(),
+                roles: user.role_names(),
+                respond_to,
+            })
+            .await?;
+
+        Ok(response.await??)
+    }
+
+    /// 移除用户和所有角色的关联
+    ///
+    /// # 参数
+    ///
+    /// * `user` - 用户标识
+    pub async fn unassign_user(&self, user: String) -> Result<(), Error> {
+        let (respond_to, response) = oneshot::channel();
+        self.sender
+            .send(Command::UnassignUser { user, respond_to })
+            .await?;
+
+        Ok(response.await??)
+    }
+
+    /// 从存储中重新加载全部策略, 失败时继续使用之前的策略
     pub async fn reset(&self) -> Result<(), Error> {
-        self.sender.send(Command::Reset).await?;
-        Ok(())
+        let (respond_to, response) = oneshot::channel();
+        self.sender.send(Command::Reset { respond_to }).await?;
+
+        Ok(response.await??)
+    }
+
+    /// 定期重新加载全部策略, 作为增量更新的一致性检查
+    ///
+    /// # 参数
+    ///
+    /// * `period` - 重新加载的间隔
+    pub fn spawn_periodic_reload(&self, period: Duration) {
+        let handler = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            // 启动时已经加载过, 跳过第一次立即触发
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+                match handler.reset().await {
+                    // 加载失败已经记录在日志中, 等待下一次重新加载
+                    Ok(()) | Err(Error::RBACError(_)) => {}
+                    // Actor 已经停止
+                    Err(_) => break,
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    use async_trait::async_trait;
+
+    use super::*;
+
+    /// 设置 `failing` 后读取失败, 没有任何角色和用户的存储
+    #[derive(Clone)]
+    struct Store {
+        failing: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl RBACRoleStore for Store {
+        async fn find_all(&self) -> crate::Result<Vec<Box<dyn RBACRole>>> {
+            if self.failing.load(Ordering::SeqCst) {
+                return Err(crate::Error::StoreError("connection refused".to_string()));
+            }
+            Ok(vec![])
+        }
+    }
+
+    #[async_trait]
+    impl RBACUserStore for Store {
+        async fn find_all(&self) -> crate::Result<Vec<Box<dyn RBACUser>>> {
+            Ok(vec![])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_commands_return_errors() {
+        let store = Store {
+            failing: Arc::new(AtomicBool::new(false)),
+        };
+        let handler = ActorHandler::new(store.clone(), store.clone()).await;
+        assert!(handler.reset().await.is_ok());
+
+        store.failing.store(true, Ordering::SeqCst);
+        assert!(matches!(handler.reset().await, Err(Error::RBACError(_))));
+
+        // 失败后 Actor 继续处理命令
+        assert!(handler.remove_role("viewer".to_string()).await.is_ok());
     }
 }
@@ -1,8 +1,13 @@
-use casbin::{CoreApi, Enforcer, MgmtApi, RbacApi};
+use casbin::{function_map::OperatorFunction, CoreApi, Enforcer, MgmtApi, RbacApi};
+use std::collections::HashMap;
 
 use super::{
     errors::Result,
-    model::{RBACRole, RBACRoleStore, RBACUser, RBACUserStore},
+    model::{
+        DataScope, EffectivePermissions, EffectiveScope, Explanation, Policy, RBACRole, RBACRoleStore,
+        RBACUser, RBACUserStore,
+    },
+    pattern::route_match,
 };
 
 const MODEL: &str = r#"
@@ -19,13 +24,15 @@ g = _, _
 e = some(where (p.eft == allow))
 
 [matchers]
-m = g(r.sub, p.sub) && (r.method == p.method || p.method == "*") && keyMatch2(r.path, p.path) || r.sub == "fake"
+m = g(r.sub, p.sub) && (r.method == p.method || p.method == "*") && routeMatch(r.path, p.path)
 "#;
 
 pub struct RBACEnforcer {
     enforcer: Enforcer,
     role_store: Box<dyn RBACRoleStore>,
     user_store: Box<dyn RBACUserStore>,
+    /// 角色声明的数据范围, 按角色名称索引
+    scopes: HashMap<String, Vec<DataScope>>,
 }
 
 impl RBACEnforcer {
@@ -34,14 +41,11 @@ impl RBACEnforcer {
         R: RBACRoleStore + 'static,
         U: RBACUserStore + 'static,
     {
-        let model = casbin::DefaultModel::from_str(MODEL).await?;
-        let adapter = casbin::MemoryAdapter::default();
-        let enforcer = Enforcer::new(model, adapter).await?;
-
         let mut rbac = Self {
-            enforcer,
+            enforcer: Self::empty_enforcer().await?,
             role_store: Box::new(role_fetcher),
             user_store: Box::new(user_fetcher),
+            scopes: HashMap::new(),
         };
 
         rbac.load_policies().await?;
@@ -49,44 +53,258 @@ impl RBACEnforcer {
         Ok(rbac)
     }
 
-    pub async fn load_policies(&mut self) -> Result<()> {
-        if let Err(err) = self.enforcer.clear_policy().await {
-            println!("Failed to clear policies: {}", err);
-        }
+    /// 创建没有任何策略的 casbin 执行器
+    async fn empty_enforcer() -> Result<Enforcer> {
+        let model = casbin::DefaultModel::from_str(MODEL).await?;
+        let adapter = casbin::MemoryAdapter::default();
+        let mut enforcer = Enforcer::new(model, adapter).await?;
+        // 与 RBACRole::check_permission 使用同一个路由匹配器
+        enforcer.add_function(
+            "routeMatch",
+            OperatorFunction::Arg2(|path, pattern| route_match(&path, &pattern).into()),
+        );
 
+        Ok(enforcer)
+    }
+
+    /// 从存储中重新加载全部策略
+    ///
+    /// 日常变更使用增量更新, 全量加载只在启动和定期一致性检查时执行。
+    /// 策略加载到新的执行器中, 全部成功后才替换当前的执行器,
+    /// 读取存储失败时继续使用之前的策略
+    pub async fn load_policies(&mut self) -> Result<()> {
         let all_roles: Vec<Box<dyn RBACRole>> = self.role_store.find_all().await?;
         let all_users: Vec<Box<dyn RBACUser>> = self.user_store.find_all().await?;
 
+        let mut policies = vec![];
+        let mut groupings = vec![];
+        let mut scopes = HashMap::new();
+
         for role in all_roles {
-            for policy in role.to_casbin_policy() {
-                self.enforcer.add_policy(policy).await?;
+            policies.extend(role.to_casbin_policy());
+            scopes.insert(role.get_name(), role.data_scopes());
+
+            // 角色继承: g(子角色, 父角色)
+            for parent in role.parent_names() {
+                groupings.push(vec![role.get_name(), parent]);
             }
         }
 
         for user in all_users {
-            self.enforcer
-                .add_role_for_user(&user.account(), &user.role_name(), None)
-                .await?;
+            for role in user.role_names() {
+                groupings.push(vec![user.subject(), role]);
+            }
         }
 
+        let mut enforcer = Self::empty_enforcer().await?;
+        add_policies(&mut enforcer, policies).await?;
+        add_groupings(&mut enforcer, groupings).await?;
+
+        self.enforcer = enforcer;
+        self.scopes = scopes;
+
+        Ok(())
+    }
+
+    /// 新增或替换角色的权限和继承关系
+    pub async fn upsert_role(&mut self, role: Box<dyn RBACRole>) -> Result<()> {
+        let name = role.get_name();
+        let policies = role.to_casbin_policy();
+        let groupings = role
+            .parent_names()
+            .into_iter()
+            .map(|parent| vec![name.clone(), parent])
+            .collect();
+        let scopes = role.data_scopes();
+
+        self.enforcer
+            .remove_filtered_policy(0, vec![name.clone()])
+            .await?;
+        self.enforcer
+            .remove_filtered_grouping_policy(0, vec![name.clone()])
+            .await?;
+
+        add_policies(&mut self.enforcer, policies).await?;
+        add_groupings(&mut self.enforcer, groupings).await?;
+        self.scopes.insert(name, scopes);
+
+        Ok(())
+    }
+
+    /// 移除角色的权限, 以及用户和子角色与该角色的关联
+    pub async fn remove_role(&mut self, name: &str) -> Result<()> {
+        self.enforcer
+            .remove_filtered_policy(0, vec![name.to_string()])
+            .await?;
+        self.enforcer
+            .remove_filtered_grouping_policy(0, vec![name.to_string()])
+            .await?;
+        self.enforcer
+            .remove_filtered_grouping_policy(1, vec![name.to_string()])
+            .await?;
+        self.scopes.remove(name);
+
         Ok(())
     }
 
+    /// 设置用户拥有的角色, 替换之前的全部角色
+    pub async fn assign_user(&mut self, user: &str, roles: Vec<String>) -> Result<()> {
+        self.unassign_user(user).await?;
+        add_groupings(
+            &mut self.enforcer,
+            roles
+                .into_iter()
+                .map(|role| vec![user.to_string(), role])
+                .collect(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// 移除用户拥有的全部角色
+    pub async fn unassign_user(&mut self, user: &str) -> Result<()> {
+        self.enforcer
+            .remove_filtered_grouping_policy(0, vec![user.to_string()])
+            .await?;
+
+        Ok(())
+    }
+
+    /// 计算用户的有效权限, 包括多个角色和沿继承链得到的角色
+    pub fn effective_permissions(&mut self, user: &str) -> EffectivePermissions {
+        let mut roles = self.enforcer.get_implicit_roles_for_user(user, None);
+        roles.sort();
+
+        let mut policies: Vec<Policy> = self
+            .enforcer
+            .get_implicit_permissions_for_user(user, None)
+            .into_iter()
+            .filter_map(|policy| match policy.as_slice() {
+                [role, method, path, ..] => Some(Policy {
+                    role: role.clone(),
+                    method: method.clone(),
+                    path: path.clone(),
+                }),
+                _ => None,
+            })
+            .collect();
+        policies.sort();
+        policies.dedup();
+
+        EffectivePermissions { roles, policies }
+    }
+
+    /// 计算用户对某一类资源的有效数据范围
+    ///
+    /// 按用户直接拥有的角色分别计算, 每个角色的范围包括沿继承链得到的角色声明的规则
+    pub fn data_scope(&mut self, user: &str, resource: &str) -> EffectiveScope {
+        let roles: Vec<Vec<String>> = self
+            .enforcer
+            .get_roles_for_user(user, None)
+            .into_iter()
+            .map(|role| {
+                let mut chain = self.enforcer.get_implicit_roles_for_user(&role, None);
+                chain.push(role);
+                chain
+            })
+            .collect();
+
+        EffectiveScope::from_roles(roles.iter().map(|chain| {
+            chain
+                .iter()
+                .filter_map(|role| self.scopes.get(role))
+                .flatten()
+                .filter(|scope| scope.resource == resource)
+        }))
+    }
+
+    /// 解释一次权限检查的结果, 列出授予权限的角色或拒绝的原因
+    ///
+    /// 判定结果以 casbin 的检查为准, 匹配规则与模型中的 matcher 一致
+    pub async fn explain(&mut self, user: &str, method: &str, path: &str) -> Result<Explanation> {
+        let allowed = self.check_permission(user, method, path).await?;
+        let EffectivePermissions { roles, policies } = self.effective_permissions(user);
+
+        let (matched, other_methods): (Vec<Policy>, Vec<Policy>) = policies
+            .into_iter()
+            .filter(|policy| route_match(path, &policy.path))
+            .partition(|policy| policy.method == method || policy.method == "*");
+
+        let reason = if let Some(policy) = matched.first() {
+            format!(
+                "角色 {} 的权限 {} {} 允许访问",
+                policy.role, policy.method, policy.path
+            )
+        } else if roles.is_empty() {
+            format!("用户 {} 没有任何角色", user)
+        } else if !other_methods.is_empty() {
+            let methods: Vec<String> = other_methods.iter().map(|policy| policy.method.clone()).collect();
+            format!(
+                "角色只允许以 {} 方法访问 {}, 不包括 {}",
+                methods.join(","),
+                path,
+                method
+            )
+        } else {
+            format!("角色 {} 都没有匹配 {} {} 的权限", roles.join(","), method, path)
+        };
+
+        Ok(Explanation {
+            allowed,
+            roles,
+            matched,
+            reason,
+        })
+    }
+
     pub async fn check_permission(&self, user: &str, method: &str, path: &str) -> Result<bool> {
         Ok(self.enforcer.enforce((user, method, path))?)
     }
 }
 
+/// 批量添加权限, 忽略重复的权限
+async fn add_policies(enforcer: &mut Enforcer, mut policies: Vec<Vec<String>>) -> Result<()> {
+    policies.sort();
+    policies.dedup();
+    policies.retain(|policy| !enforcer.has_policy(policy.clone()));
+
+    if !policies.is_empty() {
+        enforcer.add_policies(policies).await?;
+    }
+
+    Ok(())
+}
+
+/// 批量添加角色关系, 忽略重复的关系
+async fn add_groupings(enforcer: &mut Enforcer, mut groupings: Vec<Vec<String>>) -> Result<()> {
+    groupings.sort();
+    groupings.dedup();
+    groupings.retain(|grouping| !enforcer.has_grouping_policy(grouping.clone()));
+
+    if !groupings.is_empty() {
+        enforcer.add_grouping_policies(groupings).await?;
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ScopeRule;
     use async_trait::async_trait;
-    use std::sync::Arc;
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
 
-    #[derive(Clone)]
+    #[derive(Clone, Default)]
     struct MockRole {
         name: String,
+        parents: Vec<String>,
         permissions: Vec<(String, String, String)>,
+        scopes: Vec<DataScope>,
     }
 
     impl RBACRole for MockRole {
@@ -101,8 +319,18 @@ mod tests {
             self.name.clone()
         }
 
+        fn parent_names(&self) -> Vec<String> {
+            self.parents.clone()
+        }
+
         fn check_permission(&self, method: &str, path: &str) -> bool {
-            self.permissions.iter().any(|(m, p, _)| m == method && p == path)
+            self.permissions
+                .iter()
+                .any(|(m, p, _)| (m == method || m == "*") && route_match(path, p))
+        }
+
+        fn data_scopes(&self) -> Vec<DataScope> {
+            self.scopes.clone()
         }
     }
 
@@ -113,12 +341,12 @@ mod tests {
     }
 
     impl RBACUser for MockUser {
-        fn account(&self) -> String {
+        fn subject(&self) -> String {
             self.account.clone()
         }
 
-        fn role_name(&self) -> String {
-            self.role.clone()
+        fn role_names(&self) -> Vec<String> {
+            self.role.split(',').map(str::to_string).collect()
         }
     }
 
@@ -138,6 +366,27 @@ mod tests {
         }
     }
 
+    /// 设置 `failing` 后读取失败的角色存储
+    struct FlakyRoleStore {
+        roles: Arc<Vec<MockRole>>,
+        failing: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl RBACRoleStore for FlakyRoleStore {
+        async fn find_all(&self) -> Result<Vec<Box<dyn RBACRole>>> {
+            if self.failing.load(Ordering::SeqCst) {
+                return Err(crate::Error::StoreError("connection refused".to_string()));
+            }
+
+            MockRoleStore {
+                roles: self.roles.clone(),
+            }
+            .find_all()
+            .await
+        }
+    }
+
     struct MockUserStore {
         users: Arc<Vec<MockUser>>,
     }
@@ -167,11 +416,13 @@ mod tests {
                     "Create user".to_string(),
                 ),
             ],
+            ..Default::default()
         };
 
         let user_role = MockRole {
             name: "user".to_string(),
             permissions: vec![("GET".to_string(), "/users".to_string(), "List users".to_string())],
+            ..Default::default()
         };
 
         let roles = Arc::new(vec![admin_role, user_role]);
@@ -238,6 +489,7 @@ mod tests {
                     "Delete post".to_string(),
                 ),
             ],
+            ..Default::default()
         };
 
         let roles = Arc::new(vec![role]);
@@ -275,6 +527,133 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_combined_user_stores() -> Result<()> {
+        let role = MockRole {
+            name: "reader".to_string(),
+            permissions: vec![("GET".to_string(), "/users".to_string(), "List users".to_string())],
+            ..Default::default()
+        };
+
+        let roles = Arc::new(vec![role]);
+        let admins = Arc::new(vec![MockUser {
+            account: "admin@example.com".to_string(),
+            role: "reader".to_string(),
+        }]);
+        let api_keys = Arc::new(vec![MockUser {
+            account: "apikey:ak_1".to_string(),
+            role: "reader".to_string(),
+        }]);
+
+        let enforcer = RBACEnforcer::new(
+            MockRoleStore { roles },
+            (MockUserStore { users: admins }, MockUserStore { users: api_keys }),
+        )
+        .await?;
+
+        assert!(
+            enforcer
+                .check_permission("admin@example.com", "GET", "/users")
+                .await?
+        );
+        assert!(enforcer.check_permission("apikey:ak_1", "GET", "/users").await?);
+        assert!(!enforcer.check_permission("apikey:ak_1", "POST", "/users").await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_multiple_roles_and_inheritance() -> Result<()> {
+        let viewer = MockRole {
+            name: "viewer".to_string(),
+            permissions: vec![("GET".to_string(), "/posts".to_string(), "List posts".to_string())],
+            ..Default::default()
+        };
+        let editor = MockRole {
+            name: "editor".to_string(),
+            parents: vec!["viewer".to_string()],
+            permissions: vec![(
+                "POST".to_string(),
+                "/posts".to_string(),
+                "Create post".to_string(),
+            )],
+            ..Default::default()
+        };
+        let auditor = MockRole {
+            name: "auditor".to_string(),
+            permissions: vec![("GET".to_string(), "/logs".to_string(), "List logs".to_string())],
+            ..Default::default()
+        };
+
+        let roles = Arc::new(vec![viewer, editor, auditor]);
+        let users = Arc::new(vec![
+            MockUser {
+                account: "editor@example.com".to_string(),
+                role: "editor".to_string(),
+            },
+            MockUser {
+                account: "both@example.com".to_string(),
+                role: "viewer,auditor".to_string(),
+            },
+        ]);
+
+        let mut enforcer = RBACEnforcer::new(MockRoleStore { roles }, MockUserStore { users }).await?;
+
+        // 继承父角色的权限
+        assert!(
+            enforcer
+                .check_permission("editor@example.com", "GET", "/posts")
+                .await?
+        );
+        assert!(
+            enforcer
+                .check_permission("editor@example.com", "POST", "/posts")
+                .await?
+        );
+        assert!(
+            !enforcer
+                .check_permission("editor@example.com", "GET", "/logs")
+                .await?
+        );
+
+        // 多个角色的权限合并
+        assert!(
+            enforcer
+                .check_permission("both@example.com", "GET", "/posts")
+                .await?
+        );
+        assert!(
+            enforcer
+                .check_permission("both@example.com", "GET", "/logs")
+                .await?
+        );
+        assert!(
+            !enforcer
+                .check_permission("both@example.com", "POST", "/posts")
+                .await?
+        );
+
+        let effective = enforcer.effective_permissions("editor@example.com");
+        assert_eq!(effective.roles, vec!["editor", "viewer"]);
+        assert_eq!(
+            effective.policies,
+            vec![
+                Policy {
+                    role: "editor".to_string(),
+                    method: "POST".to_string(),
+                    path: "/posts".to_string(),
+                },
+                Policy {
+                    role: "viewer".to_string(),
+                    method: "GET".to_string(),
+                    path: "/posts".to_string(),
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_wildcard_method() -> Result<()> {
         let role = MockRole {
@@ -284,6 +663,7 @@ mod tests {
                 "/public/*".to_string(),
                 "Access public content".to_string(),
             )],
+            ..Default::default()
         };
 
         let roles = Arc::new(vec![role]);
@@ -315,4 +695,454 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_incremental_updates() -> Result<()> {
+        let viewer = MockRole {
+            name: "viewer".to_string(),
+            permissions: vec![("GET".to_string(), "/posts".to_string(), "List posts".to_string())],
+            ..Default::default()
+        };
+
+        let roles = Arc::new(vec![viewer]);
+        let users = Arc::new(vec![MockUser {
+            account: "alice@example.com".to_string(),
+            role: "viewer".to_string(),
+        }]);
+
+        let mut enforcer = RBACEnforcer::new(MockRoleStore { roles }, MockUserStore { users }).await?;
+
+        // 新增继承 viewer 的角色, 并分配给新用户
+        let editor = MockRole {
+            name: "editor".to_string(),
+            parents: vec!["viewer".to_string()],
+            permissions: vec![(
+                "POST".to_string(),
+                "/posts".to_string(),
+                "Create post".to_string(),
+            )],
+            ..Default::default()
+        };
+        enforcer.upsert_role(Box::new(editor)).await?;
+        enforcer
+            .assign_user("bob@example.com", vec!["editor".to_string()])
+            .await?;
+
+        assert!(
+            enforcer
+                .check_permission("bob@example.com", "GET", "/posts")
+                .await?
+        );
+        assert!(
+            enforcer
+                .check_permission("bob@example.com", "POST", "/posts")
+                .await?
+        );
+        assert!(
+            !enforcer
+                .check_permission("alice@example.com", "POST", "/posts")
+                .await?
+        );
+
+        // 替换角色的权限和继承关系
+        let editor = MockRole {
+            name: "editor".to_string(),
+            permissions: vec![(
+                "PUT".to_string(),
+                "/posts/:id".to_string(),
+                "Edit post".to_string(),
+            )],
+            ..Default::default()
+        };
+        enforcer.upsert_role(Box::new(editor)).await?;
+
+        assert!(
+            !enforcer
+                .check_permission("bob@example.com", "GET", "/posts")
+                .await?
+        );
+        assert!(
+            !enforcer
+                .check_permission("bob@example.com", "POST", "/posts")
+                .await?
+        );
+        assert!(
+            enforcer
+                .check_permission("bob@example.com", "PUT", "/posts/1")
+                .await?
+        );
+
+        // 替换用户的角色
+        enforcer
+            .assign_user("alice@example.com", vec!["editor".to_string()])
+            .await?;
+        assert!(
+            !enforcer
+                .check_permission("alice@example.com", "GET", "/posts")
+                .await?
+        );
+        assert!(
+            enforcer
+                .check_permission("alice@example.com", "PUT", "/posts/1")
+                .await?
+        );
+
+        // 移除角色后, 用户不再拥有该角色的权限
+        enforcer.remove_role("editor").await?;
+        assert!(
+            !enforcer
+                .check_permission("bob@example.com", "PUT", "/posts/1")
+                .await?
+        );
+        assert!(enforcer.effective_permissions("bob@example.com").roles.is_empty());
+
+        enforcer
+            .assign_user("bob@example.com", vec!["viewer".to_string()])
+            .await?;
+        enforcer.unassign_user("bob@example.com").await?;
+        assert!(
+            !enforcer
+                .check_permission("bob@example.com", "GET", "/posts")
+                .await?
+        );
+
+        // 全量加载恢复为存储中的数据
+        enforcer.load_policies().await?;
+        assert!(
+            enforcer
+                .check_permission("alice@example.com", "GET", "/posts")
+                .await?
+        );
+        assert!(
+            !enforcer
+                .check_permission("alice@example.com", "PUT", "/posts/1")
+                .await?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failed_reload_keeps_policies() -> Result<()> {
+        let supplier = MockRole {
+            name: "supplier".to_string(),
+            permissions: vec![("GET".to_string(), "/products".to_string(), String::new())],
+            scopes: vec![DataScope {
+                resource: "product".to_string(),
+                rules: vec![ScopeRule::Own],
+            }],
+            ..Default::default()
+        };
+
+        let failing = Arc::new(AtomicBool::new(false));
+        let roles = FlakyRoleStore {
+            roles: Arc::new(vec![supplier]),
+            failing: failing.clone(),
+        };
+        let users = Arc::new(vec![MockUser {
+            account: "user:alice".to_string(),
+            role: "supplier".to_string(),
+        }]);
+
+        let mut enforcer = RBACEnforcer::new(roles, MockUserStore { users }).await?;
+
+        failing.store(true, Ordering::SeqCst);
+        assert!(enforcer.load_policies().await.is_err());
+
+        // 读取失败时继续使用之前加载的策略
+        assert!(
+            enforcer
+                .check_permission("user:alice", "GET", "/products")
+                .await?
+        );
+        assert_eq!(
+            enforcer.data_scope("user:alice", "product"),
+            EffectiveScope::Rules(vec![ScopeRule::Own])
+        );
+
+        failing.store(false, Ordering::SeqCst);
+        enforcer.load_policies().await?;
+        assert!(
+            enforcer
+                .check_permission("user:alice", "GET", "/products")
+                .await?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_user_subjects_do_not_collide_with_roles() -> Result<()> {
+        let viewer = MockRole {
+            name: "viewer".to_string(),
+            permissions: vec![("GET".to_string(), "/posts".to_string(), "List posts".to_string())],
+            ..Default::default()
+        };
+        let editor = MockRole {
+            name: "editor".to_string(),
+            parents: vec!["viewer".to_string()],
+            permissions: vec![(
+                "POST".to_string(),
+                "/posts".to_string(),
+                "Create post".to_string(),
+            )],
+            ..Default::default()
+        };
+        let auditor = MockRole {
+            name: "auditor".to_string(),
+            permissions: vec![("GET".to_string(), "/logs".to_string(), "List logs".to_string())],
+            ..Default::default()
+        };
+
+        let roles = Arc::new(vec![viewer, editor, auditor]);
+        let users = Arc::new(vec![
+            MockUser {
+                account: "user:editor".to_string(),
+                role: "auditor".to_string(),
+            },
+            MockUser {
+                account: "user:bob".to_string(),
+                role: "editor".to_string(),
+            },
+        ]);
+
+        let mut enforcer = RBACEnforcer::new(MockRoleStore { roles }, MockUserStore { users }).await?;
+
+        // 账号与角色同名的管理员不会得到该角色的权限
+        assert!(!enforcer.check_permission("user:editor", "POST", "/posts").await?);
+        assert!(enforcer.check_permission("user:editor", "GET", "/logs").await?);
+
+        // 修改该管理员的角色不会删除同名角色的继承关系
+        enforcer
+            .assign_user("user:editor", vec!["viewer".to_string()])
+            .await?;
+        enforcer.unassign_user("user:editor").await?;
+        assert!(enforcer.check_permission("user:bob", "GET", "/posts").await?);
+        assert!(enforcer.check_permission("user:bob", "POST", "/posts").await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_data_scope() -> Result<()> {
+        let supplier = MockRole {
+            name: "supplier".to_string(),
+            scopes: vec![DataScope {
+                resource: "product".to_string(),
+                rules: vec![ScopeRule::In {
+                    field: "supplier_id".to_string(),
+                    values: vec!["s1".to_string()],
+                }],
+            }],
+            ..Default::default()
+        };
+        let supplier_lead = MockRole {
+            name: "supplier_lead".to_string(),
+            parents: vec!["supplier".to_string()],
+            scopes: vec![DataScope {
+                resource: "product".to_string(),
+                rules: vec![ScopeRule::Own],
+            }],
+            ..Default::default()
+        };
+        let manager = MockRole {
+            name: "manager".to_string(),
+            scopes: vec![DataScope {
+                resource: "product".to_string(),
+                rules: vec![ScopeRule::All],
+            }],
+            ..Default::default()
+        };
+        let auditor = MockRole {
+            name: "auditor".to_string(),
+            ..Default::default()
+        };
+
+        let roles = Arc::new(vec![supplier, supplier_lead, manager, auditor]);
+        let users = Arc::new(vec![
+            MockUser {
+                account: "supplier@example.com".to_string(),
+                role: "supplier,auditor".to_string(),
+            },
+            MockUser {
+                account: "lead@example.com".to_string(),
+                role: "supplier_lead".to_string(),
+            },
+            MockUser {
+                account: "manager@example.com".to_string(),
+                role: "supplier,manager".to_string(),
+            },
+            MockUser {
+                account: "auditor@example.com".to_string(),
+                role: "auditor".to_string(),
+            },
+        ]);
+
+        let mut enforcer = RBACEnforcer::new(MockRoleStore { roles }, MockUserStore { users }).await?;
+
+        let only_s1 = ScopeRule::In {
+            field: "supplier_id".to_string(),
+            values: vec!["s1".to_string()],
+        };
+
+        // 没有声明范围的角色不限制数据, 与其他角色的范围取并集
+        assert_eq!(
+            enforcer.data_scope("supplier@example.com", "product"),
+            EffectiveScope::All
+        );
+        // 继承的角色范围取并集
+        assert_eq!(
+            enforcer.data_scope("lead@example.com", "product"),
+            EffectiveScope::Rules(vec![ScopeRule::Own, only_s1])
+        );
+        assert_eq!(
+            enforcer.data_scope("manager@example.com", "product"),
+            EffectiveScope::All
+        );
+        assert_eq!(
+            enforcer.data_scope("auditor@example.com", "product"),
+            EffectiveScope::All
+        );
+        assert_eq!(
+            enforcer.data_scope("supplier@example.com", "order"),
+            EffectiveScope::All
+        );
+
+        // 没有角色时无法访问任何数据
+        assert_eq!(
+            enforcer.data_scope("nobody@example.com", "product"),
+            EffectiveScope::Rules(vec![])
+        );
+
+        // 移除角色后范围随之失效
+        enforcer.remove_role("supplier").await?;
+        assert_eq!(
+            enforcer.data_scope("lead@example.com", "product"),
+            EffectiveScope::Rules(vec![ScopeRule::Own])
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_explain() -> Result<()> {
+        let viewer = MockRole {
+            name: "viewer".to_string(),
+            permissions: vec![(
+                "GET".to_string(),
+                "/posts/:id".to_string(),
+                "Get post".to_string(),
+            )],
+            ..Default::default()
+        };
+        let editor = MockRole {
+            name: "editor".to_string(),
+            parents: vec!["viewer".to_string()],
+            ..Default::default()
+        };
+
+        let roles = Arc::new(vec![viewer, editor]);
+        let users = Arc::new(vec![MockUser {
+            account: "editor@example.com".to_string(),
+            role: "editor".to_string(),
+        }]);
+
+        let mut enforcer = RBACEnforcer::new(MockRoleStore { roles }, MockUserStore { users }).await?;
+
+        // 通过继承的角色获得权限
+        let explanation = enforcer.explain("editor@example.com", "GET", "/posts/1").await?;
+        assert!(explanation.allowed);
+        assert_eq!(explanation.roles, vec!["editor", "viewer"]);
+        assert_eq!(
+            explanation.matched,
+            vec![Policy {
+                role: "viewer".to_string(),
+                method: "GET".to_string(),
+                path: "/posts/:id".to_string(),
+            }]
+        );
+
+        // 路径匹配但方法不匹配
+        let explanation = enforcer
+            .explain("editor@example.com", "DELETE", "/posts/1")
+            .await?;
+        assert!(!explanation.allowed);
+        assert!(explanation.matched.is_empty());
+        assert!(explanation.reason.contains("GET"));
+
+        let explanation = enforcer.explain("nobody@example.com", "GET", "/posts/1").await?;
+        assert!(!explanation.allowed);
+        assert!(explanation.roles.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_role_check_agrees_with_enforcer() -> Result<()> {
+        use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+        const PATTERNS: &[&str] = &[
+            "/admins",
+            "/admins/:id",
+            "/admins/:id/role",
+            "/admins/*",
+            "/messages/:message_id/retry",
+            "/:module/:id",
+            "/*",
+        ];
+        const PATHS: &[&str] = &[
+            "/admins",
+            "/admins/",
+            "/admins/1",
+            "/admins/1/role",
+            "/admins//role",
+            "/messages/1/retry",
+            "/messages/1",
+            "/roles",
+        ];
+        const METHODS: &[&str] = &["GET", "PUT", "*"];
+
+        let mut rng = StdRng::seed_from_u64(45);
+
+        for _ in 0..50 {
+            let permissions = (0..rng.gen_range(1..=3))
+                .map(|_| {
+                    (
+                        METHODS.choose(&mut rng).unwrap().to_string(),
+                        PATTERNS.choose(&mut rng).unwrap().to_string(),
+                        String::new(),
+                    )
+                })
+                .collect();
+            let role = MockRole {
+                name: "random".to_string(),
+                permissions,
+                ..Default::default()
+            };
+
+            let roles = Arc::new(vec![role.clone()]);
+            let users = Arc::new(vec![MockUser {
+                account: "user@example.com".to_string(),
+                role: "random".to_string(),
+            }]);
+            let enforcer = RBACEnforcer::new(MockRoleStore { roles }, MockUserStore { users }).await?;
+
+            for path in PATHS {
+                for method in ["GET", "PUT"] {
+                    assert_eq!(
+                        role.check_permission(method, path),
+                        enforcer
+                            .check_permission("user@example.com", method, path)
+                            .await?,
+                        "{} {} with {:?}",
+                        method,
+                        path,
+                        role.permissions
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
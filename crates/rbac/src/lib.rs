@@ -2,9 +2,14 @@ mod actor;
 mod enforcer;
 mod errors;
 mod model;
+mod pattern;
 
 pub use actor::ActorHandler;
 pub use actor::Error as ActorError;
 pub use enforcer::RBACEnforcer;
 pub use errors::{Error, Result};
-pub use model::{RBACRole, RBACRoleStore, RBACUser, RBACUserStore};
+pub use model::{
+    DataScope, EffectivePermissions, EffectiveScope, Explanation, Policy, RBACRole, RBACRoleStore, RBACUser,
+    RBACUserStore, ScopeRule,
+};
+pub use pattern::{route_match, RoutePattern};
@@ -4,12 +4,122 @@ use async_trait::async_trait;
 pub trait RBACRole: Send {
     fn to_casbin_policy(&self) -> Vec<Vec<String>>;
     fn get_name(&self) -> String;
+    /// 继承的父角色, 拥有父角色(及其祖先)的全部权限
+    fn parent_names(&self) -> Vec<String>;
     fn check_permission(&self, method: &str, path: &str) -> bool;
+    /// 角色的数据范围, 未声明的资源不受限制
+    fn data_scopes(&self) -> Vec<DataScope> {
+        vec![]
+    }
 }
 
 pub trait RBACUser: Send {
-    fn account(&self) -> String;
-    fn role_name(&self) -> String;
+    /// RBAC 中的主体标识, 需要带有前缀(例如 `user:`、`apikey:`), 不能与角色名称相同
+    fn subject(&self) -> String;
+    /// 直接拥有的角色
+    fn role_names(&self) -> Vec<String>;
+}
+
+/// 角色授予的一条权限
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Policy {
+    pub role: String,
+    pub method: String,
+    pub path: String,
+}
+
+/// 用户的有效权限, 包括多个角色和继承的角色
+///
+/// # 字段
+///
+/// * `roles` - 直接拥有和继承的全部角色
+/// * `policies` - 这些角色授予的全部权限
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EffectivePermissions {
+    pub roles: Vec<String>,
+    pub policies: Vec<Policy>,
+}
+
+/// 一次权限检查的判定说明
+///
+/// # 字段
+///
+/// * `allowed` - 是否允许访问
+/// * `roles` - 用户直接拥有和继承的全部角色
+/// * `matched` - 匹配本次请求的权限, 允许访问时至少有一条
+/// * `reason` - 允许或拒绝的原因
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    pub allowed: bool,
+    pub roles: Vec<String>,
+    pub matched: Vec<Policy>,
+    pub reason: String,
+}
+
+/// 数据范围规则, 限定角色可以访问哪些数据
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ScopeRule {
+    /// 全部数据
+    All,
+    /// 用户自己创建的数据
+    Own,
+    /// 字段取值在列表中的数据
+    In { field: String, values: Vec<String> },
+}
+
+/// 角色对某一类资源的数据范围, 多条规则满足任意一条即可访问
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataScope {
+    pub resource: String,
+    pub rules: Vec<ScopeRule>,
+}
+
+/// 用户对某一类资源的有效数据范围
+///
+/// 每个角色的范围是它和它继承的角色声明的规则的并集, 都没有声明时不限制;
+/// 用户可以访问其所有角色范围的并集, 没有任何角色时无法访问任何数据
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EffectiveScope {
+    /// 不限制
+    All,
+    /// 满足任意一条规则的数据, 规则为空时无法访问任何数据
+    Rules(Vec<ScopeRule>),
+}
+
+impl EffectiveScope {
+    /// 合并用户各个角色对同一资源声明的数据范围
+    ///
+    /// # 参数
+    ///
+    /// * `roles` - 每个角色(包括其继承的角色)对该资源声明的数据范围
+    pub fn from_roles<'a, I, S>(roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: IntoIterator<Item = &'a DataScope>,
+    {
+        let mut rules = vec![];
+
+        for scopes in roles {
+            let mut declared = false;
+            for scope in scopes {
+                declared = true;
+                rules.extend(scope.rules.iter().cloned());
+            }
+
+            // 没有声明范围的角色不限制数据
+            if !declared {
+                return Self::All;
+            }
+        }
+
+        if rules.contains(&ScopeRule::All) {
+            return Self::All;
+        }
+
+        rules.sort();
+        rules.dedup();
+        Self::Rules(rules)
+    }
 }
 
 #[async_trait]
@@ -21,3 +131,17 @@ pub trait RBACRoleStore: Send + Sync {
 pub trait RBACUserStore: Send + Sync {
     async fn find_all(&self) -> Result<Vec<Box<dyn RBACUser>>>;
 }
+
+/// 合并两个用户存储, 例如管理员和 API 密钥共用同一套角色权限
+#[async_trait]
+impl<A, B> RBACUserStore for (A, B)
+where
+    A: RBACUserStore,
+    B: RBACUserStore,
+{
+    async fn find_all(&self) -> Result<Vec<Box<dyn RBACUser>>> {
+        let mut users = self.0.find_all().await?;
+        users.extend(self.1.find_all().await?);
+        Ok(users)
+    }
+}
@@ -0,0 +1,220 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock, RwLock},
+};
+
+/// 路由模式的一段
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// 必须完全相同的路径段
+    Literal(String),
+    /// `:name`, 匹配一个非空的路径段
+    Param,
+    /// `*`, 匹配一个或多个任意路径段(可以为空), 包括其中的 `/`
+    Wildcard,
+}
+
+/// 预编译的路由模式, 权限检查和角色的权限匹配共用同一套规则
+///
+/// 模式按 `/` 分段:
+///
+/// * `:name` - 任意名称的参数, 匹配一个非空的路径段, 例如 `/admins/:id/role`
+/// * `*` - 通配符, 匹配剩余的任意路径, 例如 `/admins/*` 匹配 `/admins/1/role`
+/// * 其他 - 必须完全相同的路径段
+///
+/// 与 casbin 的 `keyMatch2` 行为一致, 但不会把路径中的 `.` 等字符当作正则表达式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoutePattern {
+    segments: Vec<Segment>,
+}
+
+impl RoutePattern {
+    /// 编译路由模式
+    pub fn new(pattern: &str) -> Self {
+        let segments = pattern
+            .split('/')
+            .map(|segment| {
+                if segment == "*" {
+                    Segment::Wildcard
+                } else if segment.starts_with(':') {
+                    Segment::Param
+                } else {
+                    Segment::Literal(segment.to_string())
+                }
+            })
+            .collect();
+
+        Self { segments }
+    }
+
+    /// 模式中通配符 `*` 的数量, 每多一个通配符匹配时的回溯次数就成倍增加
+    pub fn wildcards(&self) -> usize {
+        self.segments
+            .iter()
+            .filter(|segment| **segment == Segment::Wildcard)
+            .count()
+    }
+
+    /// 检查路径是否匹配该模式
+    pub fn matches(&self, path: &str) -> bool {
+        let parts: Vec<&str> = path.split('/').collect();
+        Self::matches_from(&self.segments, &parts)
+    }
+
+    fn matches_from(segments: &[Segment], parts: &[&str]) -> bool {
+        match segments.split_first() {
+            None => parts.is_empty(),
+            Some((Segment::Wildcard, rest)) => {
+                // 通配符至少占用一段, 尝试所有可能的长度
+                (1..=parts.len()).any(|taken| Self::matches_from(rest, &parts[taken..]))
+            }
+            Some((segment, rest)) => match parts.split_first() {
+                Some((part, parts)) => {
+                    let matched = match segment {
+                        Segment::Literal(literal) => literal == part,
+                        Segment::Param => !part.is_empty(),
+                        Segment::Wildcard => unreachable!(),
+                    };
+                    matched && Self::matches_from(rest, parts)
+                }
+                None => false,
+            },
+        }
+    }
+}
+
+/// 最多缓存的路由模式数量
+const MAX_CACHED_PATTERNS: usize = 1024;
+
+/// 已编译的路由模式, 权限策略中的模式数量有限, 编译一次后重复使用
+///
+/// casbin 的匹配函数是普通的函数指针, 无法持有加载策略时编译的模式, 因此使用全局缓存;
+/// 缓存满了之后新的模式每次重新编译, 避免缓存随调用方传入的模式无限增长
+static PATTERNS: LazyLock<RwLock<HashMap<String, Arc<RoutePattern>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// 使用缓存的路由模式检查路径是否匹配
+///
+/// # 参数
+///
+/// * `path` - 请求路径
+/// * `pattern` - 路由模式
+pub fn route_match(path: &str, pattern: &str) -> bool {
+    let cached = PATTERNS
+        .read()
+        .ok()
+        .and_then(|patterns| patterns.get(pattern).cloned());
+
+    let compiled = match cached {
+        Some(compiled) => compiled,
+        None => {
+            let compiled = Arc::new(RoutePattern::new(pattern));
+            if let Ok(mut patterns) = PATTERNS.write() {
+                if patterns.len() < MAX_CACHED_PATTERNS {
+                    patterns.insert(pattern.to_string(), compiled.clone());
+                }
+            }
+            compiled
+        }
+    };
+
+    compiled.matches(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use casbin::function_map::key_match2;
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+    #[test]
+    fn test_route_match() {
+        assert!(route_match("/admins", "/admins"));
+        assert!(!route_match("/admins/", "/admins"));
+        assert!(route_match("/admins/1/role", "/admins/:id/role"));
+        assert!(route_match("/messages/1/retry", "/messages/:message_id/retry"));
+        assert!(!route_match("/messages//retry", "/messages/:id/retry"));
+        assert!(route_match("/admins/1/role", "/admins/*"));
+        assert!(route_match("/admins/", "/admins/*"));
+        assert!(!route_match("/admins", "/admins/*"));
+        assert!(route_match("/a/x/y/b", "/a/*/b"));
+        assert!(!route_match("/a/b", "/a/*/b"));
+        assert!(!route_match("/files/axb", "/files/a.b"));
+    }
+
+    #[test]
+    fn test_pattern_cache_is_bounded() {
+        for i in 0..MAX_CACHED_PATTERNS + 10 {
+            assert!(route_match(&format!("/cache/{}", i), &format!("/cache/{}", i)));
+        }
+
+        assert!(PATTERNS.read().unwrap().len() <= MAX_CACHED_PATTERNS);
+        assert_eq!(RoutePattern::new("/a/*/b/*").wildcards(), 2);
+    }
+
+    const LITERALS: &[&str] = &[
+        "admins", "roles", "messages", "retry", "role", "1", "a-b", "x_y", "",
+    ];
+
+    fn random_pattern(rng: &mut StdRng) -> String {
+        let len = rng.gen_range(1..=4);
+        let segments: Vec<String> = (0..len)
+            .map(|_| match rng.gen_range(0..10) {
+                0..=1 => format!(":{}", ["id", "message_id", "x"].choose(rng).unwrap()),
+                2 => "*".to_string(),
+                _ => LITERALS.choose(rng).unwrap().to_string(),
+            })
+            .collect();
+
+        format!("/{}", segments.join("/"))
+    }
+
+    /// 从模式生成一个路径, 使用一半的概率让生成的路径符合模式
+    fn random_path(rng: &mut StdRng, pattern: &str) -> String {
+        if rng.gen_bool(0.5) {
+            let len = rng.gen_range(1..=5);
+            let segments: Vec<&str> = (0..len).map(|_| *LITERALS.choose(rng).unwrap()).collect();
+            return format!("/{}", segments.join("/"));
+        }
+
+        pattern
+            .split('/')
+            .map(|segment| {
+                if segment == "*" {
+                    let len = rng.gen_range(1..=3);
+                    let parts: Vec<&str> = (0..len).map(|_| *LITERALS.choose(rng).unwrap()).collect();
+                    parts.join("/")
+                } else if segment.starts_with(':') {
+                    LITERALS[..LITERALS.len() - 1].choose(rng).unwrap().to_string()
+                } else {
+                    segment.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    #[test]
+    fn test_agrees_with_key_match2() {
+        let mut rng = StdRng::seed_from_u64(20241018);
+        let mut matched = 0;
+
+        for _ in 0..5_000 {
+            let pattern = random_pattern(&mut rng);
+            let path = random_path(&mut rng, &pattern);
+
+            let expected = key_match2(&path, &pattern);
+            assert_eq!(
+                route_match(&path, &pattern),
+                expected,
+                "path {:?} pattern {:?}",
+                path,
+                pattern
+            );
+            matched += expected as usize;
+        }
+
+        // 确保生成的用例同时覆盖匹配和不匹配的情况
+        assert!(matched > 500);
+    }
+}
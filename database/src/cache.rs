@@ -0,0 +1,201 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use libs::cache::{Cache, CacheError, Result};
+use metrics::counter;
+use mongodb::{
+    bson::{doc, spec::BinarySubtype, Binary, Bson, DateTime, Document},
+    options::{IndexOptions, ReturnDocument},
+    Collection, Database, IndexModel,
+};
+
+use crate::repositories::collection_names::CACHE;
+
+/// Cache backed by a MongoDB collection
+///
+/// Every instance connected to the same database sees the same entries, so
+/// state such as refresh tokens and login failure counters is shared when
+/// the application runs behind a load balancer. Each entry is a document
+/// keyed by `_id`; values are stored as binary in `value`, counters as an
+/// integer in `count`. Expired entries are ignored on read and removed by
+/// the TTL index created in [`MongoCache::create_indexes`].
+#[derive(Clone)]
+pub struct MongoCache {
+    collection: Collection<Document>,
+}
+
+impl MongoCache {
+    pub fn new(database: Database) -> Self {
+        Self {
+            collection: database.collection(CACHE),
+        }
+    }
+
+    /// Creates the TTL index removing expired entries in the background
+    pub async fn create_indexes(&self) -> crate::Result<()> {
+        let index = IndexModel::builder()
+            .keys(doc! { "expires_at": 1 })
+            .options(IndexOptions::builder().expire_after(Duration::ZERO).build())
+            .build();
+        self.collection.create_index(index).await?;
+        Ok(())
+    }
+}
+
+/// Filter matching the entry of a key unless it has expired
+fn alive(key: &str, now: DateTime) -> Document {
+    doc! {
+        "_id": key,
+        "$or": [{ "expires_at": Bson::Null }, { "expires_at": { "$gt": now } }],
+    }
+}
+
+fn expires_at(ttl: Option<u64>, now: DateTime) -> Bson {
+    match ttl {
+        Some(ttl) => Bson::DateTime(DateTime::from_millis(now.timestamp_millis() + ttl as i64 * 1000)),
+        None => Bson::Null,
+    }
+}
+
+/// Reads the value of an entry, counters are returned as decimal strings
+fn decode(document: &Document) -> Option<Vec<u8>> {
+    if let Ok(count) = document.get_i64("count") {
+        return Some(count.to_string().into_bytes());
+    }
+
+    document.get_binary_generic("value").ok().cloned()
+}
+
+fn cache_error(e: mongodb::error::Error) -> CacheError {
+    CacheError::Other(e.to_string())
+}
+
+#[async_trait]
+impl Cache for MongoCache {
+    async fn get_raw(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let document = self
+            .collection
+            .find_one(alive(key, DateTime::now()))
+            .await
+            .map_err(cache_error)?;
+        let data = document.as_ref().and_then(decode);
+
+        let result = if data.is_some() { "hit" } else { "miss" };
+        counter!("cache_requests_total", "cache" => "mongodb", "result" => result).increment(1);
+
+        Ok(data)
+    }
+
+    async fn set_raw(&self, key: &str, value: Vec<u8>, ttl: Option<u64>) -> Result<()> {
+        let value = Binary {
+            subtype: BinarySubtype::Generic,
+            bytes: value,
+        };
+        self.collection
+            .update_one(
+                doc! { "_id": key },
+                doc! {
+                    "$set": { "value": value, "expires_at": expires_at(ttl, DateTime::now()) },
+                    "$unset": { "count": "" },
+                },
+            )
+            .upsert(true)
+            .await
+            .map_err(cache_error)?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool> {
+        let result = self
+            .collection
+            .delete_one(doc! { "_id": key })
+            .await
+            .map_err(cache_error)?;
+
+        Ok(result.deleted_count > 0)
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        let count = self
+            .collection
+            .count_documents(alive(key, DateTime::now()))
+            .await
+            .map_err(cache_error)?;
+
+        Ok(count > 0)
+    }
+
+    async fn expire(&self, key: &str, ttl: Duration) -> Result<bool> {
+        let now = DateTime::now();
+        let result = self
+            .collection
+            .update_one(
+                alive(key, now),
+                doc! { "$set": { "expires_at": expires_at(Some(ttl.as_secs()), now) } },
+            )
+            .await
+            .map_err(cache_error)?;
+
+        Ok(result.matched_count > 0)
+    }
+
+    async fn incr(&self, key: &str, ttl: Option<u64>) -> Result<u64> {
+        let now = DateTime::now();
+
+        // an expired counter starts over from zero
+        self.collection
+            .delete_one(doc! { "_id": key, "expires_at": { "$lte": now } })
+            .await
+            .map_err(cache_error)?;
+
+        let document = self
+            .collection
+            .find_one_and_update(
+                doc! { "_id": key },
+                doc! {
+                    "$inc": { "count": 1_i64 },
+                    "$setOnInsert": { "expires_at": expires_at(ttl, now) },
+                },
+            )
+            .upsert(true)
+            .return_document(ReturnDocument::After)
+            .await
+            .map_err(cache_error)?;
+
+        document
+            .and_then(|document| document.get_i64("count").ok())
+            .map(|count| count as u64)
+            .ok_or_else(|| CacheError::DeserializationError("计数器不是数字".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let value = doc! {
+            "_id": "key",
+            "value": Binary { subtype: BinarySubtype::Generic, bytes: b"value".to_vec() },
+        };
+        assert_eq!(decode(&value), Some(b"value".to_vec()));
+
+        let counter = doc! { "_id": "key", "count": 3_i64 };
+        assert_eq!(decode(&counter), Some(b"3".to_vec()));
+
+        assert_eq!(decode(&doc! { "_id": "key" }), None);
+    }
+
+    #[test]
+    fn test_expires_at() {
+        let now = DateTime::from_millis(1_000);
+
+        assert_eq!(expires_at(None, now), Bson::Null);
+        assert_eq!(
+            expires_at(Some(2), now),
+            Bson::DateTime(DateTime::from_millis(3_000))
+        );
+    }
+}
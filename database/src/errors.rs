@@ -22,6 +22,9 @@ pub type Result<T> = std::result::Result<T, Error>;
 // 将数据库错误转换为服务层错误
 impl From<Error> for ServiceError {
     fn from(value: Error) -> Self {
-        ServiceError::RepositoryError(value.to_string())
+        match value {
+            Error::OptimisticLockingError => ServiceError::OptimisticLock,
+            value => ServiceError::RepositoryError(value.to_string()),
+        }
     }
 }
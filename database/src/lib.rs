@@ -1,3 +1,4 @@
+pub mod cache;
 pub mod errors;
 pub mod mongodb;
 pub mod repositories;
@@ -1,5 +1,16 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
 use super::errors::Result;
-use mongodb::{Client, Database};
+use metrics::{counter, histogram};
+use mongodb::{
+    bson::Document,
+    event::{command::CommandEvent, EventHandler},
+    options::ClientOptions,
+    Client, Database,
+};
 
 /// connect to the database
 ///
@@ -9,7 +20,95 @@ use mongodb::{Client, Database};
 ///
 /// This function will return an error if connection to the database fails.
 pub async fn connect(uri: &str, db_name: &str) -> Result<(Client, Database)> {
-    let client = mongodb::Client::with_uri_str(uri).await?;
+    let mut options = ClientOptions::parse(uri).await?;
+    options.command_event_handler = Some(command_metrics());
+
+    let client = Client::with_options(options)?;
     let database = client.database(db_name);
     Ok((client, database))
 }
+
+/// Records the duration of every command per collection
+///
+/// Each repository works on its own collection, so the collection label
+/// tells which repository issued the command. Completion events do not
+/// carry the collection, it is remembered by request id when the command starts.
+fn command_metrics() -> EventHandler<CommandEvent> {
+    let collections: Arc<Mutex<HashMap<i32, String>>> = Arc::default();
+
+    EventHandler::callback(move |event| match event {
+        CommandEvent::Started(event) => {
+            if let Some(collection) = command_collection(&event.command_name, &event.command) {
+                if let Ok(mut collections) = collections.lock() {
+                    collections.insert(event.request_id, collection);
+                }
+            }
+        }
+        CommandEvent::Succeeded(event) => {
+            let collection = collections
+                .lock()
+                .ok()
+                .and_then(|mut c| c.remove(&event.request_id));
+            if let Some(collection) = collection {
+                histogram!(
+                    "mongodb_command_duration_seconds",
+                    "collection" => collection,
+                    "command" => event.command_name
+                )
+                .record(event.duration.as_secs_f64());
+            }
+        }
+        CommandEvent::Failed(event) => {
+            let collection = collections
+                .lock()
+                .ok()
+                .and_then(|mut c| c.remove(&event.request_id));
+            if let Some(collection) = collection {
+                counter!(
+                    "mongodb_command_failures_total",
+                    "collection" => collection.clone(),
+                    "command" => event.command_name.clone()
+                )
+                .increment(1);
+                histogram!(
+                    "mongodb_command_duration_seconds",
+                    "collection" => collection,
+                    "command" => event.command_name
+                )
+                .record(event.duration.as_secs_f64());
+            }
+        }
+        _ => {}
+    })
+}
+
+/// Returns the collection a command works on, `None` for database level commands
+fn command_collection(command_name: &str, command: &Document) -> Option<String> {
+    let key = match command_name {
+        "getMore" => "collection",
+        name => name,
+    };
+
+    command.get_str(key).ok().map(|collection| collection.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::doc;
+
+    #[test]
+    fn test_command_collection() {
+        let find = doc! { "find": "admins", "filter": { "id": "1" } };
+        assert_eq!(command_collection("find", &find).as_deref(), Some("admins"));
+
+        let get_more = doc! { "getMore": 1_i64, "collection": "messages" };
+        assert_eq!(
+            command_collection("getMore", &get_more).as_deref(),
+            Some("messages")
+        );
+
+        let aggregate = doc! { "aggregate": 1, "pipeline": [] };
+        assert_eq!(command_collection("aggregate", &aggregate), None);
+    }
+}
@@ -0,0 +1,98 @@
+use async_trait::async_trait;
+use mongodb::{bson::doc, Database};
+
+use super::{collection_names::API_KEY, IRepository};
+use crate::errors::Error;
+use entities::ApiKey;
+use rbac::{Error as RBACError, RBACUser, RBACUserStore, Result as RBACResult};
+use services::api_key::IApiKeyRepository;
+use services::errors::Result as ServiceResult;
+
+/// API 密钥仓储结构体
+///
+/// # 字段
+///
+/// * `coll_name` - MongoDB集合名称
+/// * `database` - MongoDB数据库实例
+pub struct ApiKeyRepository {
+    pub coll_name: String,
+    database: Database,
+}
+
+impl ApiKeyRepository {
+    pub fn new(database: Database) -> Self {
+        Self {
+            coll_name: API_KEY.to_string(),
+            database,
+        }
+    }
+}
+
+impl IRepository<ApiKey> for ApiKeyRepository {
+    fn get_collection_name(&self) -> &str {
+        &self.coll_name
+    }
+
+    fn get_database(&self) -> &Database {
+        &self.database
+    }
+}
+
+#[async_trait]
+impl RBACUserStore for ApiKeyRepository {
+    /// 获取所有未删除的 API 密钥, 禁用和过期的密钥由授权中间件拒绝
+    async fn find_all(&self) -> RBACResult<Vec<Box<dyn RBACUser>>> {
+        let api_keys = IRepository::find_all(self)
+            .await
+            .map_err(|e| RBACError::StoreError(e.to_string()))?;
+
+        Ok(api_keys
+            .into_iter()
+            .map(|api_key| Box::new(api_key) as Box<dyn RBACUser>)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl IApiKeyRepository for ApiKeyRepository {
+    async fn create(&self, api_key: &ApiKey) -> ServiceResult<()> {
+        IRepository::create(self, api_key).await?;
+        Ok(())
+    }
+
+    async fn update(&self, api_key: &ApiKey) -> ServiceResult<()> {
+        IRepository::update(self, api_key).await?;
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: &str) -> ServiceResult<Option<ApiKey>> {
+        Ok(IRepository::find_by_id(self, id).await?)
+    }
+
+    async fn find_by_key_id(&self, key_id: &str) -> ServiceResult<Option<ApiKey>> {
+        let collection = self.database.collection::<ApiKey>(self.coll_name.as_str());
+        let api_key = collection
+            .find_one(doc! { "key_id": key_id, "deleted_at": 0 })
+            .await
+            .map_err(Error::DatabaseError)?;
+
+        Ok(api_key)
+    }
+
+    async fn find_all(&self) -> ServiceResult<Vec<ApiKey>> {
+        Ok(IRepository::find_all(self).await?)
+    }
+
+    async fn update_last_used(&self, id: &str, last_used_at: i64) -> ServiceResult<()> {
+        let collection = self.database.collection::<ApiKey>(self.coll_name.as_str());
+        collection
+            .update_one(
+                doc! { "id": id },
+                doc! { "$set": { "last_used_at": last_used_at } },
+            )
+            .await
+            .map_err(Error::DatabaseError)?;
+
+        Ok(())
+    }
+}
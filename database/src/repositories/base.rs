@@ -133,6 +133,27 @@ where
         Ok(())
     }
 
+    /// Creates multiple entities in a single round trip
+    ///
+    /// # Arguments
+    ///
+    /// * `entities` - The entities to create
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    async fn create_many(&self, entities: &[T]) -> Result<()> {
+        if entities.is_empty() {
+            return Ok(());
+        }
+
+        self.get_database()
+            .collection::<T>(self.get_collection_name())
+            .insert_many(entities)
+            .await?;
+        Ok(())
+    }
+
     /// Finds an entity by its ID
     ///
     /// # Arguments
@@ -0,0 +1,131 @@
+use async_trait::async_trait;
+use mongodb::{bson::doc, options::ReturnDocument, Database};
+
+use entities::{Broadcast, BroadcastStatus};
+use services::broadcast::{BroadcastQuery, IBroadcastRepository};
+use services::errors::Result as ServiceResult;
+
+use super::base::{IFilter, IPaginator};
+use super::{collection_names::BROADCAST, IRepository};
+use crate::errors::Error;
+
+/// 广播仓储结构体
+///
+/// 负责处理广播相关的数据库操作
+///
+/// # 字段
+///
+/// * `coll_name` - MongoDB集合名称
+/// * `database` - MongoDB数据库实例
+pub struct BroadcastRepository {
+    pub coll_name: String,
+    database: Database,
+}
+
+impl BroadcastRepository {
+    pub fn new(database: Database) -> Self {
+        Self {
+            coll_name: BROADCAST.to_string(),
+            database,
+        }
+    }
+}
+
+impl IRepository<Broadcast> for BroadcastRepository {
+    fn get_collection_name(&self) -> &str {
+        &self.coll_name
+    }
+
+    fn get_database(&self) -> &Database {
+        &self.database
+    }
+}
+
+impl IFilter for BroadcastQuery {
+    fn to_doc(&self) -> mongodb::bson::Document {
+        let mut filter = doc! {
+            "deleted_at": 0
+        };
+
+        if let Some(status) = &self.status {
+            filter.insert("status", status.to_string());
+        }
+
+        filter
+    }
+}
+
+impl IPaginator for BroadcastQuery {
+    fn skip(&self) -> u64 {
+        ((self.page.max(1) - 1) * self.page_size).max(0) as u64
+    }
+
+    fn limit(&self) -> i64 {
+        self.page_size
+    }
+}
+
+#[async_trait]
+impl IBroadcastRepository for BroadcastRepository {
+    async fn create(&self, broadcast: &Broadcast) -> ServiceResult<()> {
+        IRepository::create(self, broadcast).await?;
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: &str) -> ServiceResult<Option<Broadcast>> {
+        let broadcast = IRepository::find_by_id(self, id).await?;
+        Ok(broadcast)
+    }
+
+    async fn query(&self, query: &BroadcastQuery) -> ServiceResult<Vec<Broadcast>> {
+        Ok(IRepository::search_slice(self, query).await?)
+    }
+
+    /// 计数通过 `$inc` 累加, 多个实例同时投递同一广播时不会丢失更新
+    async fn record_delivery(&self, id: &str, sent: i64, failed: i64) -> ServiceResult<Option<Broadcast>> {
+        let broadcast = self
+            .database
+            .collection::<Broadcast>(self.coll_name.as_str())
+            .find_one_and_update(
+                doc! { "id": id, "deleted_at": 0 },
+                doc! { "$inc": { "sent": sent, "failed": failed } },
+            )
+            .return_document(ReturnDocument::After)
+            .await
+            .map_err(Error::DatabaseError)?;
+
+        Ok(broadcast)
+    }
+
+    async fn mark_completed(&self, id: &str) -> ServiceResult<()> {
+        self.database
+            .collection::<Broadcast>(self.coll_name.as_str())
+            .update_one(
+                doc! { "id": id, "status": BroadcastStatus::Sending.to_string() },
+                doc! {
+                    "$set": { "status": BroadcastStatus::Completed.to_string() },
+                    "$inc": { "version": 1_i64 },
+                },
+            )
+            .await
+            .map_err(Error::DatabaseError)?;
+
+        Ok(())
+    }
+
+    async fn mark_failed(&self, id: &str, total: u64) -> ServiceResult<()> {
+        self.database
+            .collection::<Broadcast>(self.coll_name.as_str())
+            .update_one(
+                doc! { "id": id },
+                doc! {
+                    "$set": { "status": BroadcastStatus::Failed.to_string(), "total": total as i64 },
+                    "$inc": { "version": 1_i64 },
+                },
+            )
+            .await
+            .map_err(Error::DatabaseError)?;
+
+        Ok(())
+    }
+}
@@ -2,14 +2,22 @@ pub const ADMIN: &str = "admins";
 
 pub const ROLE: &str = "roles";
 
+pub const API_KEY: &str = "api_keys";
+
 pub const OPERATION_LOG: &str = "operation_logs";
 
 pub const MESSAGE: &str = "messages";
 
 pub const INTERNAL_MESSAGE: &str = "internal_messages";
 
+pub const BROADCAST: &str = "broadcasts";
+
+pub const WEBHOOK_ENDPOINT: &str = "webhook_endpoints";
+
 pub const PRODUCT: &str = "products";
 pub const SKU: &str = "skus";
 pub const CATEGORY: &str = "categories";
 pub const BRAND: &str = "brands";
 pub const SUPPLIER: &str = "suppliers";
+
+pub const CACHE: &str = "cache";
@@ -1,10 +1,13 @@
-use super::{base::cursor_to_vec, collection_names::INTERNAL_MESSAGE, IRepository};
+use super::base::{IFilter, IPaginator};
+use super::{collection_names::INTERNAL_MESSAGE, IRepository};
 use crate::errors::Error;
 use async_trait::async_trait;
-use entities::InternalMessage;
-use mongodb::{bson::doc, Database};
+use chrono::Local;
+use entities::{InternalMessage, InternalMessageStatus};
+use mongodb::bson::{doc, Document};
+use mongodb::Database;
 use services::errors::Result as ServiceResult;
-use services::internal_message::IInternalMessageRepository;
+use services::internal_message::{IInternalMessageRepository, InternalMessageQuery};
 
 pub struct InternalMessageRepository {
     pub coll_name: String,
@@ -18,6 +21,26 @@ impl InternalMessageRepository {
             database,
         }
     }
+
+    /// 对接收者自己的未删除消息执行更新, 返回匹配的数量
+    async fn update_own(
+        &self,
+        mut filter: Document,
+        recipient: &str,
+        update: Document,
+    ) -> ServiceResult<u64> {
+        filter.insert("recipient", recipient);
+        filter.insert("deleted_at", 0);
+
+        let result = self
+            .database
+            .collection::<InternalMessage>(self.coll_name.as_str())
+            .update_many(filter, update)
+            .await
+            .map_err(Error::DatabaseError)?;
+
+        Ok(result.matched_count)
+    }
 }
 
 impl IRepository<InternalMessage> for InternalMessageRepository {
@@ -30,6 +53,45 @@ impl IRepository<InternalMessage> for InternalMessageRepository {
     }
 }
 
+/// 未归档的消息没有 `archived_at` 字段或其值为 0
+fn archived_filter(archived: bool) -> Document {
+    if archived {
+        doc! { "$gt": 0 }
+    } else {
+        doc! { "$not": { "$gt": 0 } }
+    }
+}
+
+impl IFilter for InternalMessageQuery {
+    fn to_doc(&self) -> Document {
+        let mut filter = doc! {
+            "recipient": &self.recipient,
+            "deleted_at": 0,
+            "archived_at": archived_filter(self.archived),
+        };
+
+        if let Some(status) = &self.status {
+            filter.insert("status", status);
+        }
+
+        if let Some(category) = &self.category {
+            filter.insert("category", category.to_string());
+        }
+
+        filter
+    }
+}
+
+impl IPaginator for InternalMessageQuery {
+    fn skip(&self) -> u64 {
+        ((self.page.max(1) - 1) * self.page_size).max(0) as u64
+    }
+
+    fn limit(&self) -> i64 {
+        self.page_size
+    }
+}
+
 #[async_trait]
 impl IInternalMessageRepository for InternalMessageRepository {
     async fn create(&self, message: &InternalMessage) -> ServiceResult<()> {
@@ -37,30 +99,28 @@ impl IInternalMessageRepository for InternalMessageRepository {
         Ok(())
     }
 
-    async fn find_by_recipient_with_filter(
-        &self,
-        recipient: &str,
-        status: Option<String>,
-        skip: u64,
-        limit: i64,
-    ) -> ServiceResult<Vec<InternalMessage>> {
-        let mut filter = doc! { "recipient": recipient, "deleted_at": 0 };
-        if let Some(status) = status {
-            filter.insert("status", status);
-        }
+    async fn find_by_query(&self, query: &InternalMessageQuery) -> ServiceResult<Vec<InternalMessage>> {
+        Ok(IRepository::search_slice(self, query).await?)
+    }
 
-        let cursor = self
+    async fn count_by_query(&self, query: &InternalMessageQuery) -> ServiceResult<u64> {
+        Ok(IRepository::search_count(self, query).await?)
+    }
+
+    async fn count_unread(&self, recipient: &str) -> ServiceResult<u64> {
+        let count = self
             .database
             .collection::<InternalMessage>(self.coll_name.as_str())
-            .find(filter)
-            .skip(skip)
-            .limit(limit)
-            .sort(doc! { "created_at": -1 })
+            .count_documents(doc! {
+                "recipient": recipient,
+                "status": InternalMessageStatus::Unread.to_string(),
+                "deleted_at": 0,
+                "archived_at": archived_filter(false),
+            })
             .await
-            .map_err(|e| Error::DatabaseError(e))?;
+            .map_err(Error::DatabaseError)?;
 
-        let slice = cursor_to_vec(cursor).await?;
-        Ok(slice)
+        Ok(count)
     }
 
     async fn mark_as_read(&self, id: &str, recipient: &str) -> ServiceResult<bool> {
@@ -80,8 +140,52 @@ impl IInternalMessageRepository for InternalMessageRepository {
                 },
             )
             .await
-            .map_err(|e| Error::DatabaseError(e))?;
+            .map_err(Error::DatabaseError)?;
 
         Ok(result.modified_count > 0)
     }
+
+    async fn mark_many_as_read(&self, ids: &[String], recipient: &str) -> ServiceResult<u64> {
+        self.update_own(
+            doc! { "id": { "$in": ids }, "status": InternalMessageStatus::Unread.to_string() },
+            recipient,
+            doc! { "$set": { "status": InternalMessageStatus::Read.to_string() } },
+        )
+        .await
+    }
+
+    async fn mark_all_as_read(&self, recipient: &str) -> ServiceResult<u64> {
+        self.update_own(
+            doc! { "status": InternalMessageStatus::Unread.to_string() },
+            recipient,
+            doc! { "$set": { "status": InternalMessageStatus::Read.to_string() } },
+        )
+        .await
+    }
+
+    async fn archive(&self, id: &str, recipient: &str) -> ServiceResult<bool> {
+        let now = Local::now().timestamp();
+        let matched = self
+            .update_own(
+                doc! { "id": id },
+                recipient,
+                doc! { "$set": { "archived_at": now } },
+            )
+            .await?;
+
+        Ok(matched > 0)
+    }
+
+    async fn delete(&self, id: &str, recipient: &str) -> ServiceResult<bool> {
+        let now = Local::now().timestamp();
+        let matched = self
+            .update_own(
+                doc! { "id": id },
+                recipient,
+                doc! { "$set": { "deleted_at": now } },
+            )
+            .await?;
+
+        Ok(matched > 0)
+    }
 }
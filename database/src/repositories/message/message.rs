@@ -1,6 +1,12 @@
 use super::super::{collection_names::MESSAGE, IRepository};
+use crate::errors::Result;
 use entities::Message;
-use mongodb::Database;
+use mongodb::{
+    bson::{doc, Document},
+    options::ReturnDocument,
+    Database,
+};
+use services::notification::MessageLease;
 
 pub struct MessageRepository {
     pub coll_name: String,
@@ -14,6 +20,83 @@ impl MessageRepository {
             database,
         }
     }
+
+    /// Atomically leases the oldest message matching the filter
+    ///
+    /// Only messages that are not leased, or whose lease has expired, and
+    /// that are not deferred past the lease time are considered. The version
+    /// is bumped so that a worker still holding an expired lease fails its
+    /// optimistic update.
+    ///
+    /// # Arguments
+    /// * `filter` - Additional criteria the message must match
+    /// * `lease` - The lease to apply to the claimed message
+    ///
+    /// # Returns
+    /// * `Result<Option<Message>>` - The claimed message, or None if nothing is claimable
+    pub(super) async fn lease_one(
+        &self,
+        mut filter: Document,
+        lease: &MessageLease,
+    ) -> Result<Option<Message>> {
+        filter.insert("deleted_at", 0);
+        filter.insert("deliver_after", doc! { "$not": { "$gt": lease.now as i64 } });
+        filter.insert(
+            "$or",
+            vec![
+                doc! { "locked_by": null },
+                doc! { "lease_until": { "$lte": lease.now as i64 } },
+            ],
+        );
+
+        let message = self
+            .database
+            .collection::<Message>(self.coll_name.as_str())
+            .find_one_and_update(
+                filter,
+                doc! {
+                    "$set": {
+                        "locked_by": &lease.worker,
+                        "lease_until": lease.until as i64,
+                    },
+                    "$inc": { "version": 1_i64 },
+                },
+            )
+            .sort(doc! { "created_at": 1 })
+            .return_document(ReturnDocument::After)
+            .await?;
+
+        Ok(message)
+    }
+
+    /// Extends the lease of a message still held by the lease's worker
+    ///
+    /// # Arguments
+    /// * `message` - The message as returned by the claim
+    /// * `lease` - The new lease
+    ///
+    /// # Returns
+    /// * `Result<Option<Message>>` - The renewed message, or None if the version or holder changed
+    pub(super) async fn renew(&self, message: &Message, lease: &MessageLease) -> Result<Option<Message>> {
+        let message = self
+            .database
+            .collection::<Message>(self.coll_name.as_str())
+            .find_one_and_update(
+                doc! {
+                    "id": &message.base.id,
+                    "version": message.base.version as i64,
+                    "locked_by": &lease.worker,
+                },
+                doc! {
+                    "$set": { "lease_until": lease.until as i64 },
+                    "$inc": { "version": 1_i64 },
+                },
+            )
+            .return_document(ReturnDocument::After)
+            .await?;
+
+        Ok(message)
+    }
 }
 
 impl IRepository<Message> for MessageRepository {
@@ -1,4 +1,5 @@
 mod service_impl;
+#[allow(clippy::module_inception)]
 mod message;
 
 pub use message::MessageRepository;
@@ -1,12 +1,11 @@
-use super::super::base::{cursor_to_vec, IFilter, IPaginator};
+use super::super::base::{IFilter, IPaginator};
 use super::super::IRepository;
 use super::MessageRepository;
-use crate::errors::Error;
 use async_trait::async_trait;
-use entities::{Message, MessageStatus};
+use entities::{Message, MessageStatus, MAX_RETRY_TIMES};
 use mongodb::bson::doc;
 use services::errors::Result as ServiceResult;
-use services::notification::{IMessageRepository, MessageQuery};
+use services::notification::{IMessageRepository, MessageLease, MessageQuery};
 
 /// Converts MessageQuery into MongoDB query document
 ///
@@ -29,6 +28,10 @@ impl IFilter for MessageQuery {
             filter.insert("status", status);
         }
 
+        if let Some(broadcast_id) = &self.broadcast_id {
+            filter.insert("broadcast_id", broadcast_id);
+        }
+
         filter
     }
 }
@@ -70,6 +73,18 @@ impl IMessageRepository for MessageRepository {
         Ok(())
     }
 
+    /// Creates a batch of messages in the database
+    ///
+    /// # Arguments
+    /// * `messages` - The messages to create
+    ///
+    /// # Returns
+    /// * `ServiceResult<()>` - Success or error result
+    async fn create_many(&self, messages: &[Message]) -> ServiceResult<()> {
+        IRepository::create_many(self, messages).await?;
+        Ok(())
+    }
+
     /// Updates an existing message in the database
     ///
     /// # Arguments
@@ -94,42 +109,68 @@ impl IMessageRepository for MessageRepository {
         Ok(message)
     }
 
-    /// Retrieves all messages marked as failed
+    /// Claims the oldest claimable message with the given status
+    ///
+    /// Failed messages that reached the retry limit are never claimed.
+    ///
+    /// # Arguments
+    /// * `status` - The status the message must have
+    /// * `broadcast_id` - Restricts the claim to messages of this broadcast
+    /// * `lease` - The lease to apply to the claimed message
+    ///
+    /// # Returns
+    /// * `ServiceResult<Option<Message>>` - The claimed message or None if nothing is claimable
+    async fn claim_next(
+        &self,
+        status: MessageStatus,
+        broadcast_id: Option<&str>,
+        lease: &MessageLease,
+    ) -> ServiceResult<Option<Message>> {
+        let mut filter = doc! { "status": status.to_string() };
+        if status == MessageStatus::Failed {
+            filter.insert("retry_times", doc! { "$lt": MAX_RETRY_TIMES as i32 });
+        }
+
+        if let Some(broadcast_id) = broadcast_id {
+            filter.insert("broadcast_id", broadcast_id);
+        }
+
+        Ok(self.lease_one(filter, lease).await?)
+    }
+
+    /// Claims a specific message by its unique identifier
     ///
-    /// This includes messages that:
-    /// - Have a Failed status
-    /// - Are not deleted (deleted_at = 0)
+    /// # Arguments
+    /// * `id` - The message ID to claim
+    /// * `status` - The status the message must have
+    /// * `lease` - The lease to apply to the claimed message
     ///
     /// # Returns
-    /// * `ServiceResult<Vec<Message>>` - List of failed messages
-    async fn find_failed_messages(&self) -> ServiceResult<Vec<Message>> {
-        let cursor = self
-            .get_database()
-            .collection::<Message>(self.get_collection_name())
-            .find(doc! {
-                "status": MessageStatus::Failed.to_string(),
-                "deleted_at": 0
-            })
-            .await
-            .map_err(|e| Error::DatabaseError(e))?;
-
-        let slice = cursor_to_vec(cursor).await?;
-        Ok(slice)
+    /// * `ServiceResult<Option<Message>>` - The claimed message or None if it is leased elsewhere
+    async fn claim_by_id(
+        &self,
+        id: &str,
+        status: MessageStatus,
+        lease: &MessageLease,
+    ) -> ServiceResult<Option<Message>> {
+        let filter = doc! { "id": id, "status": status.to_string() };
+
+        Ok(self.lease_one(filter, lease).await?)
     }
 
-    /// Retrieves all messages with pending status
+    /// Renews the lease of a message this worker claimed earlier
+    ///
+    /// Matches on the version seen when claiming, so the renewal fails once
+    /// the lease has expired and another worker claimed the message.
+    ///
+    /// # Arguments
+    /// * `message` - The message as returned by the claim
+    /// * `lease` - The new lease
     ///
     /// # Returns
-    /// * `ServiceResult<Vec<Message>>` - List of pending messages
-    async fn find_pending_messages(&self) -> ServiceResult<Vec<Message>> {
-        let cursor = self
-            .get_database()
-            .collection::<Message>(self.get_collection_name())
-            .find(doc! { "status": MessageStatus::Pending.to_string() })
-            .await
-            .map_err(|e| Error::DatabaseError(e))?;
-        let slice = cursor_to_vec(cursor).await?;
-        Ok(slice)
+    /// * `ServiceResult<Option<Message>>` - The renewed message or None if the lease was lost
+    async fn renew_lease(&self, message: &Message, lease: &MessageLease) -> ServiceResult<Option<Message>> {
+        Ok(self.renew(message, lease).await?)
     }
 
     /// Queries messages based on provided filters and pagination
@@ -1,16 +1,22 @@
+mod api_key;
 mod base;
-mod collection_names;
+mod broadcast;
+pub(crate) mod collection_names;
 mod internal_message;
 mod message;
 mod operation_log;
 mod product;
 mod role;
 mod user;
+mod webhook;
 
+pub use api_key::ApiKeyRepository;
 pub use base::IRepository;
+pub use broadcast::BroadcastRepository;
 pub use internal_message::InternalMessageRepository;
 pub use message::MessageRepository;
 pub use operation_log::OperationLogRepository;
 pub use product::*;
 pub use role::RoleRepository;
 pub use user::AdminRepository;
+pub use webhook::WebhookEndpointRepository;
@@ -1,9 +1,8 @@
-use crate::errors::Error;
 use async_trait::async_trait;
 use entities::product::Brand;
 use mongodb::Database;
+use services::brand::IBrandRepository;
 use services::errors::Result as ServiceResult;
-use services::product::IBrandRepository;
 
 use super::super::collection_names::BRAND;
 use super::super::IRepository;
@@ -49,4 +48,4 @@ impl IBrandRepository for BrandRepository {
     async fn find_all(&self) -> ServiceResult<Vec<Brand>> {
         Ok(IRepository::find_all(self).await?)
     }
-} 
\ No newline at end of file
+}
@@ -3,8 +3,8 @@ use crate::repositories::base::cursor_to_vec;
 use async_trait::async_trait;
 use entities::product::Category;
 use mongodb::{bson::doc, Database};
+use services::category::ICategoryRepository;
 use services::errors::Result as ServiceResult;
-use services::product::ICategoryRepository;
 
 use super::super::collection_names::CATEGORY;
 use super::super::IRepository;
@@ -60,8 +60,8 @@ impl ICategoryRepository for CategoryRepository {
                 "deleted_at": 0
             })
             .await
-            .map_err(|e| Error::DatabaseError(e))?;
+            .map_err(Error::DatabaseError)?;
 
         Ok(cursor_to_vec(cursor).await?)
     }
-} 
\ No newline at end of file
+}
@@ -1,5 +1,6 @@
 mod brand;
 mod category;
+#[allow(clippy::module_inception)]
 mod product;
 mod supplier;
 
@@ -1,10 +1,11 @@
 use crate::errors::Error;
 use crate::repositories::base::cursor_to_vec;
 use async_trait::async_trait;
-use entities::product::{Product, SKU};
+use entities::product::Product;
 use mongodb::{bson::doc, Database};
 use services::errors::Result as ServiceResult;
-use services::product::{IProductRepository, ProductDetail, ProductSearchParams, ProductSortBy};
+use services::product::{IProductRepository, ProductDetail, ProductSearchParams};
+use services::role::DataFilter;
 
 use super::super::base::{IFilter, IPaginator};
 use super::super::collection_names::{PRODUCT, SKU as SKU_COLL};
@@ -94,6 +95,45 @@ impl IPaginator for ProductSearchParams {
     }
 }
 
+/// 附加了数据范围的商品搜索条件
+struct ScopedSearch<'a> {
+    params: &'a ProductSearchParams,
+    scope: &'a DataFilter,
+}
+
+impl IFilter for ScopedSearch<'_> {
+    fn to_doc(&self) -> mongodb::bson::Document {
+        let mut filter = self.params.to_doc();
+
+        // 数据范围, 与关键词的 $or 条件分开放在 $and 中
+        if let DataFilter::AnyOf(conditions) = self.scope {
+            let scope: Vec<_> = conditions
+                .iter()
+                .map(|condition| doc! { &condition.field: { "$in": &condition.values } })
+                .collect();
+
+            if scope.is_empty() {
+                // 没有任何可访问的数据
+                filter.insert("id", doc! { "$in": [] });
+            } else {
+                filter.insert("$and", vec![doc! { "$or": scope }]);
+            }
+        }
+
+        filter
+    }
+}
+
+impl IPaginator for ScopedSearch<'_> {
+    fn skip(&self) -> u64 {
+        self.params.skip()
+    }
+
+    fn limit(&self) -> i64 {
+        self.params.limit()
+    }
+}
+
 impl IRepository<Product> for ProductRepository {
     fn get_collection_name(&self) -> &str {
         &self.coll_name
@@ -131,7 +171,7 @@ impl IProductRepository for ProductRepository {
                 "deleted_at": 0
             })
             .await
-            .map_err(|e| Error::DatabaseError(e))?;
+            .map_err(Error::DatabaseError)?;
 
         Ok(cursor_to_vec(cursor).await?)
     }
@@ -145,13 +185,24 @@ impl IProductRepository for ProductRepository {
                 "deleted_at": 0
             })
             .await
-            .map_err(|e| Error::DatabaseError(e))?;
+            .map_err(Error::DatabaseError)?;
 
         Ok(cursor_to_vec(cursor).await?)
     }
 
-    async fn search(&self, params: &ProductSearchParams) -> ServiceResult<(u64, Vec<ProductDetail>)> {
-        let res = IRepository::search(self, params).await?;
+    async fn search(
+        &self,
+        params: &ProductSearchParams,
+        filter: &DataFilter,
+    ) -> ServiceResult<(u64, Vec<ProductDetail>)> {
+        let res = IRepository::search(
+            self,
+            &ScopedSearch {
+                params,
+                scope: filter,
+            },
+        )
+        .await?;
         Ok((
             res.total as u64,
             res.items.into_iter().map(ProductDetail::from).collect(),
@@ -161,7 +212,7 @@ impl IProductRepository for ProductRepository {
     async fn update_many(&self, products: &[Product]) -> ServiceResult<()> {
         for product in products {
             self.get_database()
-                .collection::<Product>(&self.get_collection_name())
+                .collection::<Product>(self.get_collection_name())
                 .update_one(
                     doc! {
                         "id": &product.base.id,
@@ -170,7 +221,7 @@ impl IProductRepository for ProductRepository {
                     doc! { "$set": mongodb::bson::to_document(product).unwrap() },
                 )
                 .await
-                .map_err(|e| Error::DatabaseError(e))?;
+                .map_err(Error::DatabaseError)?;
         }
 
         Ok(())
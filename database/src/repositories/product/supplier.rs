@@ -1,9 +1,8 @@
-use crate::errors::Error;
 use async_trait::async_trait;
 use entities::product::Supplier;
 use mongodb::Database;
 use services::errors::Result as ServiceResult;
-use services::product::ISupplierRepository;
+use services::supplier::ISupplierRepository;
 
 use super::super::collection_names::SUPPLIER;
 use super::super::IRepository;
@@ -49,4 +48,4 @@ impl ISupplierRepository for SupplierRepository {
     async fn find_all(&self) -> ServiceResult<Vec<Supplier>> {
         Ok(IRepository::find_all(self).await?)
     }
-} 
\ No newline at end of file
+}
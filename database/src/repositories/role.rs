@@ -106,8 +106,22 @@ impl IRoleRepository for RoleRepository {
                 "deleted_at": 0
             })
             .await
-            .map_err(|e| Error::DatabaseError(e))?;
+            .map_err(Error::DatabaseError)?;
 
         Ok(count > 0)
     }
+
+    async fn find_by_name(&self, name: &str) -> ServiceResult<Option<Role>> {
+        let role = self
+            .database
+            .collection::<Role>(self.coll_name.as_str())
+            .find_one(doc! {
+                "name": name,
+                "deleted_at": 0
+            })
+            .await
+            .map_err(Error::DatabaseError)?;
+
+        Ok(role)
+    }
 }
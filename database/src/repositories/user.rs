@@ -1,7 +1,9 @@
-use entity_core::BaseModel;
-use mongodb::{bson::doc, Database};
+use mongodb::{
+    bson::{doc, Document},
+    Database,
+};
 
-use entities::{Admin, Secret};
+use entities::Admin;
 
 use super::{base::IRepository, collection_names::ADMIN};
 use rbac::{Error as RBACError, RBACUser, RBACUserStore, Result as RBACResult};
@@ -9,6 +11,7 @@ use rbac::{Error as RBACError, RBACUser, RBACUserStore, Result as RBACResult};
 use async_trait::async_trait;
 use futures_util::StreamExt;
 
+use super::base::cursor_to_vec;
 use crate::errors::Error;
 use services::admin::IAdminRepository;
 use services::errors::Result as ServiceResult;
@@ -38,6 +41,56 @@ impl AdminRepository {
             database,
         }
     }
+
+    /// 将旧数据中单个字符串的 `role_name` 迁移到 `role_names`
+    ///
+    /// 同时存在两个字段的文档保留 `role_names`, 迁移后删除 `role_name`。
+    /// 可以重复执行, 没有旧字段的文档不受影响。
+    ///
+    /// # 返回值
+    ///
+    /// 返回迁移的文档数量
+    pub async fn migrate_role_names(&self) -> crate::Result<u64> {
+        let result = self
+            .database
+            .collection::<Admin>(self.coll_name.as_str())
+            .update_many(
+                doc! { "role_name": { "$exists": true } },
+                migrate_role_names_pipeline(),
+            )
+            .await?;
+
+        Ok(result.modified_count)
+    }
+}
+
+/// 迁移 `role_name` 的更新管道
+///
+/// 已有 `role_names` 数组时保留; 否则数组形式的 `role_name` 直接使用, 空字符串或 null 转为空列表,
+/// 其他值作为唯一的角色。最后删除 `role_name`。
+fn migrate_role_names_pipeline() -> Vec<Document> {
+    vec![
+        doc! {
+            "$set": {
+                "role_names": {
+                    "$cond": [
+                        { "$isArray": "$role_names" },
+                        "$role_names",
+                        {
+                            "$switch": {
+                                "branches": [
+                                    { "case": { "$isArray": "$role_name" }, "then": "$role_name" },
+                                    { "case": { "$in": ["$role_name", ["", null]] }, "then": [] },
+                                ],
+                                "default": ["$role_name"],
+                            }
+                        },
+                    ]
+                }
+            }
+        },
+        doc! { "$unset": "role_name" },
+    ]
 }
 
 #[async_trait]
@@ -96,24 +149,21 @@ impl IAdminRepository for AdminRepository {
     }
 
     async fn find_by_account(&self, account: &str) -> ServiceResult<Option<Admin>> {
-        // fake account. for test
-        if account == "qqwweeasf" {
-            return Ok(Some(Admin {
-                base: BaseModel::fake(),
-                secret: Secret::fake(),
-                name: "fake".to_string(),
-                age: 18,
-                avatar: "".to_string(),
-                is_active: true,
-                role_name: "admin".to_string(),
-            }));
-        }
-
         let collection = self.database.collection::<Admin>(self.coll_name.as_str());
         let user = collection
             .find_one(doc! { "account": account, "deleted_at": 0 })
             .await
-            .map_err(|e| Error::DatabaseError(e))?;
+            .map_err(Error::DatabaseError)?;
+
+        Ok(user)
+    }
+
+    async fn find_by_sso(&self, issuer: &str, subject: &str) -> ServiceResult<Option<Admin>> {
+        let collection = self.database.collection::<Admin>(self.coll_name.as_str());
+        let user = collection
+            .find_one(doc! { "sso.issuer": issuer, "sso.subject": subject, "deleted_at": 0 })
+            .await
+            .map_err(Error::DatabaseError)?;
 
         Ok(user)
     }
@@ -122,4 +172,140 @@ impl IAdminRepository for AdminRepository {
         let admins = IRepository::find_all(self).await?;
         Ok(admins)
     }
+
+    async fn find_by_ids(&self, ids: &[String]) -> ServiceResult<Vec<Admin>> {
+        let admins = IRepository::find_by_ids(self, ids).await?;
+        Ok(admins)
+    }
+
+    async fn find_by_role(&self, role_name: &str) -> ServiceResult<Vec<Admin>> {
+        let collection = self.database.collection::<Admin>(self.coll_name.as_str());
+        let cursor = collection
+            .find(doc! {
+                "role_names": role_name,
+                "deleted_at": 0,
+            })
+            .await
+            .map_err(Error::DatabaseError)?;
+
+        Ok(cursor_to_vec(cursor).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::{from_document, to_document, Bson};
+
+    /// 按 MongoDB 的语义计算管道中用到的表达式, 只支持迁移管道使用的操作符
+    fn eval(expr: &Bson, document: &Document) -> Bson {
+        match expr {
+            Bson::String(field) if field.starts_with('$') => {
+                document.get(&field[1..]).cloned().unwrap_or(Bson::Null)
+            }
+            Bson::Array(items) => Bson::Array(items.iter().map(|item| eval(item, document)).collect()),
+            Bson::Document(operator) => {
+                let (name, args) = operator.iter().next().unwrap();
+                match name.as_str() {
+                    "$cond" => {
+                        let args = args.as_array().unwrap();
+                        match eval(&args[0], document) {
+                            Bson::Boolean(true) => eval(&args[1], document),
+                            _ => eval(&args[2], document),
+                        }
+                    }
+                    "$isArray" => Bson::Boolean(matches!(eval(args, document), Bson::Array(_))),
+                    "$in" => {
+                        let args = args.as_array().unwrap();
+                        let value = eval(&args[0], document);
+                        Bson::Boolean(eval(&args[1], document).as_array().unwrap().contains(&value))
+                    }
+                    "$switch" => {
+                        let args = args.as_document().unwrap();
+                        for branch in args.get_array("branches").unwrap() {
+                            let branch = branch.as_document().unwrap();
+                            if eval(branch.get("case").unwrap(), document) == Bson::Boolean(true) {
+                                return eval(branch.get("then").unwrap(), document);
+                            }
+                        }
+                        eval(args.get("default").unwrap(), document)
+                    }
+                    _ => panic!("unsupported operator {}", name),
+                }
+            }
+            _ => expr.clone(),
+        }
+    }
+
+    /// 对文档执行迁移管道
+    fn migrate(mut document: Document) -> Document {
+        for stage in migrate_role_names_pipeline() {
+            if let Ok(fields) = stage.get_document("$set") {
+                let values: Vec<(String, Bson)> = fields
+                    .iter()
+                    .map(|(field, expr)| (field.clone(), eval(expr, &document)))
+                    .collect();
+                document.extend(values);
+            }
+            if let Ok(field) = stage.get_str("$unset") {
+                document.remove(field);
+            }
+        }
+        document
+    }
+
+    #[test]
+    fn test_migrate_role_names_pipeline() {
+        let cases = [
+            (doc! { "role_name": "admin" }, vec!["admin"]),
+            (doc! { "role_name": "" }, vec![]),
+            (doc! { "role_name": null }, vec![]),
+            (
+                doc! { "role_name": ["admin", "auditor"] },
+                vec!["admin", "auditor"],
+            ),
+            (
+                doc! { "role_names": ["admin"], "role_name": "old" },
+                vec!["admin"],
+            ),
+            (doc! { "role_names": [], "role_name": "old" }, vec![]),
+        ];
+
+        for (document, expected) in cases {
+            let migrated = migrate(document.clone());
+            assert!(!migrated.contains_key("role_name"), "{}", document);
+
+            let role_names: Vec<&str> = migrated
+                .get_array("role_names")
+                .unwrap()
+                .iter()
+                .map(|role| role.as_str().unwrap())
+                .collect();
+            assert_eq!(role_names, expected, "{}", document);
+        }
+    }
+
+    #[test]
+    fn test_admin_with_legacy_role_name() {
+        let admin = Admin::new(
+            "1".to_string(),
+            Default::default(),
+            "admin".to_string(),
+            vec!["admin".to_string(), "auditor".to_string()],
+        );
+
+        // 旧版本只 $set 了 role_names, 文档中同时留有 role_name
+        let mut document = to_document(&admin).unwrap();
+        document.insert("role_name", "old");
+
+        let admin: Admin = from_document(document).unwrap();
+        assert_eq!(admin.role_names, vec!["admin".to_string(), "auditor".to_string()]);
+
+        let document = to_document(&admin).unwrap();
+        assert!(!document.contains_key("role_name"));
+        assert_eq!(
+            from_document::<Admin>(document).unwrap().role_names,
+            admin.role_names
+        );
+    }
 }
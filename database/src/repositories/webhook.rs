@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use mongodb::Database;
+
+use super::{collection_names::WEBHOOK_ENDPOINT, IRepository};
+use entities::WebhookEndpoint;
+use services::errors::Result as ServiceResult;
+use services::webhook::IWebhookEndpointRepository;
+
+/// Webhook 端点仓储结构体
+///
+/// # 字段
+///
+/// * `coll_name` - MongoDB集合名称
+/// * `database` - MongoDB数据库实例
+pub struct WebhookEndpointRepository {
+    pub coll_name: String,
+    database: Database,
+}
+
+impl WebhookEndpointRepository {
+    pub fn new(database: Database) -> Self {
+        Self {
+            coll_name: WEBHOOK_ENDPOINT.to_string(),
+            database,
+        }
+    }
+}
+
+impl IRepository<WebhookEndpoint> for WebhookEndpointRepository {
+    fn get_collection_name(&self) -> &str {
+        &self.coll_name
+    }
+
+    fn get_database(&self) -> &Database {
+        &self.database
+    }
+}
+
+#[async_trait]
+impl IWebhookEndpointRepository for WebhookEndpointRepository {
+    async fn create(&self, endpoint: &WebhookEndpoint) -> ServiceResult<()> {
+        IRepository::create(self, endpoint).await?;
+        Ok(())
+    }
+
+    async fn update(&self, endpoint: &WebhookEndpoint) -> ServiceResult<()> {
+        IRepository::update(self, endpoint).await?;
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: &str) -> ServiceResult<Option<WebhookEndpoint>> {
+        Ok(IRepository::find_by_id(self, id).await?)
+    }
+
+    async fn find_all(&self) -> ServiceResult<Vec<WebhookEndpoint>> {
+        Ok(IRepository::find_all(self).await?)
+    }
+}
@@ -0,0 +1,180 @@
+use entity_core::BaseModel;
+use entity_macros::Entity;
+use rand::{rngs::OsRng, RngCore};
+use rbac::RBACUser;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// 密钥ID前缀, 便于在日志和配置中识别
+const KEY_ID_PREFIX: &str = "ak_";
+/// 密钥ID的随机字节数
+const KEY_ID_BYTES: usize = 8;
+/// 密钥的随机字节数
+const SECRET_BYTES: usize = 32;
+/// RBAC 中 API 密钥主体的前缀, 与管理员账号区分
+const SUBJECT_PREFIX: &str = "apikey:";
+
+/// API 密钥, 供集成任务等机器调用接口使用
+///
+/// 客户端使用 `<key_id>.<secret>` 格式的令牌认证, 服务端只保存密钥的哈希
+///
+/// # 字段
+///
+/// * `name` - 名称
+/// * `key_id` - 公开的密钥ID
+/// * `secret_hash` - 密钥的 SHA-256 哈希
+/// * `role_name` - 绑定的角色, 权限与该角色的管理员相同
+/// * `expires_at` - 过期时间(秒级时间戳), 0 表示永不过期
+/// * `last_used_at` - 最近一次使用时间(秒级时间戳)
+/// * `is_active` - 是否启用
+/// * `created_by` - 创建者ID
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Entity)]
+#[serde(default)]
+pub struct ApiKey {
+    #[serde(flatten)]
+    pub base: BaseModel,
+    pub name: String,
+    pub key_id: String,
+    pub secret_hash: String,
+    pub role_name: String,
+    pub expires_at: i64,
+    pub last_used_at: i64,
+    pub is_active: bool,
+    pub created_by: String,
+}
+
+impl RBACUser for ApiKey {
+    fn subject(&self) -> String {
+        self.subject()
+    }
+
+    fn role_names(&self) -> Vec<String> {
+        vec![self.role_name.clone()]
+    }
+}
+
+impl ApiKey {
+    /// 生成新的 API 密钥
+    ///
+    /// # 返回值
+    ///
+    /// 返回密钥实体和客户端使用的令牌, 令牌只在此时返回一次
+    pub fn generate(
+        id: String,
+        name: String,
+        role_name: String,
+        expires_at: i64,
+        created_by: String,
+    ) -> (Self, String) {
+        let key_id = format!("{}{}", KEY_ID_PREFIX, random_hex(KEY_ID_BYTES));
+        let secret = random_hex(SECRET_BYTES);
+        let token = format!("{}.{}", key_id, secret);
+
+        let api_key = Self {
+            base: BaseModel::new(id),
+            name,
+            key_id,
+            secret_hash: hash_secret(&secret),
+            role_name,
+            expires_at,
+            last_used_at: 0,
+            is_active: true,
+            created_by,
+        };
+
+        (api_key, token)
+    }
+
+    /// 拆分客户端令牌, 返回密钥ID和密钥
+    pub fn parse_token(token: &str) -> Option<(&str, &str)> {
+        let (key_id, secret) = token.trim().split_once('.')?;
+        if !key_id.starts_with(KEY_ID_PREFIX) || secret.is_empty() {
+            return None;
+        }
+
+        Some((key_id, secret))
+    }
+
+    /// 密钥是否匹配
+    pub fn verify_secret(&self, secret: &str) -> bool {
+        let hash = hash_secret(secret);
+
+        // 逐字节比较全部内容, 避免泄露匹配长度
+        hash.len() == self.secret_hash.len()
+            && hash
+                .bytes()
+                .zip(self.secret_hash.bytes())
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    }
+
+    /// 在指定时间是否已过期
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at > 0 && self.expires_at <= now
+    }
+
+    /// 在指定时间是否可以使用
+    pub fn is_usable(&self, now: i64) -> bool {
+        self.is_active && self.base.deleted_at == 0 && !self.is_expired(now)
+    }
+
+    /// 在 RBAC 中使用的主体标识
+    pub fn subject(&self) -> String {
+        format!("{}{}", SUBJECT_PREFIX, self.key_id)
+    }
+}
+
+fn random_hex(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+fn hash_secret(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_and_verify() {
+        let (api_key, token) = ApiKey::generate(
+            "1".to_string(),
+            "ci".to_string(),
+            "admin".to_string(),
+            0,
+            "2".to_string(),
+        );
+
+        let (key_id, secret) = ApiKey::parse_token(&token).unwrap();
+        assert_eq!(key_id, api_key.key_id);
+        assert!(api_key.verify_secret(secret));
+        assert!(!api_key.verify_secret("wrong"));
+        assert!(!api_key.secret_hash.contains(secret));
+
+        assert!(ApiKey::parse_token("not-a-key").is_none());
+        assert!(ApiKey::parse_token("ak_1.").is_none());
+    }
+
+    #[test]
+    fn test_is_usable() {
+        let (mut api_key, _) = ApiKey::generate(
+            "1".to_string(),
+            "ci".to_string(),
+            "admin".to_string(),
+            100,
+            "2".to_string(),
+        );
+
+        assert!(api_key.is_usable(99));
+        assert!(!api_key.is_usable(100));
+
+        api_key.expires_at = 0;
+        assert!(api_key.is_usable(i64::MAX));
+
+        api_key.is_active = false;
+        assert!(!api_key.is_usable(0));
+    }
+}
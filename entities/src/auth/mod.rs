@@ -1,3 +1,11 @@
+mod api_key;
+mod password;
 mod secret;
+mod sso_identity;
+mod two_factor;
 
+pub use api_key::ApiKey;
+pub use password::{hash_password, is_legacy_hash, verify_password, PasswordPolicy};
 pub use secret::Secret;
+pub use sso_identity::SsoIdentity;
+pub use two_factor::TwoFactor;
@@ -0,0 +1,186 @@
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use rand::rngs::OsRng;
+use serde::Deserialize;
+
+use crate::errors::{Error, Result};
+
+/// 旧版本使用的 MD5 十六进制哈希长度
+const LEGACY_MD5_LENGTH: usize = 32;
+
+/// 密码策略
+///
+/// 可以在配置文件的 `[password_policy]` 中覆盖默认值
+///
+/// # 字段
+///
+/// * `min_length` - 最小长度
+/// * `max_length` - 最大长度
+/// * `require_lowercase` - 是否必须包含小写字母
+/// * `require_uppercase` - 是否必须包含大写字母
+/// * `require_digit` - 是否必须包含数字
+/// * `require_symbol` - 是否必须包含特殊字符
+/// * `min_char_classes` - 至少包含的字符种类数(小写、大写、数字、特殊字符)
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_lowercase: bool,
+    pub require_uppercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    pub min_char_classes: usize,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: 128,
+            require_lowercase: false,
+            require_uppercase: false,
+            require_digit: false,
+            require_symbol: false,
+            min_char_classes: 2,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// 校验密码是否满足策略
+    ///
+    /// # 参数
+    ///
+    /// * `password` - 待校验的原始密码
+    ///
+    /// # 返回值
+    ///
+    /// 满足策略时返回Ok,否则返回描述第一个不满足条件的错误
+    pub fn check(&self, password: &str) -> Result<()> {
+        let length = password.chars().count();
+        if length == 0 {
+            return Err(Error::LogicError("密码不能为空".to_string()));
+        }
+
+        if length < self.min_length {
+            return Err(Error::LogicError(format!(
+                "密码长度不能少于{}个字符",
+                self.min_length
+            )));
+        }
+
+        if length > self.max_length {
+            return Err(Error::LogicError(format!(
+                "密码长度不能超过{}个字符",
+                self.max_length
+            )));
+        }
+
+        let has_lowercase = password.chars().any(|c| c.is_lowercase());
+        let has_uppercase = password.chars().any(|c| c.is_uppercase());
+        let has_digit = password.chars().any(|c| c.is_ascii_digit());
+        let has_symbol = password.chars().any(|c| !c.is_alphanumeric());
+
+        let rules = [
+            (self.require_lowercase, has_lowercase, "密码必须包含小写字母"),
+            (self.require_uppercase, has_uppercase, "密码必须包含大写字母"),
+            (self.require_digit, has_digit, "密码必须包含数字"),
+            (self.require_symbol, has_symbol, "密码必须包含特殊字符"),
+        ];
+        for (required, satisfied, message) in rules {
+            if required && !satisfied {
+                return Err(Error::LogicError(message.to_string()));
+            }
+        }
+
+        let classes = [has_lowercase, has_uppercase, has_digit, has_symbol]
+            .iter()
+            .filter(|c| **c)
+            .count();
+        if classes < self.min_char_classes {
+            return Err(Error::LogicError(format!(
+                "密码至少需要包含{}种字符(小写字母、大写字母、数字、特殊字符)",
+                self.min_char_classes
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// 使用 Argon2id 和随机盐计算密码哈希
+///
+/// # 返回值
+///
+/// 返回 PHC 格式的哈希字符串,包含算法、参数和盐
+pub fn hash_password(password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| Error::LogicError(format!("密码哈希失败: {}", e)))
+}
+
+/// 校验密码是否与哈希匹配
+///
+/// 同时兼容 PHC 格式的哈希和旧版本无盐的 MD5 哈希
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    if is_legacy_hash(hash) {
+        return format!("{:x}", md5::compute(password.as_bytes())) == hash;
+    }
+
+    match PasswordHash::new(hash) {
+        Ok(parsed) => Argon2::default()
+            .verify_password(password.as_bytes(), &parsed)
+            .is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// 判断是否为旧版本的 MD5 哈希
+pub fn is_legacy_hash(hash: &str) -> bool {
+    hash.len() == LEGACY_MD5_LENGTH && hash.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_and_verify() {
+        let hash = hash_password("Passw0rd!").unwrap();
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(verify_password("Passw0rd!", &hash));
+        assert!(!verify_password("passw0rd!", &hash));
+
+        // 相同的密码每次使用不同的盐
+        assert_ne!(hash, hash_password("Passw0rd!").unwrap());
+    }
+
+    #[test]
+    fn test_verify_legacy_hash() {
+        let legacy = format!("{:x}", md5::compute("123456".as_bytes()));
+        assert!(is_legacy_hash(&legacy));
+        assert!(verify_password("123456", &legacy));
+        assert!(!verify_password("1234567", &legacy));
+    }
+
+    #[test]
+    fn test_policy() {
+        let policy = PasswordPolicy::default();
+        assert!(policy.check("").is_err());
+        assert!(policy.check("abc123").is_err());
+        assert!(policy.check("abcdefgh").is_err());
+        assert!(policy.check("abcdefg1").is_ok());
+
+        let strict = PasswordPolicy {
+            require_uppercase: true,
+            require_symbol: true,
+            ..Default::default()
+        };
+        assert!(strict.check("abcdefg1").is_err());
+        assert!(strict.check("Abcdefg1").is_err());
+        assert!(strict.check("Abcdefg1!").is_ok());
+    }
+}
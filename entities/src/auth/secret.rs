@@ -1,13 +1,15 @@
-use crate::errors::Error;
 use crate::errors::Result;
+use rand::{rngs::OsRng, RngCore};
 use serde::{Deserialize, Serialize};
 
+use super::password::{hash_password, is_legacy_hash, verify_password, PasswordPolicy};
+
 /// 表示用户认证信息的结构体
 ///
 /// # 字段
 ///
 /// * `account` - 用户账号
-/// * `password` - 用户密码(MD5加密后的哈希值)
+/// * `password` - 用户密码的 Argon2id 哈希(PHC 格式), 旧数据可能是无盐的 MD5 哈希
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Secret {
     pub account: String,
@@ -15,36 +17,37 @@ pub struct Secret {
 }
 
 impl Secret {
-    /// 创建一个用于测试的假Secret实例
+    /// 创建一个新的Secret实例
+    ///
+    /// # 参数
+    ///
+    /// * `account` - 用户账号
+    /// * `password` - 用户原始密码
+    /// * `policy` - 密码策略
     ///
     /// # 返回值
     ///
-    /// 返回一个包含假数据的Secret实例
-    pub fn fake() -> Self {
-        let password = format!("{:x}", md5::compute("fake".as_bytes()));
+    /// 返回Result,成功时包含新的Secret实例,密码不满足策略时返回错误
+    pub fn new(account: String, password: String, policy: &PasswordPolicy) -> Result<Self> {
+        policy.check(&password)?;
+
+        let password = hash_password(&password)?;
 
-        Self {
-            account: "fake".to_string(),
-            password,
-        }
+        Ok(Self { account, password })
     }
 
-    /// 创建一个新的Secret实例
+    /// 使用随机密码创建Secret实例
+    ///
+    /// 用于单点登录自动创建的账号, 随机密码不会返回, 无法用于密码登录
     ///
     /// # 参数
     ///
     /// * `account` - 用户账号
-    /// * `password` - 用户原始密码
-    ///
-    /// # 返回值
-    ///
-    /// 返回Result,成功时包含新的Secret实例,失败时返回错误
-    pub fn new(account: String, password: String) -> std::result::Result<Self, Error> {
-        if password.is_empty() {
-            return Err(Error::LogicError("密码不能为空".to_string()));
-        }
+    pub fn random(account: String) -> Result<Self> {
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
 
-        let password = format!("{:x}", md5::compute(password.as_bytes()));
+        let password = hash_password(&hex::encode(bytes))?;
 
         Ok(Self { account, password })
     }
@@ -54,12 +57,11 @@ impl Secret {
     /// # 参数
     ///
     /// * `password` - 新的密码
-    pub fn change_password(&mut self, password: String) -> Result<()> {
-        if password.is_empty() {
-            return Err(Error::LogicError("密码不能为空".to_string()));
-        }
+    /// * `policy` - 密码策略
+    pub fn change_password(&mut self, password: String, policy: &PasswordPolicy) -> Result<()> {
+        policy.check(&password)?;
 
-        self.password = format!("{:x}", md5::compute(password.as_bytes()));
+        self.password = hash_password(&password)?;
 
         Ok(())
     }
@@ -74,6 +76,52 @@ impl Secret {
     ///
     /// 如果密码匹配返回true,否则返回false
     pub fn is_match(&self, password: &str) -> bool {
-        format!("{:x}", md5::compute(password.as_bytes())) == self.password
+        verify_password(password, &self.password)
+    }
+
+    /// 是否仍在使用旧版本的 MD5 哈希, 需要在登录成功后重新计算
+    pub fn needs_rehash(&self) -> bool {
+        is_legacy_hash(&self.password)
+    }
+
+    /// 使用当前算法重新计算密码哈希
+    ///
+    /// 仅用于登录成功后升级旧哈希, 不校验密码策略
+    ///
+    /// # 参数
+    ///
+    /// * `password` - 已验证通过的原始密码
+    pub fn rehash(&mut self, password: &str) -> Result<()> {
+        self.password = hash_password(password)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rehash_legacy_secret() {
+        let mut secret = Secret {
+            account: "admin".to_string(),
+            password: format!("{:x}", md5::compute("123456".as_bytes())),
+        };
+        assert!(secret.needs_rehash());
+        assert!(secret.is_match("123456"));
+
+        secret.rehash("123456").unwrap();
+        assert!(!secret.needs_rehash());
+        assert!(secret.is_match("123456"));
+    }
+
+    #[test]
+    fn test_new_secret_checks_policy() {
+        let policy = PasswordPolicy::default();
+        assert!(Secret::new("admin".to_string(), "123456".to_string(), &policy).is_err());
+
+        let secret = Secret::new("admin".to_string(), "Passw0rd".to_string(), &policy).unwrap();
+        assert!(secret.is_match("Passw0rd"));
+        assert!(!secret.needs_rehash());
     }
 }
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// 单点登录身份, 关联身份提供方中的用户
+///
+/// # 字段
+///
+/// * `issuer` - 身份提供方的签发方地址
+/// * `subject` - 用户在身份提供方中的唯一标识(`sub` 声明)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SsoIdentity {
+    pub issuer: String,
+    pub subject: String,
+}
+
+impl SsoIdentity {
+    pub fn new(issuer: String, subject: String) -> Self {
+        Self { issuer, subject }
+    }
+}
@@ -0,0 +1,211 @@
+//! Broadcast entity module
+//!
+//! A broadcast sends the same notification to a group of admins. It is
+//! fanned out into one `Message` per recipient, and the broadcast record
+//! keeps track of how many of those messages were delivered.
+
+use std::fmt::Display;
+
+use entity_core::BaseModel;
+use entity_macros::Entity;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{MessageCategory, MessageChannel, MessageStatus};
+
+/// Broadcast recipients selector
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BroadcastTarget {
+    /// All admins holding the given role
+    Role { role_name: String },
+    /// An explicit list of admin ids
+    Admins { ids: Vec<String> },
+    /// Every active admin
+    All,
+}
+
+/// Broadcast status enumeration
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub enum BroadcastStatus {
+    /// Messages are being delivered
+    Sending,
+    /// Every message has been attempted at least once
+    Completed,
+    /// Fan-out stopped before every message was created, the messages
+    /// already created are still delivered
+    Failed,
+}
+
+impl Display for BroadcastStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Broadcast entity structure
+#[derive(Debug, Serialize, Deserialize, Clone, Entity)]
+pub struct Broadcast {
+    /// Base model fields including ID and timestamps
+    #[serde(flatten)]
+    pub base: BaseModel,
+
+    /// Channel every fanned out message is delivered through
+    pub channel: MessageChannel,
+
+    /// Recipients selector
+    pub target: BroadcastTarget,
+
+    /// Category of the fanned out messages
+    #[serde(default)]
+    pub category: MessageCategory,
+
+    /// Message subject
+    pub subject: String,
+
+    /// Message content
+    pub content: String,
+
+    /// Current broadcast status
+    pub status: BroadcastStatus,
+
+    /// Number of messages fanned out, reduced to the number actually
+    /// created when the fan-out fails
+    pub total: u64,
+
+    /// Number of recipients skipped because of their notification preferences
+    #[serde(default)]
+    pub skipped: u64,
+
+    /// Number of messages delivered successfully
+    pub sent: u64,
+
+    /// Number of messages whose latest attempt failed
+    pub failed: u64,
+
+    /// Id of the admin who created the broadcast
+    pub created_by: String,
+}
+
+impl Broadcast {
+    /// Creates a new broadcast instance
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Unique identifier for the broadcast
+    /// * `channel` - Channel to deliver the messages through
+    /// * `target` - Recipients selector
+    /// * `category` - Category of the fanned out messages
+    /// * `subject` - Message subject
+    /// * `content` - Message content
+    /// * `total` - Number of messages fanned out
+    /// * `skipped` - Number of recipients skipped by their preferences
+    /// * `created_by` - Id of the admin creating the broadcast
+    ///
+    /// # Returns
+    ///
+    /// A new Broadcast instance, already completed when there are no recipients
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: String,
+        channel: MessageChannel,
+        target: BroadcastTarget,
+        category: MessageCategory,
+        subject: String,
+        content: String,
+        total: u64,
+        skipped: u64,
+        created_by: String,
+    ) -> Self {
+        Self {
+            base: BaseModel::new(id),
+            channel,
+            target,
+            category,
+            subject,
+            content,
+            status: if total == 0 {
+                BroadcastStatus::Completed
+            } else {
+                BroadcastStatus::Sending
+            },
+            total,
+            skipped,
+            sent: 0,
+            failed: 0,
+            created_by,
+        }
+    }
+
+    /// Checks whether every message has been attempted at least once
+    pub fn is_finished(&self) -> bool {
+        self.sent + self.failed >= self.total
+    }
+
+    /// Delivery progress in percent
+    pub fn progress(&self) -> f64 {
+        if self.total == 0 {
+            return 100.0;
+        }
+
+        ((self.sent + self.failed) as f64 / self.total as f64 * 100.0).min(100.0)
+    }
+
+    /// Computes how the sent and failed counters change when a message
+    /// of this broadcast moves from one status to another
+    ///
+    /// # Arguments
+    ///
+    /// * `previous` - Status before the delivery attempt
+    /// * `current` - Status after the delivery attempt
+    ///
+    /// # Returns
+    ///
+    /// A `(sent, failed)` tuple of counter deltas
+    pub fn delivery_delta(previous: &MessageStatus, current: &MessageStatus) -> (i64, i64) {
+        match (previous, current) {
+            (MessageStatus::Pending, MessageStatus::Sent) => (1, 0),
+            (MessageStatus::Pending, MessageStatus::Failed) => (0, 1),
+            (MessageStatus::Failed, MessageStatus::Sent) => (1, -1),
+            _ => (0, 0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delivery_delta() {
+        use MessageStatus::*;
+
+        assert_eq!(Broadcast::delivery_delta(&Pending, &Sent), (1, 0));
+        assert_eq!(Broadcast::delivery_delta(&Pending, &Failed), (0, 1));
+        assert_eq!(Broadcast::delivery_delta(&Failed, &Sent), (1, -1));
+        assert_eq!(Broadcast::delivery_delta(&Failed, &Failed), (0, 0));
+    }
+
+    #[test]
+    fn test_progress() {
+        let mut broadcast = Broadcast::new(
+            "1".to_string(),
+            MessageChannel::InternalMessage,
+            BroadcastTarget::All,
+            MessageCategory::Announcement,
+            "subject".to_string(),
+            "content".to_string(),
+            4,
+            1,
+            "admin".to_string(),
+        );
+
+        assert_eq!(broadcast.status, BroadcastStatus::Sending);
+        assert!(!broadcast.is_finished());
+
+        broadcast.sent = 3;
+        broadcast.failed = 1;
+        assert!(broadcast.is_finished());
+        assert_eq!(broadcast.progress(), 100.0);
+    }
+}
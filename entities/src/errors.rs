@@ -5,6 +5,7 @@ pub enum Error {
 }
 
 impl Error {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Self {
         Error::LogicError(s.to_string())
     }
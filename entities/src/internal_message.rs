@@ -2,6 +2,8 @@ use entity_core::BaseModel;
 use entity_macros::Entity;
 use serde::{Deserialize, Serialize};
 
+use crate::MessageCategory;
+
 /// 内部消息状态枚举
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum InternalMessageStatus {
@@ -11,6 +13,34 @@ pub enum InternalMessageStatus {
     Read,
 }
 
+impl std::fmt::Display for InternalMessageStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// 内部消息优先级枚举
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum MessagePriority {
+    /// 低
+    Low,
+    /// 普通
+    #[default]
+    Normal,
+    /// 高
+    High,
+}
+
+impl MessagePriority {
+    /// 根据消息分类确定默认优先级, 安全类消息为高优先级
+    pub fn for_category(category: MessageCategory) -> Self {
+        match category {
+            MessageCategory::Security => Self::High,
+            _ => Self::Normal,
+        }
+    }
+}
+
 /// 内部消息实体结构体
 #[derive(Debug, Serialize, Deserialize, Entity)]
 pub struct InternalMessage {
@@ -25,6 +55,15 @@ pub struct InternalMessage {
     pub content: String,
     /// 消息读取状态
     pub status: InternalMessageStatus,
+    /// 消息分类
+    #[serde(default)]
+    pub category: MessageCategory,
+    /// 消息优先级
+    #[serde(default)]
+    pub priority: MessagePriority,
+    /// 归档时间, 0 表示未归档
+    #[serde(default)]
+    pub archived_at: u64,
 }
 
 impl InternalMessage {
@@ -36,17 +75,27 @@ impl InternalMessage {
     /// * `recipient` - 接收者ID
     /// * `subject` - 消息主题
     /// * `content` - 消息内容
+    /// * `category` - 消息分类, 同时决定消息的优先级
     ///
     /// # 返回值
     ///
     /// 返回一个新的InternalMessage实例，初始状态为未读
-    pub fn new(id: String, recipient: String, subject: String, content: String) -> Self {
+    pub fn new(
+        id: String,
+        recipient: String,
+        subject: String,
+        content: String,
+        category: MessageCategory,
+    ) -> Self {
         Self {
             base: BaseModel::new(id),
             recipient,
             subject,
             content,
             status: InternalMessageStatus::Unread,
+            category,
+            priority: MessagePriority::for_category(category),
+            archived_at: 0,
         }
     }
 }
@@ -1,18 +1,24 @@
 mod auth;
+mod broadcast;
 pub mod errors;
 mod internal_message;
 mod message;
+mod notification_preference;
 mod operation_log;
 pub mod product;
 mod role;
-mod time;
+pub mod time;
 mod user;
+mod webhook;
 
 pub use auth::*;
+pub use broadcast::*;
 pub use errors::*;
 pub use internal_message::*;
 pub use message::*;
+pub use notification_preference::*;
 pub use operation_log::*;
 pub use product::*;
 pub use role::*;
 pub use user::*;
+pub use webhook::*;
@@ -10,10 +10,14 @@ use entity_core::BaseModel;
 use entity_macros::Entity;
 use serde::{Deserialize, Serialize};
 
-const MAX_RETRY_TIMES: u8 = 10;
+/// Maximum number of delivery retries for a single message
+pub const MAX_RETRY_TIMES: u8 = 10;
+
+/// Maximum number of characters of a delivery response kept on a message
+pub const MAX_RESPONSE_BODY_LENGTH: usize = 1024;
 
 /// Message delivery channel enumeration
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum MessageChannel {
     /// Email
     Email,
@@ -23,6 +27,8 @@ pub enum MessageChannel {
     WebSocket,
     /// Internal message
     InternalMessage,
+    /// HTTP webhook, the recipient is a webhook endpoint id
+    Webhook,
 }
 
 impl Display for MessageChannel {
@@ -31,6 +37,50 @@ impl Display for MessageChannel {
     }
 }
 
+/// Message category enumeration
+///
+/// Admins choose the channels they want to be notified through per category.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MessageCategory {
+    /// System notices such as maintenance windows
+    System,
+    /// Security alerts, never deferred by quiet hours
+    Security,
+    /// Announcements broadcast to a group of admins
+    Announcement,
+    /// Everything else
+    #[default]
+    General,
+}
+
+impl Display for MessageCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl MessageCategory {
+    /// Converts a string to MessageCategory enumeration
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to convert
+    ///
+    /// # Returns
+    ///
+    /// Result<MessageCategory>, returns Error::LogicError if the string is not a known category
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s.to_uppercase().as_str() {
+            "SYSTEM" => Ok(Self::System),
+            "SECURITY" => Ok(Self::Security),
+            "ANNOUNCEMENT" => Ok(Self::Announcement),
+            "GENERAL" => Ok(Self::General),
+            _ => Err(Error::LogicError(format!("Invalid message category: {}", s))),
+        }
+    }
+}
+
 /// Message status enumeration
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum MessageStatus {
@@ -53,7 +103,7 @@ impl Display for MessageStatus {
 }
 
 /// Message entity structure
-#[derive(Debug, Serialize, Deserialize, Entity)]
+#[derive(Debug, Clone, Serialize, Deserialize, Entity)]
 pub struct Message {
     /// Base model fields including ID and timestamps
     #[serde(flatten)]
@@ -65,6 +115,10 @@ pub struct Message {
     /// Message recipient (email/phone etc)
     pub recipient: String,
 
+    /// Message category, used to match the recipient's notification preferences
+    #[serde(default)]
+    pub category: MessageCategory,
+
     /// Message subject
     pub subject: String,
 
@@ -77,8 +131,37 @@ pub struct Message {
     /// Error message if sending failed
     pub error: Option<String>,
 
+    /// Status code returned by the receiver of the latest attempt
+    #[serde(default)]
+    pub response_status: Option<u16>,
+
+    /// Truncated response body returned by the receiver of the latest attempt
+    #[serde(default)]
+    pub response_body: Option<String>,
+
     /// Number of retry attempts
     pub retry_times: u8,
+
+    /// Worker currently holding the delivery lease
+    #[serde(default)]
+    pub locked_by: Option<String>,
+
+    /// Lease expiry timestamp in seconds, 0 when the message is not leased
+    #[serde(default)]
+    pub lease_until: u64,
+
+    /// Broadcast this message was fanned out from
+    #[serde(default)]
+    pub broadcast_id: Option<String>,
+
+    /// Timestamp in seconds before which the message must not be delivered,
+    /// 0 when the message can be delivered immediately
+    #[serde(default)]
+    pub deliver_after: u64,
+
+    /// Id of the HTTP request that created the message, for matching it with log lines
+    #[serde(default)]
+    pub request_id: Option<String>,
 }
 
 impl Message {
@@ -106,20 +189,79 @@ impl Message {
             base: BaseModel::new(id),
             channel,
             recipient,
+            category: MessageCategory::default(),
             subject,
             content,
             status: MessageStatus::Pending,
             error: None,
+            response_status: None,
+            response_body: None,
             retry_times: 0,
+            locked_by: None,
+            lease_until: 0,
+            broadcast_id: None,
+            deliver_after: 0,
+            request_id: None,
         }
     }
 
+    /// Checks whether the message is deferred at the given time
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - Current timestamp in seconds
+    pub fn is_deferred(&self, now: u64) -> bool {
+        self.deliver_after > now
+    }
+
+    /// Checks whether the message is leased by a worker at the given time
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - Current timestamp in seconds
+    pub fn is_leased(&self, now: u64) -> bool {
+        self.locked_by.is_some() && self.lease_until > now
+    }
+
+    /// Leases the message to a worker until the given timestamp
+    ///
+    /// # Arguments
+    ///
+    /// * `worker` - Identifier of the worker delivering the message
+    /// * `until` - Lease expiry timestamp in seconds
+    pub fn lease(&mut self, worker: &str, until: u64) {
+        self.locked_by = Some(worker.to_string());
+        self.lease_until = until;
+    }
+
+    /// Releases the delivery lease so the message can be claimed again
+    pub fn release(&mut self) {
+        self.locked_by = None;
+        self.lease_until = 0;
+    }
+
+    /// Records the response of a delivery attempt
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - Status code returned by the receiver
+    /// * `body` - Response body, truncated to `MAX_RESPONSE_BODY_LENGTH` characters
+    pub fn record_response(&mut self, status: u16, body: &str) {
+        self.response_status = Some(status);
+        self.response_body = Some(body.chars().take(MAX_RESPONSE_BODY_LENGTH).collect());
+    }
+
+    /// Checks whether the message may still be retried
+    pub fn can_retry(&self) -> bool {
+        self.retry_times < MAX_RETRY_TIMES
+    }
+
     pub fn add_retry_times(&mut self) -> crate::errors::Result<()> {
         if self.retry_times >= MAX_RETRY_TIMES {
             return Err(Error::LogicError("重试次数超过最大限制".to_string()));
         }
 
-        self.retry_times = self.retry_times + 1;
+        self.retry_times += 1;
         Ok(())
     }
 }
@@ -135,13 +277,55 @@ impl MessageChannel {
     ///
     /// Result<MessageChannel>, if conversion is successful, returns the corresponding enumeration value;
     /// if unsuccessful, returns Error::LogicError
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Result<Self> {
         match s.to_uppercase().as_str() {
             "EMAIL" => Ok(Self::Email),
             "SMS" => Ok(Self::SMS),
             "WEBSOCKET" => Ok(Self::WebSocket),
-            "INTERNAL" => Ok(Self::InternalMessage),
+            "INTERNAL" | "INTERNALMESSAGE" => Ok(Self::InternalMessage),
+            "WEBHOOK" => Ok(Self::Webhook),
             _ => Err(Error::LogicError(format!("Invalid message channel: {}", s))),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_message() -> Message {
+        Message::new(
+            "1".to_string(),
+            MessageChannel::Email,
+            "user@example.com".to_string(),
+            "subject".to_string(),
+            "content".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_lease_and_release() {
+        let mut message = new_message();
+        assert!(!message.is_leased(100));
+
+        message.lease("worker-1", 160);
+        assert!(message.is_leased(100));
+        assert!(!message.is_leased(160));
+
+        message.release();
+        assert!(!message.is_leased(100));
+        assert_eq!(message.lease_until, 0);
+    }
+
+    #[test]
+    fn test_retry_limit() {
+        let mut message = new_message();
+        for _ in 0..MAX_RETRY_TIMES {
+            message.add_retry_times().unwrap();
+        }
+
+        assert!(!message.can_retry());
+        assert!(message.add_retry_times().is_err());
+    }
+}
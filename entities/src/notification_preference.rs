@@ -0,0 +1,283 @@
+//! Notification preference module
+//!
+//! Every admin owns a set of notification preferences: which channels they
+//! want to be notified through for each message category, the contact
+//! addresses used by the email and SMS channels, and optional quiet hours
+//! during which intrusive channels are deferred.
+
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{Error, Result};
+use crate::{MessageCategory, MessageChannel};
+
+/// Channels used for a category the admin has not configured
+pub const DEFAULT_CHANNELS: [MessageChannel; 2] =
+    [MessageChannel::InternalMessage, MessageChannel::WebSocket];
+
+/// Time format used by quiet hours
+const QUIET_HOURS_FORMAT: &str = "%H:%M";
+
+/// Daily window during which intrusive channels are deferred
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct QuietHours {
+    /// Start of the window, `HH:MM` in the admin's timezone
+    pub start: String,
+    /// End of the window, `HH:MM` in the admin's timezone
+    pub end: String,
+    /// IANA timezone name, e.g. `Asia/Shanghai`
+    pub timezone: String,
+}
+
+impl QuietHours {
+    /// Creates validated quiet hours
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - Start of the window, `HH:MM`
+    /// * `end` - End of the window, `HH:MM`, may be earlier than `start` to span midnight
+    /// * `timezone` - IANA timezone name
+    pub fn new(start: String, end: String, timezone: String) -> Result<Self> {
+        let quiet_hours = Self { start, end, timezone };
+        quiet_hours.validate()?;
+        Ok(quiet_hours)
+    }
+
+    /// Checks that the times and the timezone can be parsed
+    pub fn validate(&self) -> Result<()> {
+        self.parse().map(|_| ())
+    }
+
+    fn parse(&self) -> Result<(NaiveTime, NaiveTime, Tz)> {
+        let parse_time = |s: &str| {
+            NaiveTime::parse_from_str(s, QUIET_HOURS_FORMAT)
+                .map_err(|_| Error::LogicError(format!("Invalid quiet hours time: {}", s)))
+        };
+
+        let tz = self
+            .timezone
+            .parse::<Tz>()
+            .map_err(|_| Error::LogicError(format!("Invalid timezone: {}", self.timezone)))?;
+
+        Ok((parse_time(&self.start)?, parse_time(&self.end)?, tz))
+    }
+
+    /// Returns the timestamp at which the quiet window ends, if `now` falls inside it
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - Current timestamp in seconds
+    ///
+    /// # Returns
+    ///
+    /// `Some(timestamp)` of the end of the current window, `None` outside quiet hours
+    pub fn deferred_until(&self, now: u64) -> Option<u64> {
+        let (start, end, tz) = self.parse().ok()?;
+        if start == end {
+            return None;
+        }
+
+        let local = Utc.timestamp_opt(now as i64, 0).single()?.with_timezone(&tz);
+        let time = local.time();
+        let quiet = if start < end {
+            time >= start && time < end
+        } else {
+            time >= start || time < end
+        };
+
+        if !quiet {
+            return None;
+        }
+
+        // 结束时间早于当前时间说明窗口跨越午夜, 在次日结束
+        let mut date = local.date_naive();
+        if time >= end {
+            date += Duration::days(1);
+        }
+
+        let until = tz
+            .from_local_datetime(&date.and_time(end))
+            .earliest()
+            .map(|dt| dt.timestamp() as u64)
+            // 结束时间落在夏令时跳过的区间内时顺延一小时
+            .unwrap_or(now + 3600);
+
+        Some(until)
+    }
+}
+
+/// Notification preferences of an admin
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct NotificationPreferences {
+    /// Enabled channels per message category, `DEFAULT_CHANNELS` when absent
+    pub channels: HashMap<MessageCategory, Vec<MessageChannel>>,
+    /// Address used by the email channel
+    pub email: Option<String>,
+    /// Number used by the SMS channel
+    pub phone: Option<String>,
+    /// Optional quiet hours
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl NotificationPreferences {
+    /// Checks that the quiet hours, if any, are valid
+    pub fn validate(&self) -> Result<()> {
+        match &self.quiet_hours {
+            Some(quiet_hours) => quiet_hours.validate(),
+            None => Ok(()),
+        }
+    }
+
+    /// Channels enabled for the given category
+    pub fn enabled_channels(&self, category: MessageCategory) -> Vec<MessageChannel> {
+        self.channels
+            .get(&category)
+            .cloned()
+            .unwrap_or_else(|| DEFAULT_CHANNELS.to_vec())
+    }
+
+    /// Checks whether the channel is enabled for the given category
+    pub fn allows(&self, category: MessageCategory, channel: &MessageChannel) -> bool {
+        self.enabled_channels(category).contains(channel)
+    }
+
+    /// Resolves the address a message on the given channel should be sent to
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - Delivery channel
+    /// * `admin_id` - Id of the admin, used by the internal and WebSocket channels
+    ///
+    /// # Returns
+    ///
+    /// `None` when the admin has not configured a contact for the channel
+    pub fn contact_for(&self, channel: &MessageChannel, admin_id: &str) -> Option<String> {
+        match channel {
+            MessageChannel::Email => self.email.clone(),
+            MessageChannel::SMS => self.phone.clone(),
+            MessageChannel::WebSocket | MessageChannel::InternalMessage => Some(admin_id.to_string()),
+            // webhook 的接收者是注册的端点, 不对应单个管理员
+            MessageChannel::Webhook => None,
+        }
+    }
+
+    /// Returns the timestamp a message must wait for before delivery
+    ///
+    /// Internal messages land silently in the inbox and security alerts are
+    /// urgent, so neither is deferred by quiet hours.
+    ///
+    /// # Returns
+    ///
+    /// 0 when the message can be delivered immediately
+    pub fn deliver_after(&self, category: MessageCategory, channel: &MessageChannel, now: u64) -> u64 {
+        if category == MessageCategory::Security || *channel == MessageChannel::InternalMessage {
+            return 0;
+        }
+
+        self.quiet_hours
+            .as_ref()
+            .and_then(|quiet_hours| quiet_hours.deferred_until(now))
+            .unwrap_or(0)
+    }
+
+    /// Resolves where and when a message for this admin should be delivered
+    ///
+    /// # Arguments
+    ///
+    /// * `category` - Message category
+    /// * `channel` - Delivery channel
+    /// * `admin_id` - Id of the admin
+    /// * `now` - Current timestamp in seconds
+    ///
+    /// # Returns
+    ///
+    /// `Some((recipient, deliver_after))`, or `None` when the admin does not
+    /// want to be notified through the channel or has no contact for it
+    pub fn route(
+        &self,
+        category: MessageCategory,
+        channel: &MessageChannel,
+        admin_id: &str,
+        now: u64,
+    ) -> Option<(String, u64)> {
+        if !self.allows(category, channel) {
+            return None;
+        }
+
+        let recipient = self.contact_for(channel, admin_id)?;
+        Some((recipient, self.deliver_after(category, channel, now)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-01-01 00:00:00 UTC, 08:00 in Asia/Shanghai
+    const MIDNIGHT_UTC: u64 = 1_704_067_200;
+
+    fn quiet_hours(start: &str, end: &str) -> QuietHours {
+        QuietHours::new(start.to_string(), end.to_string(), "Asia/Shanghai".to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_invalid_quiet_hours() {
+        assert!(QuietHours::new("25:00".to_string(), "08:00".to_string(), "UTC".to_string()).is_err());
+        assert!(QuietHours::new("22:00".to_string(), "08:00".to_string(), "Mars/Base".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_quiet_hours_across_midnight() {
+        let quiet_hours = quiet_hours("22:00", "09:00");
+
+        // 08:00 本地时间处于窗口内, 在 09:00 结束
+        assert_eq!(
+            quiet_hours.deferred_until(MIDNIGHT_UTC),
+            Some(MIDNIGHT_UTC + 3600)
+        );
+        // 12:00 本地时间不在窗口内
+        assert_eq!(quiet_hours.deferred_until(MIDNIGHT_UTC + 4 * 3600), None);
+        // 23:00 本地时间处于窗口内, 在次日 09:00 结束
+        assert_eq!(
+            quiet_hours.deferred_until(MIDNIGHT_UTC + 15 * 3600),
+            Some(MIDNIGHT_UTC + 25 * 3600)
+        );
+    }
+
+    #[test]
+    fn test_preferences() {
+        let mut preferences = NotificationPreferences {
+            quiet_hours: Some(quiet_hours("07:00", "09:00")),
+            ..Default::default()
+        };
+        preferences
+            .channels
+            .insert(MessageCategory::System, vec![MessageChannel::Email]);
+
+        assert!(preferences.allows(MessageCategory::System, &MessageChannel::Email));
+        assert!(!preferences.allows(MessageCategory::System, &MessageChannel::InternalMessage));
+        assert!(preferences.allows(MessageCategory::General, &MessageChannel::InternalMessage));
+        assert_eq!(
+            preferences.route(MessageCategory::System, &MessageChannel::Email, "1", 0),
+            None
+        );
+
+        let now = MIDNIGHT_UTC;
+        assert_eq!(
+            preferences.deliver_after(MessageCategory::System, &MessageChannel::Email, now),
+            now + 3600
+        );
+        assert_eq!(
+            preferences.deliver_after(MessageCategory::Security, &MessageChannel::Email, now),
+            0
+        );
+        assert_eq!(
+            preferences.deliver_after(MessageCategory::System, &MessageChannel::InternalMessage, now),
+            0
+        );
+    }
+}
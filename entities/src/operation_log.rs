@@ -15,9 +15,12 @@ pub struct OperationLog {
     pub request_method: String,       // 请求方法
     pub request_body: Option<String>, // 请求体
     pub ip_address: String,           // IP地址
+    #[serde(default)]
+    pub request_id: String, // 请求ID, 与日志中的 request_id 对应
 }
 
 impl OperationLog {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         id: String,
         operator: &str,
@@ -41,6 +44,7 @@ impl OperationLog {
             request_method: request_method.to_string(),
             request_body: request_body.map(|s| s.to_string()),
             ip_address: ip_address.to_string(),
+            request_id: String::new(),
         }
     }
 }
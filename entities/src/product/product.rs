@@ -5,8 +5,9 @@ use crate::{errors::Result, Error};
 use entity_core::BaseModel;
 use entity_macros::Entity;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize, Entity)]
+#[derive(Debug, Clone, Serialize, Deserialize, Entity)]
 pub struct Product {
     #[serde(flatten)]
     pub base: BaseModel,
@@ -22,9 +23,12 @@ pub struct Product {
     pub is_featured: bool,
     #[serde(default)]
     pub skus: Vec<SKU>,
+    /// 创建商品的管理员 ID
+    #[serde(default)]
+    pub created_by: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
 pub enum ProductStatus {
     Draft,    // 草稿
     Active,   // 上架
@@ -122,6 +126,7 @@ pub struct ProductBuilder {
     sort_order: Option<i32>,
     is_featured: Option<bool>,
     skus: Vec<SKU>,
+    created_by: Option<String>,
 }
 
 impl ProductBuilder {
@@ -179,6 +184,7 @@ impl ProductBuilder {
         self
     }
 
+    #[allow(clippy::wrong_self_convention)]
     pub fn is_featured(mut self, is_featured: bool) -> Self {
         self.is_featured = Some(is_featured);
         self
@@ -189,6 +195,11 @@ impl ProductBuilder {
         self
     }
 
+    pub fn created_by(mut self, created_by: String) -> Self {
+        self.created_by = Some(created_by);
+        self
+    }
+
     pub fn build(self) -> Result<Product> {
         let id = self.id.ok_or(Error::from_str("id is required"))?;
         let name = self.name.ok_or(Error::from_str("name is required"))?;
@@ -222,6 +233,7 @@ impl ProductBuilder {
             sort_order: self.sort_order.unwrap_or(0),
             is_featured: self.is_featured.unwrap_or(false),
             skus: self.skus,
+            created_by: self.created_by.unwrap_or_default(),
         })
     }
 }
@@ -2,8 +2,9 @@ use crate::errors::{Error, Result};
 use entity_core::BaseModel;
 use entity_macros::Entity;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize, Entity)]
+#[derive(Debug, Clone, Serialize, Deserialize, Entity)]
 pub struct SKU {
     #[serde(flatten)]
     pub base: BaseModel,
@@ -17,13 +18,13 @@ pub struct SKU {
     pub status: SkuStatus,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct SkuSpec {
     pub name: String,  // 如：颜色、尺寸
     pub value: String, // 如：红色、XL
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
 pub enum SkuStatus {
     Active,   // 可售
     Inactive, // 停售
@@ -1,9 +1,9 @@
 use entity_core::BaseModel;
 use entity_macros::Entity;
-use regex::Regex;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-use rbac::RBACRole;
+use rbac::{route_match, RBACRole};
 
 /// 表示一个路由项的结构体
 ///
@@ -13,7 +13,7 @@ use rbac::RBACRole;
 /// * `method` - HTTP 方法
 /// * `path` - 路由路径
 /// * `description` - 路由描述
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RouteItem {
     pub module: String,
     pub method: String,
@@ -43,7 +43,7 @@ impl RouteItem {
         }
     }
 
-    /// 检查路由项是否匹配给定的请求
+    /// 检查路由项是否匹配给定的请求, 与 RBAC 权限检查使用同一个路由匹配器
     ///
     /// # 参数
     ///
@@ -58,11 +58,54 @@ impl RouteItem {
             return false;
         }
 
-        // 将路由模式转换为正则表达式
-        let path_pattern = self.path.replace(":id", r"[^/]+");
-        let re = Regex::new(&format!("^{}$", path_pattern));
+        route_match(path, &self.path)
+    }
+}
+
+/// 数据范围规则
+///
+/// * `All` - 全部数据
+/// * `Own` - 自己创建的数据
+/// * `In` - 字段取值在列表中的数据, 例如只能访问指定供应商的商品
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScopeRule {
+    All,
+    Own,
+    In { field: String, values: Vec<String> },
+}
+
+/// 角色对某一类资源的数据范围, 满足任意一条规则即可访问
+///
+/// # 字段
+///
+/// * `resource` - 资源名称, 例如 `product`
+/// * `rules` - 数据范围规则
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DataScope {
+    pub resource: String,
+    pub rules: Vec<ScopeRule>,
+}
 
-        re.is_ok() && re.unwrap().is_match(path)
+impl From<&DataScope> for rbac::DataScope {
+    fn from(scope: &DataScope) -> Self {
+        let rules = scope
+            .rules
+            .iter()
+            .map(|rule| match rule {
+                ScopeRule::All => rbac::ScopeRule::All,
+                ScopeRule::Own => rbac::ScopeRule::Own,
+                ScopeRule::In { field, values } => rbac::ScopeRule::In {
+                    field: field.clone(),
+                    values: values.clone(),
+                },
+            })
+            .collect();
+
+        rbac::DataScope {
+            resource: scope.resource.clone(),
+            rules,
+        }
     }
 }
 
@@ -73,12 +116,21 @@ impl RouteItem {
 /// * `base` - 基础模型字段
 /// * `name` - 角色名称
 /// * `permissions` - 角色拥有的权限列表
+/// * `parents` - 继承的父角色, 拥有父角色的全部权限
+/// * `data_scopes` - 数据范围, 未声明的资源不受限制
+/// * `require_two_factor` - 是否要求该角色的管理员启用双因素认证
 #[derive(Debug, Serialize, Deserialize, Clone, Entity)]
 pub struct Role {
     #[serde(flatten)]
     pub base: BaseModel,
     pub name: String,
     pub permissions: Vec<RouteItem>,
+    #[serde(default)]
+    pub parents: Vec<String>,
+    #[serde(default)]
+    pub data_scopes: Vec<DataScope>,
+    #[serde(default)]
+    pub require_two_factor: bool,
 }
 
 impl Role {
@@ -98,6 +150,9 @@ impl Role {
             base: BaseModel::new(id),
             name,
             permissions,
+            parents: vec![],
+            data_scopes: vec![],
+            require_two_factor: false,
         }
     }
 }
@@ -121,7 +176,15 @@ impl RBACRole for Role {
         self.name.clone()
     }
 
+    fn parent_names(&self) -> Vec<String> {
+        self.parents.clone()
+    }
+
     fn check_permission(&self, method: &str, path: &str) -> bool {
         self.permissions.iter().any(|p| p.matches(method, path))
     }
+
+    fn data_scopes(&self) -> Vec<rbac::DataScope> {
+        self.data_scopes.iter().map(Into::into).collect()
+    }
 }
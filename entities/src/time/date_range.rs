@@ -30,7 +30,7 @@ impl DateRange {
     /// # 返回值
     ///
     /// 返回一个包含开始和结束时间戳的元组,如果某个时间戳为 None 则使用 0 代替
-    pub fn to_period(&self) -> Period {
+    pub fn to_period(self) -> Period {
         (self.start.unwrap_or(0), self.end.unwrap_or(0))
     }
 }
@@ -1 +1,3 @@
 mod date_range;
+
+pub use date_range::*;
@@ -1,10 +1,11 @@
+use chrono::Utc;
 use entity_core::BaseModel;
 use entity_macros::Entity;
 use serde::{Deserialize, Serialize};
 
 use rbac::RBACUser;
 
-use crate::Secret;
+use crate::{NotificationPreferences, Secret, SsoIdentity, TwoFactor};
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone, Entity)]
 #[serde(default)]
@@ -17,21 +18,33 @@ pub struct Admin {
     pub age: u8,
     pub avatar: String,
     pub is_active: bool,
-    pub role_name: String,
+    /// 拥有的角色, 旧数据中的`role_name`由`AdminRepository::migrate_role_names`迁移
+    pub role_names: Vec<String>,
+    pub notification_preferences: NotificationPreferences,
+    /// 早于该时间(毫秒级时间戳)签发的令牌全部失效
+    pub tokens_valid_after: i64,
+    /// 双因素认证设置
+    pub two_factor: TwoFactor,
+    /// 单点登录身份, 通过身份提供方登录过的管理员才有
+    pub sso: Option<SsoIdentity>,
 }
 
+/// RBAC 中管理员主体的前缀, 与角色名称和 API 密钥区分
+const SUBJECT_PREFIX: &str = "user:";
+
+/// RBAC 的主体由登录账号加上前缀得到, 与授权中间件按访问令牌中的`account`注入的`Subject`一致
 impl RBACUser for Admin {
-    fn account(&self) -> String {
-        self.name.clone()
+    fn subject(&self) -> String {
+        Self::subject_of(&self.secret.account)
     }
 
-    fn role_name(&self) -> String {
-        self.role_name.clone()
+    fn role_names(&self) -> Vec<String> {
+        self.role_names.clone()
     }
 }
 
 impl Admin {
-    pub fn new(id: String, secret: Secret, name: String, role_name: String) -> Self {
+    pub fn new(id: String, secret: Secret, name: String, role_names: Vec<String>) -> Self {
         Self {
             base: BaseModel::new(id),
             secret,
@@ -39,7 +52,81 @@ impl Admin {
             age: 0,
             avatar: String::new(),
             is_active: true,
-            role_name,
+            role_names,
+            notification_preferences: NotificationPreferences::default(),
+            tokens_valid_after: 0,
+            two_factor: TwoFactor::default(),
+            sso: None,
         }
     }
+
+    /// 登录账号在 RBAC 中的主体标识
+    pub fn subject_of(account: &str) -> String {
+        format!("{}{}", SUBJECT_PREFIX, account)
+    }
+
+    /// 是否直接拥有指定角色
+    pub fn has_role(&self, role_name: &str) -> bool {
+        self.role_names.iter().any(|role| role == role_name)
+    }
+
+    /// 使此前签发的所有令牌失效
+    pub fn invalidate_tokens(&mut self) {
+        self.tokens_valid_after = Utc::now().timestamp_millis();
+    }
+
+    /// 启用账号
+    pub fn enable(&mut self) {
+        self.is_active = true;
+    }
+
+    /// 禁用账号, 已签发的令牌同时失效
+    pub fn disable(&mut self) {
+        self.is_active = false;
+        self.invalidate_tokens();
+    }
+
+    /// 账号是否可以登录
+    pub fn can_login(&self) -> bool {
+        self.is_active && self.base.deleted_at == 0
+    }
+
+    /// 在指定时间签发的令牌是否仍然有效
+    ///
+    /// # 参数
+    ///
+    /// * `issued_at` - 令牌签发时间(毫秒级时间戳)
+    pub fn accepts_token(&self, issued_at: i64) -> bool {
+        self.can_login() && issued_at >= self.tokens_valid_after
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accepts_token() {
+        let mut admin = Admin::new(
+            "1".to_string(),
+            Secret::default(),
+            "admin".to_string(),
+            vec!["admin".to_string()],
+        );
+        let issued_at = Utc::now().timestamp_millis() - 1;
+        assert!(admin.accepts_token(issued_at));
+
+        admin.disable();
+        assert!(!admin.can_login());
+        assert!(!admin.accepts_token(issued_at));
+
+        // 重新启用后, 禁用前签发的令牌仍然无效
+        admin.enable();
+        assert!(admin.can_login());
+        assert!(!admin.accepts_token(issued_at));
+        assert!(admin.accepts_token(admin.tokens_valid_after));
+
+        admin.base.delete();
+        assert!(!admin.can_login());
+    }
 }
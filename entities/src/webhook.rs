@@ -0,0 +1,54 @@
+//! Webhook endpoint entity module
+//!
+//! A webhook endpoint is an external HTTP receiver registered by an admin.
+//! Messages sent through the `Webhook` channel use the endpoint id as their
+//! recipient and are signed with the endpoint secret.
+
+use entity_core::BaseModel;
+use entity_macros::Entity;
+use serde::{Deserialize, Serialize};
+
+/// Webhook endpoint entity structure
+#[derive(Debug, Serialize, Deserialize, Clone, Entity)]
+pub struct WebhookEndpoint {
+    /// Base model fields including ID and timestamps
+    #[serde(flatten)]
+    pub base: BaseModel,
+
+    /// Human readable name
+    pub name: String,
+
+    /// URL the payloads are POSTed to
+    pub url: String,
+
+    /// Secret used to sign the payloads
+    pub secret: String,
+
+    /// Inactive endpoints are not delivered to
+    pub is_active: bool,
+
+    /// Id of the admin who registered the endpoint
+    pub created_by: String,
+}
+
+impl WebhookEndpoint {
+    /// Creates a new active webhook endpoint
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Unique identifier for the endpoint
+    /// * `name` - Human readable name
+    /// * `url` - URL the payloads are POSTed to
+    /// * `secret` - Secret used to sign the payloads
+    /// * `created_by` - Id of the admin registering the endpoint
+    pub fn new(id: String, name: String, url: String, secret: String, created_by: String) -> Self {
+        Self {
+            base: BaseModel::new(id),
+            name,
+            url,
+            secret,
+            is_active: true,
+            created_by,
+        }
+    }
+}
@@ -1,11 +1,13 @@
+use entities::{Admin, NotificationPreferences};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateAdminParams {
     pub account: String,
     pub password: String,
     pub name: String,
-    pub role_name: String,
+    pub role_names: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -13,11 +15,94 @@ pub struct UpdateAdminParams {
     pub id: String,
     pub name: Option<String>,
     pub password: Option<String>,
-    pub role_name: Option<String>,
+    pub role_names: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChangePasswordParams {
+    pub id: String,
+    pub old_password: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateProfileParams {
+    pub id: String,
+    pub name: Option<String>,
+    pub avatar: Option<String>,
+    pub age: Option<u8>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateAdminRoleParams {
     pub id: String,
+    pub role_names: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateNotificationPreferencesParams {
+    pub id: String,
+    pub preferences: NotificationPreferences,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SeedSuperAdminParams {
+    pub account: String,
+    pub password: String,
+    pub name: String,
     pub role_name: String,
 }
+
+/// 初始化结果, 用于区分本次创建的数据和已存在而跳过的数据
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct SeedReport {
+    pub role_created: bool,
+    pub admin_created: bool,
+}
+
+/// 单点登录时身份提供方返回的用户信息
+///
+/// # 字段
+///
+/// * `issuer` - 身份提供方的签发方地址
+/// * `subject` - 用户在身份提供方中的唯一标识
+/// * `email` - 邮箱, 作为自动创建的管理员账号
+/// * `email_verified` - 邮箱是否已验证, 只有已验证的邮箱才会关联已有的管理员
+/// * `name` - 名称
+/// * `role_names` - 按用户组映射的角色
+/// * `provision` - 管理员不存在时是否自动创建
+/// * `sync_role` - 是否同步已有管理员的角色
+/// * `link_by_email` - 是否使用已验证的邮箱关联同账号的本地管理员
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SsoLoginParams {
+    pub issuer: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub name: Option<String>,
+    pub role_names: Vec<String>,
+    pub provision: bool,
+    pub sync_role: bool,
+    pub link_by_email: bool,
+}
+
+/// 单点登录结果
+///
+/// # 字段
+///
+/// * `admin` - 登录的管理员
+/// * `provisioned` - 是否本次自动创建
+/// * `role_changed` - 角色是否发生变化, 需要重新加载权限
+#[derive(Debug)]
+pub struct SsoLogin {
+    pub admin: Admin,
+    pub provisioned: bool,
+    pub role_changed: bool,
+}
+
+/// 开始绑定双因素认证时返回的信息
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorEnrollment {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
@@ -8,7 +8,10 @@ pub trait IAdminRepository: Send + Sync {
     async fn update(&self, admin: &Admin) -> Result<()>;
     async fn find_by_id(&self, id: &str) -> Result<Option<Admin>>;
     async fn find_by_account(&self, account: &str) -> Result<Option<Admin>>;
+    async fn find_by_sso(&self, issuer: &str, subject: &str) -> Result<Option<Admin>>;
     async fn find_all(&self) -> Result<Vec<Admin>>;
+    async fn find_by_ids(&self, ids: &[String]) -> Result<Vec<Admin>>;
+    async fn find_by_role(&self, role_name: &str) -> Result<Vec<Admin>>;
 }
 
 #[async_trait]
@@ -18,4 +21,5 @@ pub trait IRoleRepository: Send + Sync {
     async fn find_by_id(&self, id: &str) -> Result<Option<entities::Role>>;
     async fn find_all(&self) -> Result<Vec<entities::Role>>;
     async fn exists(&self, name: &str) -> Result<bool>;
+    async fn find_by_name(&self, name: &str) -> Result<Option<entities::Role>>;
 }
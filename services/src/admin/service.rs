@@ -1,12 +1,20 @@
-use crate::errors::Result;
-use entities::{Admin, Secret};
+use chrono::Utc;
 
+use crate::errors::{Error, Resource, Result};
+use entities::{Admin, NotificationPreferences, PasswordPolicy, Role, RouteItem, Secret, SsoIdentity};
+
+use super::dto::{
+    ChangePasswordParams, CreateAdminParams, SeedReport, SeedSuperAdminParams, SsoLogin, SsoLoginParams,
+    TwoFactorEnrollment, UpdateAdminParams, UpdateAdminRoleParams, UpdateNotificationPreferencesParams,
+    UpdateProfileParams,
+};
 use super::repository::{IAdminRepository, IRoleRepository};
-use super::dto::{CreateAdminParams, UpdateAdminParams, UpdateAdminRoleParams};
+use crate::role::resolve_roles;
 
 pub struct AdminService<A: IAdminRepository, R: IRoleRepository> {
     admin_repo: A,
     role_repo: R,
+    password_policy: PasswordPolicy,
 }
 
 impl<A: IAdminRepository, R: IRoleRepository> AdminService<A, R> {
@@ -14,26 +22,156 @@ impl<A: IAdminRepository, R: IRoleRepository> AdminService<A, R> {
         Self {
             admin_repo,
             role_repo,
+            password_policy: PasswordPolicy::default(),
         }
     }
 
-    pub async fn create_admin(&self, params: CreateAdminParams) -> Result<()> {
+    /// 设置创建或修改密码时使用的密码策略
+    pub fn with_password_policy(mut self, policy: PasswordPolicy) -> Self {
+        self.password_policy = policy;
+        self
+    }
+
+    pub async fn create_admin(&self, params: CreateAdminParams) -> Result<Admin> {
         // 检查账号是否已存在
-        if let Some(_) = self.admin_repo.find_by_account(&params.account).await? {
-            return Err("账号已存在".into());
+        if self.admin_repo.find_by_account(&params.account).await?.is_some() {
+            return Err(Error::Conflict("账号已存在".to_string()));
         }
 
-        if !self.role_repo.exists(&params.role_name).await? {
-            return Err("指定的角色不存在".into());
-        }
+        self.check_roles(&params.role_names).await?;
 
-        let secret = Secret::new(params.account.clone(), params.password)?;
+        let secret = Secret::new(params.account.clone(), params.password, &self.password_policy)?;
         let id = libs::next_id().await;
 
-        let user = Admin::new(id, secret, params.name, params.role_name);
+        let user = Admin::new(id, secret, params.name, params.role_names);
         self.admin_repo.create(&user).await?;
 
-        Ok(())
+        Ok(user)
+    }
+
+    /// 初始化超级管理员角色和账号
+    ///
+    /// 角色拥有所有路由的权限。角色或账号已存在时跳过, 可以重复执行。
+    pub async fn seed_super_admin(&self, params: SeedSuperAdminParams) -> Result<SeedReport> {
+        let mut report = SeedReport::default();
+
+        if !self.role_repo.exists(&params.role_name).await? {
+            let permission = RouteItem::new(
+                "*".to_string(),
+                "*".to_string(),
+                "/*".to_string(),
+                "所有权限".to_string(),
+            );
+            let role = Role::new(libs::next_id().await, params.role_name.clone(), vec![permission]);
+            self.role_repo.create(&role).await?;
+            report.role_created = true;
+        }
+
+        if self.admin_repo.find_by_account(&params.account).await?.is_none() {
+            let secret = Secret::new(params.account, params.password, &self.password_policy)?;
+            let user = Admin::new(libs::next_id().await, secret, params.name, vec![params.role_name]);
+            self.admin_repo.create(&user).await?;
+            report.admin_created = true;
+        }
+
+        Ok(report)
+    }
+
+    /// 单点登录
+    ///
+    /// 按身份提供方的用户标识查找管理员; 找不到时, 只有开启了按邮箱关联且邮箱已验证,
+    /// 才关联同账号的本地管理员, 否则拒绝登录, 需要手动关联;
+    /// 仍找不到且允许自动创建时, 使用映射的角色创建管理员
+    pub async fn sso_login(&self, params: SsoLoginParams) -> Result<SsoLogin> {
+        let identity = SsoIdentity::new(params.issuer, params.subject);
+
+        let mut user = self
+            .admin_repo
+            .find_by_sso(&identity.issuer, &identity.subject)
+            .await?;
+
+        if user.is_none() {
+            if let Some(email) = &params.email {
+                user = self.admin_repo.find_by_account(email).await?;
+
+                let linkable = user
+                    .as_ref()
+                    .is_some_and(|user| user.sso.is_none() && params.link_by_email && params.email_verified);
+                if user.is_some() && !linkable {
+                    return Err(Error::PermissionDenied(
+                        "已存在同账号的管理员, 请联系管理员手动关联".to_string(),
+                    ));
+                }
+            }
+        }
+
+        let Some(mut user) = user else {
+            if !params.provision {
+                return Err(Error::PermissionDenied(
+                    "管理员不存在, 请联系管理员开通账号".to_string(),
+                ));
+            }
+
+            if params.role_names.is_empty() {
+                return Err(Error::PermissionDenied(
+                    "没有可分配的角色, 请联系管理员开通账号".to_string(),
+                ));
+            }
+            self.check_roles(&params.role_names).await?;
+
+            let account = params
+                .email
+                .ok_or_else(|| Error::InvalidArgument("身份提供方未返回邮箱".to_string()))?;
+
+            let name = params.name.unwrap_or_else(|| account.clone());
+            let secret = Secret::random(account)?;
+            let mut user = Admin::new(libs::next_id().await, secret, name, params.role_names);
+            user.sso = Some(identity);
+            self.admin_repo.create(&user).await?;
+
+            return Ok(SsoLogin {
+                admin: user,
+                provisioned: true,
+                role_changed: true,
+            });
+        };
+
+        let mut changed = false;
+        if user.sso.is_none() {
+            user.sso = Some(identity);
+            changed = true;
+        }
+
+        let mut role_changed = false;
+        if params.sync_role
+            && !params.role_names.is_empty()
+            && !same_roles(&params.role_names, &user.role_names)
+        {
+            self.check_roles(&params.role_names).await?;
+
+            user.role_names = params.role_names;
+            role_changed = true;
+            changed = true;
+        }
+
+        if changed {
+            self.admin_repo.update(&user).await?;
+        }
+
+        Ok(SsoLogin {
+            admin: user,
+            provisioned: false,
+            role_changed,
+        })
+    }
+
+    pub async fn get_admin(&self, id: &str) -> Result<Admin> {
+        let user = self
+            .admin_repo
+            .find_by_id(id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Admin))?;
+        Ok(user)
     }
 
     pub async fn get_admin_list(&self) -> Result<Vec<Admin>> {
@@ -41,57 +179,403 @@ impl<A: IAdminRepository, R: IRoleRepository> AdminService<A, R> {
         Ok(users)
     }
 
-    pub async fn update_admin(&self, params: UpdateAdminParams) -> Result<()> {
+    pub async fn update_admin(&self, params: UpdateAdminParams) -> Result<Admin> {
         let mut user = self
             .admin_repo
             .find_by_id(&params.id)
             .await?
-            .ok_or("管理员不存在")?;
+            .ok_or(Error::NotFound(Resource::Admin))?;
 
         if let Some(name) = params.name {
             user.name = name;
         }
 
         if let Some(password) = params.password {
-            user.secret.change_password(password)?;
+            user.secret.change_password(password, &self.password_policy)?;
+            user.invalidate_tokens();
         }
 
-        if let Some(role_name) = params.role_name {
-            if !self.role_repo.exists(&role_name).await? {
-                return Err("指定的角色不存在".into());
-            }
+        if let Some(role_names) = params.role_names {
+            self.check_roles(&role_names).await?;
+            user.role_names = role_names;
+        }
+
+        self.admin_repo.update(&user).await?;
+        Ok(user)
+    }
 
-            user.role_name = role_name;
+    pub async fn update_admin_role(&self, params: UpdateAdminRoleParams) -> Result<Admin> {
+        let mut user = self
+            .admin_repo
+            .find_by_id(&params.id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Admin))?;
+
+        // 检查新角色是否存在
+        self.check_roles(&params.role_names).await?;
+
+        user.role_names = params.role_names;
+        self.admin_repo.update(&user).await?;
+
+        Ok(user)
+    }
+
+    pub async fn delete_admin(&self, id: String) -> Result<Admin> {
+        let mut user = self
+            .admin_repo
+            .find_by_id(&id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Admin))?;
+
+        user.base.delete();
+        self.admin_repo.update(&user).await?;
+
+        Ok(user)
+    }
+
+    /// 启用或禁用管理员账号
+    ///
+    /// 禁用后该管理员已签发的令牌立即失效
+    pub async fn set_admin_active(&self, id: &str, active: bool) -> Result<()> {
+        let mut user = self
+            .admin_repo
+            .find_by_id(id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Admin))?;
+
+        if active {
+            user.enable();
+        } else {
+            user.disable();
         }
 
         self.admin_repo.update(&user).await?;
         Ok(())
     }
 
-    pub async fn update_admin_role(&self, params: UpdateAdminRoleParams) -> Result<()> {
+    /// 修改自己的密码, 需要验证旧密码
+    ///
+    /// 修改后此前签发的令牌全部失效, 需要重新登录
+    pub async fn change_password(&self, params: ChangePasswordParams) -> Result<()> {
         let mut user = self
             .admin_repo
             .find_by_id(&params.id)
             .await?
-            .ok_or("管理员不存在")?;
+            .ok_or(Error::NotFound(Resource::Admin))?;
 
-        // 检查新角色是否存在
-        if !self.role_repo.exists(&params.role_name).await? {
-            return Err("指定的角色不存在".into());
+        if !user.secret.is_match(&params.old_password) {
+            return Err(Error::InvalidArgument("旧密码错误".to_string()));
         }
 
-        user.role_name = params.role_name;
+        user.secret
+            .change_password(params.new_password, &self.password_policy)?;
+        user.invalidate_tokens();
+
         self.admin_repo.update(&user).await?;
+        Ok(())
+    }
+
+    /// 修改自己的个人资料
+    pub async fn update_profile(&self, params: UpdateProfileParams) -> Result<Admin> {
+        let mut user = self
+            .admin_repo
+            .find_by_id(&params.id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Admin))?;
+
+        if let Some(name) = params.name {
+            user.name = name;
+        }
+
+        if let Some(avatar) = params.avatar {
+            user.avatar = avatar;
+        }
+
+        if let Some(age) = params.age {
+            user.age = age;
+        }
+
+        self.admin_repo.update(&user).await?;
+        Ok(user)
+    }
+
+    /// 获取管理员所有角色及其继承的父角色拥有的权限, 已去重
+    pub async fn get_permissions(&self, id: &str) -> Result<Vec<RouteItem>> {
+        let user = self
+            .admin_repo
+            .find_by_id(id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Admin))?;
+        let roles = resolve_roles(&self.role_repo, &user.role_names).await?;
+
+        let mut permissions: Vec<RouteItem> = vec![];
+        for permission in roles.into_iter().flat_map(|role| role.permissions) {
+            let exists = permissions
+                .iter()
+                .any(|item| item.method == permission.method && item.path == permission.path);
+            if !exists {
+                permissions.push(permission);
+            }
+        }
+
+        Ok(permissions)
+    }
+
+    /// 管理员的任一角色(包括继承的父角色)是否要求启用双因素认证
+    pub async fn requires_two_factor(&self, admin: &Admin) -> Result<bool> {
+        let roles = resolve_roles(&self.role_repo, &admin.role_names).await?;
+        Ok(roles.iter().any(|role| role.require_two_factor))
+    }
+
+    /// 检查角色列表不为空且角色都存在
+    async fn check_roles(&self, role_names: &[String]) -> Result<()> {
+        if role_names.is_empty() {
+            return Err(Error::InvalidArgument("至少需要指定一个角色".to_string()));
+        }
+
+        for role_name in role_names {
+            if !self.role_repo.exists(role_name).await? {
+                return Err(Error::InvalidArgument(format!("角色 {} 不存在", role_name)));
+            }
+        }
 
         Ok(())
     }
 
-    pub async fn delete_admin(&self, id: String) -> Result<()> {
-        let mut user = self.admin_repo.find_by_id(&id).await?.ok_or("管理员不存在")?;
+    /// 开始绑定双因素认证, 生成新的密钥
+    ///
+    /// # 参数
+    ///
+    /// * `id` - 管理员ID
+    /// * `issuer` - 显示在认证器应用中的签发方名称
+    pub async fn begin_two_factor_enrollment(&self, id: &str, issuer: &str) -> Result<TwoFactorEnrollment> {
+        let mut user = self
+            .admin_repo
+            .find_by_id(id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Admin))?;
 
-        user.base.delete();
+        let secret = user.two_factor.begin_enrollment()?;
+        let otpauth_uri = user
+            .two_factor
+            .otpauth_uri(issuer, &user.secret.account)
+            .ok_or("生成绑定信息失败")?;
+
+        self.admin_repo.update(&user).await?;
+
+        Ok(TwoFactorEnrollment { secret, otpauth_uri })
+    }
+
+    /// 验证绑定时的验证码并启用双因素认证
+    ///
+    /// # 返回值
+    ///
+    /// 返回恢复码明文, 只返回这一次
+    pub async fn activate_two_factor(&self, id: &str, code: &str) -> Result<Vec<String>> {
+        let mut user = self
+            .admin_repo
+            .find_by_id(id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Admin))?;
+
+        let recovery_codes = user.two_factor.activate(code, Utc::now().timestamp())?;
+        self.admin_repo.update(&user).await?;
+
+        Ok(recovery_codes)
+    }
+
+    /// 验证双因素认证的验证码或恢复码
+    ///
+    /// 验证通过后保存最近使用的时间步和剩余的恢复码
+    pub async fn verify_two_factor(&self, id: &str, code: &str) -> Result<Admin> {
+        let user = self.find_verified(id, code).await?;
+        self.admin_repo.update(&user).await?;
+        Ok(user)
+    }
+
+    /// 重新生成恢复码, 需要验证当前的验证码
+    pub async fn regenerate_recovery_codes(&self, id: &str, code: &str) -> Result<Vec<String>> {
+        let mut user = self.find_verified(id, code).await?;
+
+        let recovery_codes = user.two_factor.regenerate_recovery_codes();
+        self.admin_repo.update(&user).await?;
+
+        Ok(recovery_codes)
+    }
+
+    /// 关闭双因素认证, 角色要求启用时不允许关闭
+    pub async fn disable_two_factor(&self, id: &str, code: &str) -> Result<()> {
+        let mut user = self.find_verified(id, code).await?;
+
+        if self.requires_two_factor(&user).await? {
+            return Err(Error::PermissionDenied("当前角色要求启用双因素认证".to_string()));
+        }
+
+        user.two_factor.disable();
+        self.admin_repo.update(&user).await?;
+
+        Ok(())
+    }
+
+    /// 读取管理员并验证双因素认证的验证码, 不保存验证后的状态
+    async fn find_verified(&self, id: &str, code: &str) -> Result<Admin> {
+        let mut user = self
+            .admin_repo
+            .find_by_id(id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Admin))?;
+
+        if !user.two_factor.verify(code, Utc::now().timestamp()) {
+            return Err(Error::InvalidArgument("验证码错误".to_string()));
+        }
+
+        Ok(user)
+    }
+
+    pub async fn get_notification_preferences(&self, id: &str) -> Result<NotificationPreferences> {
+        let user = self
+            .admin_repo
+            .find_by_id(id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Admin))?;
+        Ok(user.notification_preferences)
+    }
+
+    pub async fn update_notification_preferences(
+        &self,
+        params: UpdateNotificationPreferencesParams,
+    ) -> Result<()> {
+        params.preferences.validate()?;
+
+        let mut user = self
+            .admin_repo
+            .find_by_id(&params.id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Admin))?;
+
+        user.notification_preferences = params.preferences;
         self.admin_repo.update(&user).await?;
 
         Ok(())
     }
 }
+
+/// 两个角色列表是否包含相同的角色, 忽略顺序
+fn same_roles(a: &[String], b: &[String]) -> bool {
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    a.sort();
+    a.dedup();
+    b.sort();
+    b.dedup();
+    a == b
+}
+
+#[cfg(test)]
+mod tests {
+    use entities::{Admin, Role, Secret, SsoIdentity};
+
+    use super::AdminService;
+    use crate::{
+        admin::{IAdminRepository, SsoLoginParams},
+        errors::Error,
+        testing::{MemoryAdminRepository, MemoryRoleRepository},
+    };
+
+    const ISSUER: &str = "https://sso.example.com";
+
+    fn service(admins: Vec<Admin>) -> AdminService<MemoryAdminRepository, MemoryRoleRepository> {
+        let roles = vec![Role::new("1".to_string(), "viewer".to_string(), vec![])];
+        AdminService::new(
+            MemoryAdminRepository::new(admins),
+            MemoryRoleRepository::new(roles),
+        )
+    }
+
+    fn admin(id: &str, account: &str) -> Admin {
+        let secret = Secret::random(account.to_string()).unwrap();
+        Admin::new(
+            id.to_string(),
+            secret,
+            account.to_string(),
+            vec!["viewer".to_string()],
+        )
+    }
+
+    fn params(email: &str, email_verified: bool, link_by_email: bool) -> SsoLoginParams {
+        SsoLoginParams {
+            issuer: ISSUER.to_string(),
+            subject: "user-1".to_string(),
+            email: Some(email.to_string()),
+            email_verified,
+            name: None,
+            role_names: vec!["viewer".to_string()],
+            provision: true,
+            sync_role: false,
+            link_by_email,
+        }
+    }
+
+    #[tokio::test]
+    async fn sso_login_links_local_admin_only_when_enabled_and_verified() {
+        let service = service(vec![admin("1", "root@example.com")]);
+
+        for (email_verified, link_by_email) in [(true, false), (false, true), (false, false)] {
+            let result = service
+                .sso_login(params("root@example.com", email_verified, link_by_email))
+                .await;
+            assert!(matches!(result, Err(Error::PermissionDenied(_))));
+        }
+        assert!(service
+            .admin_repo
+            .find_by_id("1")
+            .await
+            .unwrap()
+            .unwrap()
+            .sso
+            .is_none());
+
+        let login = service
+            .sso_login(params("root@example.com", true, true))
+            .await
+            .unwrap();
+        assert_eq!(login.admin.base.id, "1");
+        assert!(!login.provisioned);
+        assert_eq!(
+            login.admin.sso,
+            Some(SsoIdentity::new(ISSUER.to_string(), "user-1".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn sso_login_does_not_relink_admin_with_another_identity() {
+        let mut linked = admin("1", "root@example.com");
+        linked.sso = Some(SsoIdentity::new(ISSUER.to_string(), "user-2".to_string()));
+        let service = service(vec![linked]);
+
+        let result = service.sso_login(params("root@example.com", true, true)).await;
+        assert!(matches!(result, Err(Error::PermissionDenied(_))));
+    }
+
+    #[tokio::test]
+    async fn sso_login_provisioning_errors_are_client_errors() {
+        let service = service(vec![]);
+
+        let mut without_roles = params("new@example.com", true, false);
+        without_roles.role_names = vec![];
+        let result = service.sso_login(without_roles).await;
+        assert!(matches!(result, Err(Error::PermissionDenied(_))));
+
+        let mut without_email = params("new@example.com", true, false);
+        without_email.email = None;
+        let result = service.sso_login(without_email).await;
+        assert!(matches!(result, Err(Error::InvalidArgument(_))));
+
+        let login = service
+            .sso_login(params("new@example.com", true, false))
+            .await
+            .unwrap();
+        assert!(login.provisioned);
+        assert_eq!(login.admin.secret.account, "new@example.com");
+    }
+}
@@ -0,0 +1,58 @@
+use entities::ApiKey;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateApiKeyParams {
+    pub name: String,
+    pub role_name: String,
+    /// 过期时间(秒级时间戳), 为空表示永不过期
+    pub expires_at: Option<i64>,
+    pub created_by: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateApiKeyParams {
+    pub id: String,
+    pub name: Option<String>,
+    pub role_name: Option<String>,
+    pub expires_at: Option<i64>,
+    pub is_active: Option<bool>,
+}
+
+/// API 密钥详情, 不包含密钥哈希
+#[derive(Debug, Serialize)]
+pub struct ApiKeyDetail {
+    pub id: String,
+    pub name: String,
+    pub key_id: String,
+    pub role_name: String,
+    pub expires_at: i64,
+    pub last_used_at: i64,
+    pub is_active: bool,
+    pub created_by: String,
+    pub created_at: u64,
+}
+
+impl From<ApiKey> for ApiKeyDetail {
+    fn from(api_key: ApiKey) -> Self {
+        Self {
+            id: api_key.base.id,
+            name: api_key.name,
+            key_id: api_key.key_id,
+            role_name: api_key.role_name,
+            expires_at: api_key.expires_at,
+            last_used_at: api_key.last_used_at,
+            is_active: api_key.is_active,
+            created_by: api_key.created_by,
+            created_at: api_key.base.created_at,
+        }
+    }
+}
+
+/// 客户端令牌只在创建时返回一次
+#[derive(Debug, Serialize)]
+pub struct ApiKeyToken {
+    pub id: String,
+    pub key_id: String,
+    pub token: String,
+}
@@ -0,0 +1,7 @@
+mod dto;
+mod repository;
+mod service;
+
+pub use dto::*;
+pub use repository::IApiKeyRepository;
+pub use service::ApiKeyService;
@@ -0,0 +1,14 @@
+use crate::errors::Result;
+use async_trait::async_trait;
+use entities::ApiKey;
+
+#[async_trait]
+pub trait IApiKeyRepository: Send + Sync {
+    async fn create(&self, api_key: &ApiKey) -> Result<()>;
+    async fn update(&self, api_key: &ApiKey) -> Result<()>;
+    async fn find_by_id(&self, id: &str) -> Result<Option<ApiKey>>;
+    async fn find_by_key_id(&self, key_id: &str) -> Result<Option<ApiKey>>;
+    async fn find_all(&self) -> Result<Vec<ApiKey>>;
+    /// 更新最近使用时间, 不检查版本号, 并发请求不会互相冲突
+    async fn update_last_used(&self, id: &str, last_used_at: i64) -> Result<()>;
+}
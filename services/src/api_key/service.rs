@@ -0,0 +1,129 @@
+use chrono::Utc;
+
+use entities::ApiKey;
+
+use super::dto::{ApiKeyDetail, ApiKeyToken, CreateApiKeyParams, UpdateApiKeyParams};
+use super::repository::IApiKeyRepository;
+use crate::admin::IRoleRepository;
+use crate::errors::{Error, Resource, Result};
+
+/// 最近使用时间的更新间隔(秒), 避免每次请求都写数据库
+const LAST_USED_INTERVAL: i64 = 60;
+
+pub struct ApiKeyService<K: IApiKeyRepository, R: IRoleRepository> {
+    repo: K,
+    role_repo: R,
+}
+
+impl<K: IApiKeyRepository, R: IRoleRepository> ApiKeyService<K, R> {
+    pub fn new(repo: K, role_repo: R) -> Self {
+        Self { repo, role_repo }
+    }
+
+    /// 创建 API 密钥
+    ///
+    /// # Returns
+    /// * `Ok((ApiKey, ApiKeyToken))` - 密钥和客户端令牌, 令牌之后不会再次返回
+    pub async fn create_api_key(&self, params: CreateApiKeyParams) -> Result<(ApiKey, ApiKeyToken)> {
+        if !self.role_repo.exists(&params.role_name).await? {
+            return Err(Error::InvalidArgument("指定的角色不存在".to_string()));
+        }
+
+        let expires_at = params.expires_at.unwrap_or_default();
+        if expires_at != 0 && expires_at <= Utc::now().timestamp() {
+            return Err(Error::InvalidArgument("过期时间必须晚于当前时间".to_string()));
+        }
+
+        let (api_key, token) = ApiKey::generate(
+            libs::next_id().await,
+            params.name,
+            params.role_name,
+            expires_at,
+            params.created_by,
+        );
+        self.repo.create(&api_key).await?;
+
+        let token = ApiKeyToken {
+            id: api_key.base.id.clone(),
+            key_id: api_key.key_id.clone(),
+            token,
+        };
+
+        Ok((api_key, token))
+    }
+
+    pub async fn get_api_key_list(&self) -> Result<Vec<ApiKeyDetail>> {
+        let api_keys = self.repo.find_all().await?;
+        Ok(api_keys.into_iter().map(ApiKeyDetail::from).collect())
+    }
+
+    pub async fn update_api_key(&self, params: UpdateApiKeyParams) -> Result<ApiKey> {
+        let mut api_key = self
+            .repo
+            .find_by_id(&params.id)
+            .await?
+            .ok_or(Error::NotFound(Resource::ApiKey))?;
+
+        if let Some(name) = params.name {
+            api_key.name = name;
+        }
+
+        if let Some(role_name) = params.role_name {
+            if !self.role_repo.exists(&role_name).await? {
+                return Err(Error::InvalidArgument("指定的角色不存在".to_string()));
+            }
+            api_key.role_name = role_name;
+        }
+
+        if let Some(expires_at) = params.expires_at {
+            api_key.expires_at = expires_at;
+        }
+
+        if let Some(is_active) = params.is_active {
+            api_key.is_active = is_active;
+        }
+
+        self.repo.update(&api_key).await?;
+        Ok(api_key)
+    }
+
+    pub async fn delete_api_key(&self, id: String) -> Result<ApiKey> {
+        let mut api_key = self
+            .repo
+            .find_by_id(&id)
+            .await?
+            .ok_or(Error::NotFound(Resource::ApiKey))?;
+
+        api_key.base.delete();
+        self.repo.update(&api_key).await?;
+
+        Ok(api_key)
+    }
+
+    /// 使用客户端令牌认证
+    ///
+    /// # Returns
+    /// * `Ok(Some(ApiKey))` - 令牌有效
+    /// * `Ok(None)` - 令牌格式错误、密钥不匹配、已禁用、已删除或已过期
+    pub async fn authenticate(&self, token: &str) -> Result<Option<ApiKey>> {
+        let Some((key_id, secret)) = ApiKey::parse_token(token) else {
+            return Ok(None);
+        };
+
+        let Some(mut api_key) = self.repo.find_by_key_id(key_id).await? else {
+            return Ok(None);
+        };
+
+        let now = Utc::now().timestamp();
+        if !api_key.verify_secret(secret) || !api_key.is_usable(now) {
+            return Ok(None);
+        }
+
+        if now - api_key.last_used_at >= LAST_USED_INTERVAL {
+            self.repo.update_last_used(&api_key.base.id, now).await?;
+            api_key.last_used_at = now;
+        }
+
+        Ok(Some(api_key))
+    }
+}
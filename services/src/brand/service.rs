@@ -1,6 +1,6 @@
 use super::dto::*;
 use super::repository::IBrandRepository;
-use crate::errors::Result;
+use crate::errors::{Error, Resource, Result};
 use entities::product::Brand;
 
 pub struct BrandService<R>
@@ -19,11 +19,7 @@ where
     }
 
     pub async fn create_brand(&self, params: CreateBrandParams) -> Result<()> {
-        let brand = Brand::new(
-            libs::next_id().await,
-            params.name,
-            params.logo,
-        );
+        let brand = Brand::new(libs::next_id().await, params.name, params.logo);
 
         self.repo.create(&brand).await
     }
@@ -33,7 +29,7 @@ where
             .repo
             .find_by_id(&params.id)
             .await?
-            .ok_or("品牌不存在")?;
+            .ok_or(Error::NotFound(Resource::Brand))?;
 
         params.apply(&mut brand);
 
@@ -59,4 +55,4 @@ where
         brand_list.sort_by_key(|b| b.sort_order);
         Ok(brand_list)
     }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,59 @@
+use entities::{Broadcast, BroadcastStatus, BroadcastTarget, MessageCategory, MessageChannel};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateBroadcastParams {
+    pub channel: MessageChannel,
+    pub target: BroadcastTarget,
+    pub category: MessageCategory,
+    pub subject: String,
+    pub content: String,
+    pub created_by: String,
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BroadcastQuery {
+    pub status: Option<BroadcastStatus>,
+    pub page: i64,
+    pub page_size: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BroadcastDetail {
+    pub id: String,
+    pub channel: MessageChannel,
+    pub target: BroadcastTarget,
+    pub category: MessageCategory,
+    pub subject: String,
+    pub content: String,
+    pub status: BroadcastStatus,
+    pub total: u64,
+    pub skipped: u64,
+    pub sent: u64,
+    pub failed: u64,
+    pub progress: f64,
+    pub created_by: String,
+    pub created_at: u64,
+}
+
+impl From<Broadcast> for BroadcastDetail {
+    fn from(broadcast: Broadcast) -> Self {
+        Self {
+            progress: broadcast.progress(),
+            id: broadcast.base.id,
+            channel: broadcast.channel,
+            target: broadcast.target,
+            category: broadcast.category,
+            subject: broadcast.subject,
+            content: broadcast.content,
+            status: broadcast.status,
+            total: broadcast.total,
+            skipped: broadcast.skipped,
+            sent: broadcast.sent,
+            failed: broadcast.failed,
+            created_by: broadcast.created_by,
+            created_at: broadcast.base.created_at,
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use crate::errors::Result;
+use async_trait::async_trait;
+use entities::Broadcast;
+
+use super::BroadcastQuery;
+
+#[async_trait]
+pub trait IBroadcastRepository: Send + Sync {
+    async fn create(&self, broadcast: &Broadcast) -> Result<()>;
+    async fn find_by_id(&self, id: &str) -> Result<Option<Broadcast>>;
+    async fn query(&self, query: &BroadcastQuery) -> Result<Vec<Broadcast>>;
+
+    /// 原子地累加投递计数, 返回更新后的广播记录
+    async fn record_delivery(&self, id: &str, sent: i64, failed: i64) -> Result<Option<Broadcast>>;
+
+    /// 将仍在发送中的广播标记为已完成
+    async fn mark_completed(&self, id: &str) -> Result<()>;
+
+    /// 将拆分消息中途失败的广播标记为失败, 总数改为实际写入的消息数量
+    async fn mark_failed(&self, id: &str, total: u64) -> Result<()>;
+}
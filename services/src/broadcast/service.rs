@@ -0,0 +1,183 @@
+use std::collections::HashSet;
+
+use chrono::Local;
+use entities::{Admin, Broadcast, BroadcastTarget, Message};
+
+use super::dto::{BroadcastDetail, BroadcastQuery, CreateBroadcastParams};
+use super::repository::IBroadcastRepository;
+use crate::admin::IAdminRepository;
+use crate::errors::{Error, Resource, Result};
+use crate::notification::IMessageRepository;
+
+/// 每批写入的消息数量
+const FAN_OUT_BATCH_SIZE: usize = 500;
+
+/// 广播服务
+///
+/// 负责解析广播的接收者, 并按接收者的通知偏好将广播拆分为每个接收者一条的消息记录。
+/// 消息的实际发送由 `NotificationService` 完成。
+pub struct BroadcastService<B, A, M>
+where
+    B: IBroadcastRepository,
+    A: IAdminRepository,
+    M: IMessageRepository,
+{
+    repo: B,
+    admin_repo: A,
+    message_repo: M,
+}
+
+impl<B, A, M> BroadcastService<B, A, M>
+where
+    B: IBroadcastRepository,
+    A: IAdminRepository,
+    M: IMessageRepository,
+{
+    pub fn new(repo: B, admin_repo: A, message_repo: M) -> Self {
+        Self {
+            repo,
+            admin_repo,
+            message_repo,
+        }
+    }
+
+    /// 创建广播并按批次生成待发送的消息
+    ///
+    /// 某一批消息写入失败时广播被标记为失败, 不再写入剩余的消息
+    ///
+    /// # Arguments
+    /// * `params` - 创建广播的参数
+    ///
+    /// # Returns
+    /// * `Ok(Broadcast)` - 创建的广播记录
+    pub async fn create_broadcast(&self, params: CreateBroadcastParams) -> Result<Broadcast> {
+        let admins = self.resolve_recipients(&params.target).await?;
+        if admins.is_empty() {
+            return Err(Error::InvalidArgument("没有符合条件的接收者".to_string()));
+        }
+
+        // 按接收者的通知偏好确定接收地址和投递时间, 未开启该渠道或缺少联系方式的接收者将被跳过
+        let now = Local::now().timestamp() as u64;
+        let routes: Vec<(String, u64)> = admins
+            .iter()
+            .filter_map(|admin| {
+                admin
+                    .notification_preferences
+                    .route(params.category, &params.channel, &admin.base.id, now)
+            })
+            .collect();
+
+        let broadcast = Broadcast::new(
+            libs::next_id().await,
+            params.channel,
+            params.target,
+            params.category,
+            params.subject,
+            params.content,
+            routes.len() as u64,
+            (admins.len() - routes.len()) as u64,
+            params.created_by,
+        );
+        let request_id = params.request_id;
+        self.repo.create(&broadcast).await?;
+
+        let mut created = 0;
+        for chunk in routes.chunks(FAN_OUT_BATCH_SIZE) {
+            let mut messages = Vec::with_capacity(chunk.len());
+            for (recipient, deliver_after) in chunk {
+                let mut message = Message::new(
+                    libs::next_id().await,
+                    broadcast.channel.clone(),
+                    recipient.clone(),
+                    broadcast.subject.clone(),
+                    broadcast.content.clone(),
+                );
+                message.category = broadcast.category;
+                message.deliver_after = *deliver_after;
+                message.broadcast_id = Some(broadcast.base.id.clone());
+                message.request_id = request_id.clone();
+                messages.push(message);
+            }
+
+            // 已写入的消息仍会由调度器投递, 广播按实际写入的数量统计进度
+            if let Err(e) = self.message_repo.create_many(&messages).await {
+                self.repo.mark_failed(&broadcast.base.id, created).await?;
+                return Err(e);
+            }
+            created += messages.len() as u64;
+        }
+
+        Ok(broadcast)
+    }
+
+    pub async fn get_broadcast(&self, id: &str) -> Result<BroadcastDetail> {
+        let broadcast = self
+            .repo
+            .find_by_id(id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Broadcast))?;
+        Ok(broadcast.into())
+    }
+
+    pub async fn get_broadcast_list(&self, query: BroadcastQuery) -> Result<Vec<BroadcastDetail>> {
+        let broadcasts = self.repo.query(&query).await?;
+        Ok(broadcasts.into_iter().map(BroadcastDetail::from).collect())
+    }
+
+    /// 解析广播目标, 返回去重后的启用中管理员
+    async fn resolve_recipients(&self, target: &BroadcastTarget) -> Result<Vec<Admin>> {
+        let admins: Vec<Admin> = match target {
+            BroadcastTarget::Role { role_name } => self.admin_repo.find_by_role(role_name).await?,
+            BroadcastTarget::Admins { ids } => self.admin_repo.find_by_ids(ids).await?,
+            BroadcastTarget::All => self.admin_repo.find_all().await?,
+        };
+
+        let mut seen = HashSet::new();
+        Ok(admins
+            .into_iter()
+            .filter(|admin| admin.is_active)
+            .filter(|admin| seen.insert(admin.base.id.clone()))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use entities::{Admin, BroadcastStatus, BroadcastTarget, MessageCategory, MessageChannel, Secret};
+
+    use super::*;
+    use crate::testing::{MemoryAdminRepository, MemoryBroadcastRepository, MemoryMessageRepository};
+
+    #[tokio::test]
+    async fn create_broadcast_marks_the_broadcast_failed_when_fan_out_fails() {
+        let admins = (0..FAN_OUT_BATCH_SIZE + 1)
+            .map(|i| Admin::new(i.to_string(), Secret::default(), i.to_string(), vec![]))
+            .collect();
+        let broadcasts = MemoryBroadcastRepository::default();
+        let messages = MemoryMessageRepository::default();
+        messages.fail_batch(1);
+        let service = BroadcastService::new(
+            broadcasts.clone(),
+            MemoryAdminRepository::new(admins),
+            messages.clone(),
+        );
+
+        let params = CreateBroadcastParams {
+            channel: MessageChannel::InternalMessage,
+            target: BroadcastTarget::All,
+            category: MessageCategory::Announcement,
+            subject: "subject".to_string(),
+            content: "content".to_string(),
+            created_by: "admin".to_string(),
+            request_id: None,
+        };
+        assert!(service.create_broadcast(params).await.is_err());
+
+        let created = messages.all();
+        assert_eq!(created.len(), FAN_OUT_BATCH_SIZE);
+
+        let broadcast = broadcasts.get(created[0].broadcast_id.as_ref().unwrap());
+        assert_eq!(broadcast.status, BroadcastStatus::Failed);
+        assert_eq!(broadcast.total, FAN_OUT_BATCH_SIZE as u64);
+    }
+}
@@ -1,6 +1,6 @@
 use super::dto::*;
 use super::repository::ICategoryRepository;
-use crate::errors::Result;
+use crate::errors::{Error, Resource, Result};
 use entities::product::Category;
 
 pub struct CategoryService<R>
@@ -22,7 +22,11 @@ where
         let id = libs::next_id().await;
         let (level, path) = match params.parent_id {
             Some(ref parent_id) => {
-                let parent = self.repo.find_by_id(parent_id).await?.ok_or("父分类不存在")?;
+                let parent = self
+                    .repo
+                    .find_by_id(parent_id)
+                    .await?
+                    .ok_or(Error::InvalidArgument("父分类不存在".to_string()))?;
                 (parent.level + 1, format!("{}/{}", parent.path, id))
             }
             None => (0, id.clone()),
@@ -34,7 +38,11 @@ where
     }
 
     pub async fn update_category(&self, params: UpdateCategoryParams) -> Result<()> {
-        let mut category = self.repo.find_by_id(&params.id).await?.ok_or("分类不存在")?;
+        let mut category = self
+            .repo
+            .find_by_id(&params.id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Category))?;
 
         params.apply(&mut category);
 
@@ -1,16 +1,74 @@
+use std::fmt;
+
+/// 业务数据的类型, 用于描述不存在的数据
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resource {
+    Admin,
+    Role,
+    ApiKey,
+    Message,
+    InternalMessage,
+    Broadcast,
+    Webhook,
+    Product,
+    Sku,
+    Category,
+    Brand,
+    Supplier,
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Resource::Admin => "管理员",
+            Resource::Role => "角色",
+            Resource::ApiKey => "API 密钥",
+            Resource::Message => "消息",
+            Resource::InternalMessage => "站内消息",
+            Resource::Broadcast => "广播",
+            Resource::Webhook => "端点",
+            Resource::Product => "商品",
+            Resource::Sku => "SKU",
+            Resource::Category => "分类",
+            Resource::Brand => "品牌",
+            Resource::Supplier => "供应商",
+        };
+
+        f.write_str(name)
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("系统内部错误: {0}")]
     Internal(String),
 
-    #[error("数据不存在")]
-    NotFound,
+    #[error("{0}不存在")]
+    NotFound(Resource),
+
+    /// 与已有数据冲突, 例如账号重复、角色正在使用中
+    #[error("{0}")]
+    Conflict(String),
+
+    /// 数据已被其他请求修改
+    #[error("数据已被修改, 请刷新后重试")]
+    OptimisticLock,
+
+    #[error("{0}")]
+    PermissionDenied(String),
+
+    /// 请求引用了无效的数据, 例如不存在的角色或权限
+    #[error("{0}")]
+    InvalidArgument(String),
 
     #[error(transparent)]
     Logic(#[from] entities::errors::Error),
 
     #[error("数据库错误：{0}")]
     RepositoryError(String),
+
+    #[error("请求参数错误: {0}")]
+    Validation(#[from] validator::ValidationErrors),
 }
 
 impl From<String> for Error {
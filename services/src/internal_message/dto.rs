@@ -1,5 +1,16 @@
-use entities::InternalMessageStatus;
-use serde::Serialize;
+use entities::{InternalMessage, InternalMessageStatus, MessageCategory, MessagePriority};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InternalMessageQuery {
+    pub recipient: String,
+    pub status: Option<String>,
+    pub category: Option<MessageCategory>,
+    /// 为 true 时只查询已归档的消息, 否则只查询未归档的消息
+    pub archived: bool,
+    pub page: i64,
+    pub page_size: i64,
+}
 
 #[derive(Debug, Serialize)]
 pub struct InternalMessageResponse {
@@ -7,5 +18,29 @@ pub struct InternalMessageResponse {
     pub subject: String,
     pub content: String,
     pub status: InternalMessageStatus,
+    pub category: MessageCategory,
+    pub priority: MessagePriority,
+    pub archived: bool,
     pub created_at: u64,
 }
+
+impl From<InternalMessage> for InternalMessageResponse {
+    fn from(m: InternalMessage) -> Self {
+        Self {
+            id: m.base.id,
+            subject: m.subject,
+            content: m.content,
+            status: m.status,
+            category: m.category,
+            priority: m.priority,
+            archived: m.archived_at > 0,
+            created_at: m.base.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct InternalMessagePage {
+    pub items: Vec<InternalMessageResponse>,
+    pub total: u64,
+}
@@ -2,17 +2,28 @@ use crate::errors::Result;
 use async_trait::async_trait;
 use entities::InternalMessage;
 
+use super::dto::InternalMessageQuery;
+
 #[async_trait]
 pub trait IInternalMessageRepository: Send + Sync {
     async fn create(&self, message: &InternalMessage) -> Result<()>;
 
-    async fn find_by_recipient_with_filter(
-        &self,
-        recipient: &str,
-        status: Option<String>,
-        skip: u64,
-        limit: i64,
-    ) -> Result<Vec<InternalMessage>>;
+    async fn find_by_query(&self, query: &InternalMessageQuery) -> Result<Vec<InternalMessage>>;
+
+    async fn count_by_query(&self, query: &InternalMessageQuery) -> Result<u64>;
+
+    /// 统计接收者未归档的未读消息数量
+    async fn count_unread(&self, recipient: &str) -> Result<u64>;
 
     async fn mark_as_read(&self, id: &str, recipient: &str) -> Result<bool>;
+
+    /// 将接收者的指定消息标记为已读, 返回实际更新的数量
+    async fn mark_many_as_read(&self, ids: &[String], recipient: &str) -> Result<u64>;
+
+    /// 将接收者的全部未读消息标记为已读, 返回实际更新的数量
+    async fn mark_all_as_read(&self, recipient: &str) -> Result<u64>;
+
+    async fn archive(&self, id: &str, recipient: &str) -> Result<bool>;
+
+    async fn delete(&self, id: &str, recipient: &str) -> Result<bool>;
 }
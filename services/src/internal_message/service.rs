@@ -1,6 +1,9 @@
+use super::dto::{InternalMessagePage, InternalMessageQuery, InternalMessageResponse};
 use super::repository::IInternalMessageRepository;
-use super::dto::InternalMessageResponse;
-use crate::errors::Result;
+use crate::errors::{Error, Resource, Result};
+
+/// 单次批量标记已读的最大消息数量
+const MAX_BULK_READ_SIZE: usize = 200;
 
 pub struct InternalMessageService<T: IInternalMessageRepository> {
     repo: T,
@@ -11,38 +14,63 @@ impl<T: IInternalMessageRepository> InternalMessageService<T> {
         Self { repo }
     }
 
-    pub async fn get_my_messages(
-        &self,
-        recipient: String,
-        page: Option<i64>,
-        page_size: Option<i64>,
-        status: Option<String>,
-    ) -> Result<Vec<InternalMessageResponse>> {
-        let page = page.unwrap_or(1);
-        let page_size = page_size.unwrap_or(20);
-        let skip = ((page - 1) * page_size) as u64;
-
-        let messages = self
-            .repo
-            .find_by_recipient_with_filter(&recipient, status, skip, page_size)
-            .await?;
-
-        Ok(messages
-            .into_iter()
-            .map(|m| InternalMessageResponse {
-                id: m.base.id,
-                subject: m.subject,
-                content: m.content,
-                status: m.status,
-                created_at: m.base.created_at,
-            })
-            .collect())
+    pub async fn get_my_messages(&self, query: InternalMessageQuery) -> Result<InternalMessagePage> {
+        let messages = self.repo.find_by_query(&query).await?;
+        let total = self.repo.count_by_query(&query).await?;
+
+        Ok(InternalMessagePage {
+            items: messages.into_iter().map(InternalMessageResponse::from).collect(),
+            total,
+        })
+    }
+
+    pub async fn get_unread_count(&self, recipient: String) -> Result<u64> {
+        self.repo.count_unread(&recipient).await
     }
 
     pub async fn mark_as_read(&self, id: String, recipient: String) -> Result<()> {
         let updated = self.repo.mark_as_read(&id, &recipient).await?;
         if !updated {
-            return Err("消息不存在或无权限".into());
+            return Err(Error::NotFound(Resource::InternalMessage));
+        }
+        Ok(())
+    }
+
+    /// 批量标记已读, 不属于接收者的消息会被忽略
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - 实际标记为已读的消息数量
+    pub async fn mark_many_as_read(&self, ids: Vec<String>, recipient: String) -> Result<u64> {
+        if ids.is_empty() {
+            return Err(Error::InvalidArgument("消息ID不能为空".to_string()));
+        }
+
+        if ids.len() > MAX_BULK_READ_SIZE {
+            return Err(Error::InvalidArgument(format!(
+                "单次最多标记{}条消息",
+                MAX_BULK_READ_SIZE
+            )));
+        }
+
+        self.repo.mark_many_as_read(&ids, &recipient).await
+    }
+
+    pub async fn mark_all_as_read(&self, recipient: String) -> Result<u64> {
+        self.repo.mark_all_as_read(&recipient).await
+    }
+
+    pub async fn archive(&self, id: String, recipient: String) -> Result<()> {
+        let updated = self.repo.archive(&id, &recipient).await?;
+        if !updated {
+            return Err(Error::NotFound(Resource::InternalMessage));
+        }
+        Ok(())
+    }
+
+    pub async fn delete(&self, id: String, recipient: String) -> Result<()> {
+        let deleted = self.repo.delete(&id, &recipient).await?;
+        if !deleted {
+            return Err(Error::NotFound(Resource::InternalMessage));
         }
         Ok(())
     }
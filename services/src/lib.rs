@@ -1,5 +1,7 @@
 pub mod admin;
+pub mod api_key;
 pub mod brand;
+pub mod broadcast;
 pub mod category;
 pub mod errors;
 pub mod internal_message;
@@ -8,9 +10,14 @@ pub mod operation_log;
 pub mod product;
 pub mod role;
 pub mod supplier;
+#[cfg(test)]
+mod testing;
+pub mod webhook;
 
 pub use admin::AdminService;
+pub use api_key::ApiKeyService;
 pub use brand::BrandService;
+pub use broadcast::BroadcastService;
 pub use category::CategoryService;
 pub use internal_message::InternalMessageService;
 pub use notification::NotificationService;
@@ -18,3 +25,4 @@ pub use operation_log::OperationLogService;
 pub use product::ProductService;
 pub use role::RoleService;
 pub use supplier::SupplierService;
+pub use webhook::WebhookService;
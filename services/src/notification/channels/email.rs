@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use entities::Message;
 
 use super::MessageSender;
 use crate::errors::Result;
@@ -13,7 +14,7 @@ impl EmailSender {
 
 #[async_trait]
 impl MessageSender for EmailSender {
-    async fn send(&self, recipient: &str, subject: &str, content: &str) -> Result<()> {
+    async fn send(&self, _message: &mut Message) -> Result<()> {
         Ok(())
     }
 }
@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use entities::InternalMessage;
+use entities::{InternalMessage, Message};
 
 use super::MessageSender;
 use crate::{errors::Result, internal_message::IInternalMessageRepository};
@@ -16,15 +16,16 @@ impl<T: IInternalMessageRepository> InternalMessageSender<T> {
 
 #[async_trait]
 impl<T: IInternalMessageRepository> MessageSender for InternalMessageSender<T> {
-    async fn send(&self, recipient: &str, subject: &str, content: &str) -> Result<()> {
-        let message = InternalMessage::new(
+    async fn send(&self, message: &mut Message) -> Result<()> {
+        let internal_message = InternalMessage::new(
             libs::next_id().await,
-            recipient.to_string(),
-            subject.to_string(),
-            content.to_string(),
+            message.recipient.clone(),
+            message.subject.clone(),
+            message.content.clone(),
+            message.category,
         );
 
-        self.repo.create(&message).await?;
+        self.repo.create(&internal_message).await?;
 
         Ok(())
     }
@@ -1,16 +1,23 @@
 mod email;
 mod internal;
 mod sms;
+mod webhook;
 mod websocket;
 
 pub use email::EmailSender;
 pub use internal::InternalMessageSender;
 pub use sms::SMSSender;
+pub use webhook::{
+    sign_webhook, WebhookSender, WEBHOOK_ID_HEADER, WEBHOOK_SIGNATURE_HEADER, WEBHOOK_TIMESTAMP_HEADER,
+};
 pub use websocket::WebSocketSender;
 
+use entities::Message;
+
 use super::super::errors::Result;
 
 #[async_trait::async_trait]
 pub trait MessageSender: Send + Sync {
-    async fn send(&self, recipient: &str, subject: &str, content: &str) -> Result<()>;
+    /// 发送消息, 发送方可以在消息上记录接收方的响应
+    async fn send(&self, message: &mut Message) -> Result<()>;
 }
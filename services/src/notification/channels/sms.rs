@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use entities::Message;
 
 use super::MessageSender;
 
@@ -14,7 +15,7 @@ impl SMSSender {
 
 #[async_trait]
 impl MessageSender for SMSSender {
-    async fn send(&self, recipient: &str, subject: &str, content: &str) -> Result<()> {
+    async fn send(&self, _message: &mut Message) -> Result<()> {
         // TODO: 实现实际的短信发送逻辑
         Ok(())
     }
@@ -0,0 +1,278 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Local;
+use entities::{Message, MessageCategory};
+use hmac::{Hmac, Mac};
+use reqwest::header::CONTENT_TYPE;
+use serde::Serialize;
+use sha2::Sha256;
+
+use super::MessageSender;
+use crate::{
+    errors::{Error, Resource, Result},
+    webhook::IWebhookEndpointRepository,
+};
+
+/// 请求头: 消息ID, 接收方可用于去重
+pub const WEBHOOK_ID_HEADER: &str = "X-Webhook-Id";
+/// 请求头: 签名时间戳(秒)
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+/// 请求头: `sha256=` 加上对 `{timestamp}.{body}` 的 HMAC-SHA256 签名
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// 单次投递的超时时间
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    id: &'a str,
+    category: MessageCategory,
+    subject: &'a str,
+    content: &'a str,
+    created_at: u64,
+}
+
+/// 计算 webhook 签名
+///
+/// 签名内容为 `{timestamp}.{body}`, 接收方应使用相同的方式计算并比较签名,
+/// 同时校验时间戳以防止重放
+pub fn sign_webhook(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+pub struct WebhookSender<W: IWebhookEndpointRepository> {
+    repo: W,
+    client: reqwest::Client,
+}
+
+impl<W: IWebhookEndpointRepository> WebhookSender<W> {
+    pub fn new(repo: W) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+
+        Self { repo, client }
+    }
+}
+
+#[async_trait]
+impl<W: IWebhookEndpointRepository> MessageSender for WebhookSender<W> {
+    async fn send(&self, message: &mut Message) -> Result<()> {
+        message.response_status = None;
+        message.response_body = None;
+
+        let endpoint = self
+            .repo
+            .find_by_id(&message.recipient)
+            .await?
+            .ok_or(Error::NotFound(Resource::Webhook))?;
+
+        if !endpoint.is_active {
+            return Err("webhook 端点已停用".into());
+        }
+
+        let body = serde_json::to_string(&WebhookPayload {
+            id: &message.base.id,
+            category: message.category,
+            subject: &message.subject,
+            content: &message.content,
+            created_at: message.base.created_at,
+        })
+        .map_err(|e| e.to_string())?;
+
+        let timestamp = Local::now().timestamp();
+        let response = self
+            .client
+            .post(&endpoint.url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(WEBHOOK_ID_HEADER, &message.base.id)
+            .header(WEBHOOK_TIMESTAMP_HEADER, timestamp.to_string())
+            .header(
+                WEBHOOK_SIGNATURE_HEADER,
+                sign_webhook(&endpoint.secret, timestamp, &body),
+            )
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| format!("webhook 请求失败: {}", e))?;
+
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        message.record_response(status.as_u16(), &text);
+
+        if !status.is_success() {
+            return Err(format!("webhook 返回状态码 {}", status.as_u16()).into());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use entities::{MessageChannel, WebhookEndpoint};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    const SECRET: &str = "test-secret";
+
+    struct StaticEndpoint(WebhookEndpoint);
+
+    #[async_trait]
+    impl IWebhookEndpointRepository for StaticEndpoint {
+        async fn create(&self, _endpoint: &WebhookEndpoint) -> Result<()> {
+            Ok(())
+        }
+
+        async fn update(&self, _endpoint: &WebhookEndpoint) -> Result<()> {
+            Ok(())
+        }
+
+        async fn find_by_id(&self, id: &str) -> Result<Option<WebhookEndpoint>> {
+            Ok((self.0.base.id == id).then(|| self.0.clone()))
+        }
+
+        async fn find_all(&self) -> Result<Vec<WebhookEndpoint>> {
+            Ok(vec![self.0.clone()])
+        }
+    }
+
+    struct CapturedRequest {
+        headers: HashMap<String, String>,
+        body: String,
+    }
+
+    /// 启动一个只处理一次请求的本地 HTTP 服务, 返回其地址和捕获的请求
+    async fn stand_in(status_line: &'static str) -> (String, tokio::task::JoinHandle<CapturedRequest>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = Vec::new();
+            let mut chunk = [0u8; 1024];
+
+            let (head_end, content_length) = loop {
+                let n = socket.read(&mut chunk).await.unwrap();
+                buf.extend_from_slice(&chunk[..n]);
+                let text = String::from_utf8_lossy(&buf);
+                if let Some(pos) = text.find("\r\n\r\n") {
+                    let length = text[..pos]
+                        .lines()
+                        .find_map(|l| {
+                            l.to_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().to_string())
+                        })
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .unwrap_or(0);
+                    break (pos + 4, length);
+                }
+            };
+
+            while buf.len() < head_end + content_length {
+                let n = socket.read(&mut chunk).await.unwrap();
+                buf.extend_from_slice(&chunk[..n]);
+            }
+
+            let text = String::from_utf8_lossy(&buf).to_string();
+            let headers = text[..head_end]
+                .lines()
+                .skip(1)
+                .filter_map(|l| l.split_once(':'))
+                .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_string()))
+                .collect();
+
+            let response = format!(
+                "{}\r\nContent-Length: 8\r\nConnection: close\r\n\r\nreceived",
+                status_line
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+
+            CapturedRequest {
+                headers,
+                body: text[head_end..].to_string(),
+            }
+        });
+
+        (url, handle)
+    }
+
+    fn sender(url: String) -> WebhookSender<StaticEndpoint> {
+        WebhookSender::new(StaticEndpoint(WebhookEndpoint::new(
+            "endpoint-1".to_string(),
+            "test".to_string(),
+            url,
+            SECRET.to_string(),
+            "admin".to_string(),
+        )))
+    }
+
+    fn message() -> Message {
+        Message::new(
+            "message-1".to_string(),
+            MessageChannel::Webhook,
+            "endpoint-1".to_string(),
+            "subject".to_string(),
+            "content".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_sign_webhook() {
+        let signature = sign_webhook(SECRET, 1_700_000_000, "{}");
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature, sign_webhook(SECRET, 1_700_000_000, "{}"));
+        assert_ne!(signature, sign_webhook(SECRET, 1_700_000_001, "{}"));
+        assert_ne!(signature, sign_webhook("other", 1_700_000_000, "{}"));
+    }
+
+    #[tokio::test]
+    async fn test_send_signed_payload() {
+        let (url, handle) = stand_in("HTTP/1.1 200 OK").await;
+        let mut message = message();
+
+        sender(url).send(&mut message).await.unwrap();
+        let request = handle.await.unwrap();
+
+        let timestamp: i64 = request.headers["x-webhook-timestamp"].parse().unwrap();
+        assert_eq!(request.headers["x-webhook-id"], "message-1");
+        assert_eq!(
+            request.headers["x-webhook-signature"],
+            sign_webhook(SECRET, timestamp, &request.body)
+        );
+        assert!(request.body.contains("\"subject\":\"subject\""));
+        assert_eq!(message.response_status, Some(200));
+        assert_eq!(message.response_body.as_deref(), Some("received"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_status_is_recorded() {
+        let (url, handle) = stand_in("HTTP/1.1 500 Internal Server Error").await;
+        let mut message = message();
+
+        assert!(sender(url).send(&mut message).await.is_err());
+        handle.await.unwrap();
+        assert_eq!(message.response_status, Some(500));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_endpoint() {
+        let mut message = message();
+        message.recipient = "missing".to_string();
+
+        assert!(sender("http://127.0.0.1:1".to_string())
+            .send(&mut message)
+            .await
+            .is_err());
+        assert_eq!(message.response_status, None);
+    }
+}
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use entities::Message;
 
 use super::MessageSender;
 use crate::errors::Result;
@@ -13,7 +14,7 @@ impl WebSocketSender {
 
 #[async_trait]
 impl MessageSender for WebSocketSender {
-    async fn send(&self, recipient: &str, subject: &str, content: &str) -> Result<()> {
+    async fn send(&self, _message: &mut Message) -> Result<()> {
         // TODO: 实现实际的WebSocket消息发送逻辑
         Ok(())
     }
@@ -1,12 +1,24 @@
-use entities::MessageChannel;
+use entities::{MessageCategory, MessageChannel};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SendMessageParams {
     pub channel: MessageChannel,
+    pub category: MessageCategory,
     pub recipient: String,
     pub subject: String,
     pub content: String,
+    pub request_id: Option<String>,
+}
+
+/// 按管理员的通知偏好发送消息的参数
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NotifyAdminParams {
+    pub admin_id: String,
+    pub category: MessageCategory,
+    pub subject: String,
+    pub content: String,
+    pub request_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -14,6 +26,20 @@ pub struct MessageQuery {
     pub channel: Option<MessageChannel>,
     pub recipient: Option<String>,
     pub status: Option<String>,
+    pub broadcast_id: Option<String>,
     pub page: i64,
     pub page_size: i64,
 }
+
+/// 消息租约参数
+///
+/// 消息在发送前需要先被某个实例领取, 租约到期前其他实例不能再领取同一条消息
+#[derive(Debug, Clone)]
+pub struct MessageLease {
+    /// 租约持有者(实例标识)
+    pub worker: String,
+    /// 领取时的时间戳(秒)
+    pub now: u64,
+    /// 租约到期时间戳(秒)
+    pub until: u64,
+}
@@ -3,6 +3,7 @@ mod repository;
 mod service;
 mod dto;
 
+pub use channels::{sign_webhook, WEBHOOK_ID_HEADER, WEBHOOK_SIGNATURE_HEADER, WEBHOOK_TIMESTAMP_HEADER};
 pub use repository::IMessageRepository;
 pub use service::NotificationService;
 pub use dto::*;
@@ -1,15 +1,36 @@
 use crate::errors::Result;
 use async_trait::async_trait;
-use entities::Message;
+use entities::{Message, MessageStatus};
 
-use super::MessageQuery;
+use super::{MessageLease, MessageQuery};
 
 #[async_trait]
 pub trait IMessageRepository: Send + Sync {
     async fn create(&self, message: &Message) -> Result<()>;
+    async fn create_many(&self, messages: &[Message]) -> Result<()>;
     async fn update(&self, message: &Message) -> Result<()>;
     async fn find_by_id(&self, id: &str) -> Result<Option<Message>>;
-    async fn find_failed_messages(&self) -> Result<Vec<Message>>;
-    async fn find_pending_messages(&self) -> Result<Vec<Message>>;
+
+    /// 原子地领取一条指定状态、未被租用或租约已过期的消息
+    ///
+    /// 指定 `broadcast_id` 时只领取属于该广播的消息
+    async fn claim_next(
+        &self,
+        status: MessageStatus,
+        broadcast_id: Option<&str>,
+        lease: &MessageLease,
+    ) -> Result<Option<Message>>;
+
+    /// 原子地领取指定ID的消息, 消息状态不匹配或正被其他实例租用时返回 None
+    async fn claim_by_id(
+        &self,
+        id: &str,
+        status: MessageStatus,
+        lease: &MessageLease,
+    ) -> Result<Option<Message>>;
+
+    /// 按领取时的版本号续租, 租约已被其他实例领取时返回 None
+    async fn renew_lease(&self, message: &Message, lease: &MessageLease) -> Result<Option<Message>>;
+
     async fn query(&self, query: MessageQuery) -> Result<Vec<Message>>;
 }
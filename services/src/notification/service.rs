@@ -1,75 +1,167 @@
-use crate::{errors::Result, internal_message::IInternalMessageRepository};
-use entities::{Message, MessageChannel, MessageStatus};
+use crate::{
+    admin::IAdminRepository,
+    broadcast::IBroadcastRepository,
+    errors::{Error, Resource, Result},
+    internal_message::IInternalMessageRepository,
+    webhook::IWebhookEndpointRepository,
+};
+use chrono::Local;
+use entities::{Broadcast, BroadcastStatus, Message, MessageChannel, MessageStatus};
+use metrics::counter;
 
 use super::{
-    channels::{EmailSender, InternalMessageSender, MessageSender, SMSSender, WebSocketSender},
-    dto::{MessageQuery, SendMessageParams},
+    channels::{
+        EmailSender, InternalMessageSender, MessageSender, SMSSender, WebSocketSender, WebhookSender,
+    },
+    dto::{MessageLease, MessageQuery, NotifyAdminParams, SendMessageParams},
     IMessageRepository,
 };
 
-pub struct NotificationService<T, TM>
+/// 消息租约时长(秒), 超过该时长仍未完成发送的消息可以被其他实例重新领取
+///
+/// 批量领取的消息在发送前逐条续租, 租约只需覆盖单条消息的发送(渠道超时不超过10秒)
+const MESSAGE_LEASE_SECONDS: u64 = 60;
+
+/// 广播投递时每批领取的消息数量
+const BROADCAST_BATCH_SIZE: usize = 100;
+
+pub struct NotificationService<T, TM, B, A, W>
 where
     T: IMessageRepository,
     TM: IInternalMessageRepository,
+    B: IBroadcastRepository,
+    A: IAdminRepository,
+    W: IWebhookEndpointRepository,
 {
     repo: T,
+    broadcast_repo: B,
+    admin_repo: A,
+    worker: String,
     email_sender: EmailSender,
     sms_sender: SMSSender,
     ws_sender: WebSocketSender,
     internal_sender: InternalMessageSender<TM>,
+    webhook_sender: WebhookSender<W>,
 }
 
-impl<T: IMessageRepository, TM: IInternalMessageRepository> NotificationService<T, TM> {
-    pub fn new(repo: T, internal_msg_repo: TM) -> Self {
+impl<T, TM, B, A, W> NotificationService<T, TM, B, A, W>
+where
+    T: IMessageRepository,
+    TM: IInternalMessageRepository,
+    B: IBroadcastRepository,
+    A: IAdminRepository,
+    W: IWebhookEndpointRepository,
+{
+    pub fn new(repo: T, internal_msg_repo: TM, broadcast_repo: B, admin_repo: A, webhook_repo: W) -> Self {
         Self {
             repo,
+            broadcast_repo,
+            admin_repo,
+            worker: libs::instance_id().to_string(),
             email_sender: EmailSender::new(),
             sms_sender: SMSSender::new(),
             ws_sender: WebSocketSender::new(),
             internal_sender: InternalMessageSender::new(internal_msg_repo),
+            webhook_sender: WebhookSender::new(webhook_repo),
+        }
+    }
+
+    /// 为当前实例生成一个新的消息租约
+    fn new_lease(&self) -> MessageLease {
+        let now = Local::now().timestamp() as u64;
+        MessageLease {
+            worker: self.worker.clone(),
+            now,
+            until: now + MESSAGE_LEASE_SECONDS,
         }
     }
 
     pub async fn new_message(&self, params: SendMessageParams) -> Result<()> {
         let id = libs::next_id().await;
-        let message = Message::new(
+        let mut message = Message::new(
             id,
             params.channel,
             params.recipient,
             params.subject,
             params.content,
         );
+        message.category = params.category;
+        message.request_id = params.request_id;
+
+        self.create_and_send(message).await
+    }
+
+    /// 按管理员的通知偏好发送消息
+    ///
+    /// 每个开启的渠道各生成一条消息, 缺少联系方式的渠道会被跳过,
+    /// 处于免打扰时段的消息会延后到时段结束后由调度器发送
+    ///
+    /// # Arguments
+    /// * `params` - 消息参数
+    pub async fn notify_admin(&self, params: NotifyAdminParams) -> Result<()> {
+        let admin = self
+            .admin_repo
+            .find_by_id(&params.admin_id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Admin))?;
+
+        let preferences = &admin.notification_preferences;
+        let now = Local::now().timestamp() as u64;
+
+        for channel in preferences.enabled_channels(params.category) {
+            let Some((recipient, deliver_after)) =
+                preferences.route(params.category, &channel, &admin.base.id, now)
+            else {
+                continue;
+            };
+
+            let mut message = Message::new(
+                libs::next_id().await,
+                channel,
+                recipient,
+                params.subject.clone(),
+                params.content.clone(),
+            );
+            message.category = params.category;
+            message.deliver_after = deliver_after;
+            message.request_id = params.request_id.clone();
+
+            if message.is_deferred(now) {
+                self.repo.create(&message).await?;
+                continue;
+            }
+
+            self.create_and_send(message).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn create_and_send(&self, mut message: Message) -> Result<()> {
+        // 创建时即持有租约, 避免调度器在发送完成前领取同一条消息
+        let lease = self.new_lease();
+        message.lease(&lease.worker, lease.until);
 
         // 保存消息记录
         self.repo.create(&message).await?;
-        self.send_message(message).await?;
-        Ok(())
+        self.send_message(message).await
     }
 
+    /// 发送一条已被当前实例领取的消息, 发送完成后释放租约
     pub async fn send_message(&self, mut message: Message) -> Result<()> {
+        let previous_status = message.status.clone();
         let result = match message.channel {
-            MessageChannel::Email => {
-                self.email_sender
-                    .send(&message.recipient, &message.subject, &message.content)
-                    .await
-            }
-            MessageChannel::SMS => {
-                self.sms_sender
-                    .send(&message.recipient, &message.subject, &message.content)
-                    .await
-            }
-            MessageChannel::WebSocket => {
-                self.ws_sender
-                    .send(&message.recipient, &message.subject, &message.content)
-                    .await
-            }
-            MessageChannel::InternalMessage => {
-                self.internal_sender
-                    .send(&message.recipient, &message.subject, &message.content)
-                    .await
-            }
+            MessageChannel::Email => self.email_sender.send(&mut message).await,
+            MessageChannel::SMS => self.sms_sender.send(&mut message).await,
+            MessageChannel::WebSocket => self.ws_sender.send(&mut message).await,
+            MessageChannel::InternalMessage => self.internal_sender.send(&mut message).await,
+            MessageChannel::Webhook => self.webhook_sender.send(&mut message).await,
         };
 
+        let outcome = if result.is_ok() { "sent" } else { "failed" };
+        counter!("messages_sent_total", "channel" => message.channel.to_string(), "outcome" => outcome)
+            .increment(1);
+
         // 更新发送状态
         message.status = match result {
             Ok(_) => MessageStatus::Sent,
@@ -79,22 +171,142 @@ impl<T: IMessageRepository, TM: IInternalMessageRepository> NotificationService<
             }
         };
 
+        message.release();
         self.repo.update(&message).await?;
+
+        if let Some(broadcast_id) = &message.broadcast_id {
+            self.record_broadcast_delivery(broadcast_id, &previous_status, &message.status)
+                .await?;
+        }
+
         Ok(())
     }
 
+    /// 更新广播的投递计数, 所有消息都尝试过后将广播标记为已完成
+    async fn record_broadcast_delivery(
+        &self,
+        broadcast_id: &str,
+        previous: &MessageStatus,
+        current: &MessageStatus,
+    ) -> Result<()> {
+        let (sent, failed) = Broadcast::delivery_delta(previous, current);
+        if sent == 0 && failed == 0 {
+            return Ok(());
+        }
+
+        let broadcast = self
+            .broadcast_repo
+            .record_delivery(broadcast_id, sent, failed)
+            .await?;
+        if let Some(broadcast) = broadcast {
+            if broadcast.is_finished() && broadcast.status == BroadcastStatus::Sending {
+                self.broadcast_repo.mark_completed(broadcast_id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 投递广播下所有待发送的消息
+    ///
+    /// 消息按批次领取, 未能在此处完成的消息会由调度器的待发送任务继续投递。
+    /// 单条消息出错时继续投递其余消息, 全部尝试后返回最后一个错误。
+    ///
+    /// # Arguments
+    /// * `broadcast_id` - 广播ID
+    pub async fn deliver_broadcast(&self, broadcast_id: &str) -> Result<()> {
+        let mut result = Ok(());
+        loop {
+            let messages = self
+                .claim_messages(MessageStatus::Pending, Some(broadcast_id), BROADCAST_BATCH_SIZE)
+                .await?;
+
+            if messages.is_empty() {
+                return result;
+            }
+
+            for message in messages {
+                if let Err(e) = self.send_claimed(message).await {
+                    result = Err(e);
+                }
+            }
+        }
+    }
+
     pub async fn get_message_list(&self, query: MessageQuery) -> Result<Vec<Message>> {
-        Ok(self.repo.query(query).await?)
+        self.repo.query(query).await
     }
 
     pub async fn retry_by_id(&self, id: &str) -> Result<()> {
-        let message = self.repo.find_by_id(id).await?.ok_or("消息不存在")?;
+        let message = self
+            .repo
+            .find_by_id(id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Message))?;
+        if message.status != MessageStatus::Failed {
+            return Err(Error::Conflict("只能重试失败的消息".to_string()));
+        }
+
+        if !message.can_retry() {
+            return Err(Error::Conflict("重试次数超过最大限制".to_string()));
+        }
+
+        let message = self
+            .repo
+            .claim_by_id(id, MessageStatus::Failed, &self.new_lease())
+            .await?
+            .ok_or(Error::Conflict("消息正在被处理, 请稍后再试".to_string()))?;
+
         self.retry_message(message).await
     }
 
+    /// 续租后发送一条批量领取的消息
+    ///
+    /// 批量领取的消息依次发送, 排在后面的消息在轮到它时可能已经超过租约时长并被其他实例领取。
+    /// 发送前按领取时的版本号续租, 续租失败时跳过该消息, 避免重复发送。
+    /// 失败的消息按重试处理, 待发送的消息直接发送。
+    /// 出错时将错误记录在消息上并保留租约, 租约到期后由调度器重新投递。
+    ///
+    /// # Returns
+    /// * `Ok(true)` - 已发送(发送结果记录在消息上)
+    /// * `Ok(false)` - 租约已被其他实例领取, 未发送
+    pub async fn send_claimed(&self, message: Message) -> Result<bool> {
+        let Some(message) = self.repo.renew_lease(&message, &self.new_lease()).await? else {
+            return Ok(false);
+        };
+
+        let id = message.base.id.clone();
+        let result = match message.status {
+            MessageStatus::Failed => self.retry_message(message).await,
+            _ => self.send_message(message).await,
+        };
+
+        if let Err(e) = result {
+            // 记录失败时仍返回发送时的错误
+            let _ = self.record_error(&id, &e).await;
+            return Err(e);
+        }
+
+        Ok(true)
+    }
+
+    /// 在仍由当前实例持有租约的消息上记录错误
+    async fn record_error(&self, id: &str, error: &Error) -> Result<()> {
+        let Some(mut message) = self.repo.find_by_id(id).await? else {
+            return Ok(());
+        };
+        if message.locked_by.as_deref() != Some(self.worker.as_str()) {
+            return Ok(());
+        }
+
+        message.error = Some(error.to_string());
+        self.repo.update(&message).await
+    }
+
+    /// 重试一条已被当前实例领取的失败消息
     pub async fn retry_message(&self, mut message: Message) -> Result<()> {
         if message.status != MessageStatus::Failed {
-            return Err("只能重试失败的消息".into());
+            return Err(Error::Conflict("只能重试失败的消息".to_string()));
         }
 
         message.add_retry_times()?;
@@ -102,11 +314,165 @@ impl<T: IMessageRepository, TM: IInternalMessageRepository> NotificationService<
         self.send_message(message).await
     }
 
-    pub async fn get_failed_messages(&self) -> Result<Vec<Message>> {
-        Ok(self.repo.find_failed_messages().await?)
+    /// 领取一批发送失败且仍可重试的消息
+    ///
+    /// # Arguments
+    /// * `limit` - 最多领取的消息数量
+    pub async fn claim_failed_messages(&self, limit: usize) -> Result<Vec<Message>> {
+        self.claim_messages(MessageStatus::Failed, None, limit).await
     }
 
-    pub async fn get_pending_messages(&self) -> Result<Vec<Message>> {
-        Ok(self.repo.find_pending_messages().await?)
+    /// 领取一批待发送的消息
+    ///
+    /// # Arguments
+    /// * `limit` - 最多领取的消息数量
+    pub async fn claim_pending_messages(&self, limit: usize) -> Result<Vec<Message>> {
+        self.claim_messages(MessageStatus::Pending, None, limit).await
+    }
+
+    /// 逐条原子领取指定状态的消息, 领取到的消息在租约期内不会被其他实例处理
+    async fn claim_messages(
+        &self,
+        status: MessageStatus,
+        broadcast_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Message>> {
+        let lease = self.new_lease();
+        let mut messages = Vec::new();
+
+        while messages.len() < limit {
+            match self.repo.claim_next(status.clone(), broadcast_id, &lease).await? {
+                Some(message) => messages.push(message),
+                None => break,
+            }
+        }
+
+        Ok(messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use entities::{
+        Admin, Broadcast, BroadcastTarget, Message, MessageCategory, MessageChannel, MessageStatus, Secret,
+    };
+
+    use crate::{
+        broadcast::IBroadcastRepository,
+        notification::{IMessageRepository, NotifyAdminParams},
+        testing,
+    };
+
+    fn internal_message(id: &str) -> Message {
+        Message::new(
+            id.to_string(),
+            MessageChannel::InternalMessage,
+            "admin".to_string(),
+            "subject".to_string(),
+            "content".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn send_claimed_skips_messages_taken_over_after_the_lease_expired() {
+        let (service, repo, _) = testing::notification_service(vec![]);
+        repo.create(&internal_message("1")).await.unwrap();
+        repo.create(&internal_message("2")).await.unwrap();
+
+        let claimed = service.claim_pending_messages(10).await.unwrap();
+        assert_eq!(claimed.len(), 2);
+
+        // 第二条消息的租约在第一条发送期间过期, 被其他实例重新领取
+        repo.modify("2", |m| {
+            m.lease("other", m.lease_until + 60);
+            m.base.version += 1;
+        });
+
+        let mut claimed = claimed.into_iter();
+        assert!(service.send_claimed(claimed.next().unwrap()).await.unwrap());
+        assert!(!service.send_claimed(claimed.next().unwrap()).await.unwrap());
+
+        let sent = repo.get("1");
+        assert_eq!(sent.status, MessageStatus::Sent);
+        assert_eq!(sent.locked_by, None);
+
+        let skipped = repo.get("2");
+        assert_eq!(skipped.status, MessageStatus::Pending);
+        assert_eq!(skipped.locked_by.as_deref(), Some("other"));
+    }
+
+    #[tokio::test]
+    async fn send_claimed_renews_the_lease_before_sending() {
+        let (service, repo, _) = testing::notification_service(vec![]);
+        repo.create(&internal_message("1")).await.unwrap();
+
+        let claimed = service.claim_pending_messages(10).await.unwrap().pop().unwrap();
+        // 领取后租约已过期, 但没有其他实例领取
+        repo.modify("1", |m| m.lease_until = 0);
+
+        assert!(service.send_claimed(claimed).await.unwrap());
+        assert_eq!(repo.get("1").status, MessageStatus::Sent);
+    }
+
+    #[tokio::test]
+    async fn deliver_broadcast_continues_after_a_failed_message() {
+        let (service, repo, broadcasts) = testing::notification_service(vec![]);
+        let broadcast = Broadcast::new(
+            "b".to_string(),
+            MessageChannel::InternalMessage,
+            BroadcastTarget::All,
+            MessageCategory::Announcement,
+            "subject".to_string(),
+            "content".to_string(),
+            2,
+            0,
+            "admin".to_string(),
+        );
+        broadcasts.create(&broadcast).await.unwrap();
+        for id in ["1", "2"] {
+            let mut message = internal_message(id);
+            message.broadcast_id = Some("b".to_string());
+            repo.create(&message).await.unwrap();
+        }
+        repo.fail_next_update("1");
+
+        assert!(service.deliver_broadcast("b").await.is_err());
+
+        // 出错的消息记录了错误并保留租约, 等待调度器重新投递
+        let failed = repo.get("1");
+        assert_eq!(failed.status, MessageStatus::Pending);
+        assert!(failed.error.is_some());
+        assert!(failed.locked_by.is_some());
+
+        assert_eq!(repo.get("2").status, MessageStatus::Sent);
+        assert_eq!(broadcasts.get("b").sent, 1);
+    }
+
+    #[tokio::test]
+    async fn notify_admin_routes_by_preferences() {
+        let mut admin = Admin::new("1".to_string(), Secret::default(), "admin".to_string(), vec![]);
+        // 未设置邮箱, 邮件渠道被跳过
+        admin.notification_preferences.channels.insert(
+            MessageCategory::Announcement,
+            vec![MessageChannel::InternalMessage, MessageChannel::Email],
+        );
+        let (service, repo, _) = testing::notification_service(vec![admin]);
+
+        service
+            .notify_admin(NotifyAdminParams {
+                admin_id: "1".to_string(),
+                category: MessageCategory::Announcement,
+                subject: "subject".to_string(),
+                content: "content".to_string(),
+                request_id: None,
+            })
+            .await
+            .unwrap();
+
+        let messages = repo.all();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].channel, MessageChannel::InternalMessage);
+        assert_eq!(messages[0].recipient, "1");
+        assert_eq!(messages[0].status, MessageStatus::Sent);
     }
 }
@@ -12,11 +12,12 @@ pub struct CreateLogParams {
     pub request_method: String,
     pub request_body: Option<String>,
     pub ip_address: String,
+    pub request_id: String,
 }
 
 impl CreateLogParams {
     pub async fn to_entity(&self) -> OperationLog {
-        OperationLog::new(
+        let mut log = OperationLog::new(
             libs::next_id().await,
             &self.operator,
             &self.module,
@@ -27,6 +28,8 @@ impl CreateLogParams {
             &self.request_method,
             self.request_body.as_deref(),
             &self.ip_address,
-        )
+        );
+        log.request_id = self.request_id.clone();
+        log
     }
 }
@@ -1,7 +1,4 @@
-use entities::product::{
-    Brand, BrandStatus, Category, CategoryStatus, Product, ProductStatus, SkuSpec, SkuStatus, Supplier,
-    SupplierStatus, SKU,
-};
+use entities::product::{Product, ProductStatus, SkuSpec, SkuStatus, SKU};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
@@ -11,12 +8,19 @@ pub struct CreateProductParams {
     #[validate(length(min = 1, max = 100))]
     pub name: String,
     pub description: String,
+    #[validate(length(min = 1))]
     pub brand_id: String,
+    #[validate(length(min = 1))]
     pub category_id: String,
+    #[validate(length(min = 1))]
     pub supplier_id: String,
+    #[validate(length(min = 1))]
     pub main_image: String,
     pub images: Vec<String>,
     pub is_featured: bool,
+    /// 创建人, 由接口层设置
+    #[serde(skip)]
+    pub created_by: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -82,6 +86,7 @@ pub struct ProductDetail {
     pub status: ProductStatus,
     pub sort_order: i32,
     pub is_featured: bool,
+    pub created_by: String,
     pub created_at: u64,
     pub skus: Vec<SkuDetail>,
 }
@@ -100,6 +105,7 @@ impl From<Product> for ProductDetail {
             status: product.status,
             sort_order: product.sort_order,
             is_featured: product.is_featured,
+            created_by: product.created_by,
             created_at: product.base.created_at,
             skus: product.skus.into_iter().map(SkuDetail::from).collect(),
         }
@@ -113,8 +119,11 @@ pub struct CreateSkuParams {
     #[validate(length(min = 1))]
     pub sku_code: String,
     pub name: String,
+    #[validate(range(min = 0.0))]
     pub price: f64,
+    #[validate(range(min = 0.0))]
     pub original_price: Option<f64>,
+    #[validate(range(min = 0))]
     pub stock: i32,
     pub specs: Vec<SkuSpec>,
     pub image: Option<String>,
@@ -122,6 +131,7 @@ pub struct CreateSkuParams {
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateSkuParams {
+    pub product_id: String,
     pub id: String,
     pub name: Option<String>,
     pub price: Option<f64>,
@@ -200,13 +210,21 @@ pub struct CreateProductWithSkusParams {
     #[validate(length(min = 1, max = 100))]
     pub name: String,
     pub description: String,
+    #[validate(length(min = 1))]
     pub brand_id: String,
+    #[validate(length(min = 1))]
     pub category_id: String,
+    #[validate(length(min = 1))]
     pub supplier_id: String,
+    #[validate(length(min = 1))]
     pub main_image: String,
     pub images: Vec<String>,
     pub is_featured: bool,
+    #[validate(nested)]
     pub skus: Vec<CreateSkuParams>,
+    /// 创建人, 由接口层设置
+    #[serde(skip)]
+    pub created_by: String,
 }
 
 #[derive(Debug, Deserialize)]
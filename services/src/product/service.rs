@@ -4,13 +4,27 @@
 /// - 商品的创建、查询
 /// - SKU 的创建、更新、删除
 /// - 商品和 SKU 的关联管理
+/// - 按角色的数据范围限定可以访问的商品
 use super::dto::*;
 use super::repository::IProductRepository;
 use crate::brand::IBrandRepository;
 use crate::category::ICategoryRepository;
-use crate::errors::Result;
+use crate::errors::{Error, Resource, Result};
+use crate::role::{DataFilter, OWNER_FIELD};
 use crate::supplier::ISupplierRepository;
 use entities::product::*;
+use validator::Validate;
+
+/// 读取商品用于数据范围判断的字段
+pub(crate) fn scope_value<'a>(product: &'a Product, field: &str) -> Option<&'a str> {
+    match field {
+        "supplier_id" => Some(&product.supplier_id),
+        "category_id" => Some(&product.category_id),
+        "brand_id" => Some(&product.brand_id),
+        OWNER_FIELD => Some(&product.created_by),
+        _ => None,
+    }
+}
 
 /// 商品服务结构体
 pub struct ProductService<R, B, C, SP>
@@ -55,15 +69,40 @@ where
     /// * `Err` - 验证失败，返回具体错误信息
     async fn validate_relations(&self, brand_id: &str, category_id: &str, supplier_id: &str) -> Result<()> {
         if self.brand_repo.find_by_id(brand_id).await?.is_none() {
-            return Err("品牌不存在".into());
+            return Err(Error::InvalidArgument("品牌不存在".to_string()));
         }
 
         if self.category_repo.find_by_id(category_id).await?.is_none() {
-            return Err("分类不存在".into());
+            return Err(Error::InvalidArgument("分类不存在".to_string()));
         }
 
         if self.supplier_repo.find_by_id(supplier_id).await?.is_none() {
-            return Err("供应商不存在".into());
+            return Err(Error::InvalidArgument("供应商不存在".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// 查找数据范围内的商品, 范围之外的商品视为不存在
+    async fn find_in_scope(&self, id: &str, filter: &DataFilter) -> Result<Product> {
+        let product = self
+            .repo
+            .find_by_id(id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Product))?;
+        if !filter.allows(|field| scope_value(&product, field)) {
+            return Err(Error::NotFound(Resource::Product));
+        }
+
+        Ok(product)
+    }
+
+    /// 检查商品在数据范围内, 防止创建或修改出范围之外的商品
+    fn check_scope(product: &Product, filter: &DataFilter) -> Result<()> {
+        if !filter.allows(|field| scope_value(product, field)) {
+            return Err(Error::PermissionDenied(
+                "商品超出了当前用户的数据范围".to_string(),
+            ));
         }
 
         Ok(())
@@ -73,12 +112,15 @@ where
     ///
     /// # Arguments
     /// * `params` - 创建商品的参数
-    pub async fn create_product(&self, params: CreateProductParams) -> Result<()>
+    /// * `filter` - 当前用户的数据范围
+    pub async fn create_product(&self, params: CreateProductParams, filter: &DataFilter) -> Result<()>
     where
         B: IBrandRepository,
         C: ICategoryRepository,
         SP: ISupplierRepository,
     {
+        params.validate()?;
+
         // 验证关联数据
         self.validate_relations(&params.brand_id, &params.category_id, &params.supplier_id)
             .await?;
@@ -93,8 +135,10 @@ where
             .main_image(params.main_image)
             .images(params.images)
             .is_featured(params.is_featured)
+            .created_by(params.created_by)
             .build()
             .map_err(|e| e.to_string())?;
+        Self::check_scope(&product, filter)?;
 
         self.repo.create(&product).await
     }
@@ -103,29 +147,45 @@ where
     ///
     /// # Arguments
     /// * `id` - 商品 ID
+    /// * `filter` - 当前用户的数据范围
     ///
     /// # Returns
     /// * `Ok(Some(ProductDetail))` - 商品存在时返回商品详情
-    /// * `Ok(None)` - 商品不存在
-    pub async fn get_product_detail(&self, id: &str) -> Result<Option<ProductDetail>> {
+    /// * `Ok(None)` - 商品不存在或不在数据范围内
+    pub async fn get_product_detail(&self, id: &str, filter: &DataFilter) -> Result<Option<ProductDetail>> {
         let product = match self.repo.find_by_id(id).await? {
-            Some(p) => p,
-            None => return Ok(None),
+            Some(p) if filter.allows(|field| scope_value(&p, field)) => p,
+            _ => return Ok(None),
         };
 
         Ok(Some(ProductDetail::from(product)))
     }
 
+    /// 搜索商品, 只返回数据范围内的商品
+    ///
+    /// # Arguments
+    /// * `params` - 搜索条件
+    /// * `filter` - 当前用户的数据范围
+    ///
+    /// # Returns
+    /// * `Ok((total, items))` - 商品总数和当前页的商品
+    pub async fn search_products(
+        &self,
+        params: &ProductSearchParams,
+        filter: &DataFilter,
+    ) -> Result<(u64, Vec<ProductDetail>)> {
+        self.repo.search(params, filter).await
+    }
+
     /// 创建 SKU
     ///
     /// # Arguments
     /// * `params` - 创建 SKU 的参数
-    pub async fn create_sku(&self, params: CreateSkuParams) -> Result<()> {
-        let mut product = self
-            .repo
-            .find_by_id(&params.product_id)
-            .await?
-            .ok_or("商品不存在")?;
+    /// * `filter` - 当前用户的数据范围
+    pub async fn create_sku(&self, params: CreateSkuParams, filter: &DataFilter) -> Result<()> {
+        params.validate()?;
+
+        let mut product = self.find_in_scope(&params.product_id, filter).await?;
 
         let sku = SKU::new(
             libs::next_id().await,
@@ -144,9 +204,12 @@ where
     ///
     /// # Arguments
     /// * `params` - 更新 SKU 的参数
-    pub async fn update_sku(&self, params: UpdateSkuParams) -> Result<()> {
-        let mut product = self.repo.find_by_id(&params.id).await?.ok_or("商品不存在")?;
-        let sku = product.get_sku_mut(&params.id).ok_or("SKU不存在")?;
+    /// * `filter` - 当前用户的数据范围
+    pub async fn update_sku(&self, params: UpdateSkuParams, filter: &DataFilter) -> Result<()> {
+        let mut product = self.find_in_scope(&params.product_id, filter).await?;
+        let sku = product
+            .get_sku_mut(&params.id)
+            .ok_or(Error::NotFound(Resource::Sku))?;
 
         params.apply(sku);
         self.repo.update(&product).await
@@ -155,9 +218,14 @@ where
     /// 删除 SKU
     ///
     /// # Arguments
+    /// * `product_id` - 商品 ID
     /// * `sku_id` - SKU ID
-    pub async fn delete_sku(&self, sku_id: &str) -> Result<()> {
-        let mut product = self.repo.find_by_id(&sku_id).await?.ok_or("商品不存在")?;
+    /// * `filter` - 当前用户的数据范围
+    pub async fn delete_sku(&self, product_id: &str, sku_id: &str, filter: &DataFilter) -> Result<()> {
+        let mut product = self.find_in_scope(product_id, filter).await?;
+        if product.get_sku(sku_id).is_none() {
+            return Err(Error::NotFound(Resource::Sku));
+        }
 
         product.remove_sku(sku_id);
         self.repo.update(&product).await
@@ -167,7 +235,14 @@ where
     ///
     /// # Arguments
     /// * `params` - 创建商品和 SKUs 的参数
-    pub async fn create_product_with_skus(&self, params: CreateProductWithSkusParams) -> Result<()> {
+    /// * `filter` - 当前用户的数据范围
+    pub async fn create_product_with_skus(
+        &self,
+        params: CreateProductWithSkusParams,
+        filter: &DataFilter,
+    ) -> Result<()> {
+        params.validate()?;
+
         // 验证关联数据
         self.validate_relations(&params.brand_id, &params.category_id, &params.supplier_id)
             .await?;
@@ -181,7 +256,8 @@ where
             .supplier_id(params.supplier_id)
             .main_image(params.main_image)
             .images(params.images)
-            .is_featured(params.is_featured);
+            .is_featured(params.is_featured)
+            .created_by(params.created_by);
 
         // 添加 SKUs
         for sku_params in params.skus {
@@ -197,6 +273,8 @@ where
         }
 
         let product = builder.build().map_err(|e| e.to_string())?;
+        Self::check_scope(&product, filter)?;
+
         self.repo.create(&product).await
     }
 
@@ -204,29 +282,32 @@ where
     ///
     /// # Arguments
     /// * `params` - 更新商品的参数
-    pub async fn update_product(&self, params: UpdateProductParams) -> Result<()> {
-        let mut product = self.repo.find_by_id(&params.id).await?.ok_or("商品不存在")?;
+    /// * `filter` - 当前用户的数据范围
+    pub async fn update_product(&self, params: UpdateProductParams, filter: &DataFilter) -> Result<()> {
+        let mut product = self.find_in_scope(&params.id, filter).await?;
 
         // 验证关联数据
         if let Some(brand_id) = &params.brand_id {
             if self.brand_repo.find_by_id(brand_id).await?.is_none() {
-                return Err("品牌不存在".into());
+                return Err(Error::InvalidArgument("品牌不存在".to_string()));
             }
         }
 
         if let Some(category_id) = &params.category_id {
             if self.category_repo.find_by_id(category_id).await?.is_none() {
-                return Err("分类不存在".into());
+                return Err(Error::InvalidArgument("分类不存在".to_string()));
             }
         }
 
         if let Some(supplier_id) = &params.supplier_id {
             if self.supplier_repo.find_by_id(supplier_id).await?.is_none() {
-                return Err("供应商不存在".into());
+                return Err(Error::InvalidArgument("供应商不存在".to_string()));
             }
         }
 
         params.apply(&mut product);
+        Self::check_scope(&product, filter)?;
+
         self.repo.update(&product).await
     }
 
@@ -234,14 +315,15 @@ where
     ///
     /// # Arguments
     /// * `product_ids` - 要上架的商品 ID 列表
+    /// * `filter` - 当前用户的数据范围
     ///
     /// # Returns
     /// * `Ok(())` - 操作成功
     /// * `Err` - 操作失败，返回具体错误信息
-    pub async fn batch_activate_products(&self, product_ids: &[String]) -> Result<()> {
+    pub async fn batch_activate_products(&self, product_ids: &[String], filter: &DataFilter) -> Result<()> {
         let mut products = Vec::new();
         for id in product_ids {
-            let mut product = self.repo.find_by_id(id).await?.ok_or("商品不存在")?;
+            let mut product = self.find_in_scope(id, filter).await?;
             product.activate()?;
             products.push(product);
         }
@@ -252,10 +334,11 @@ where
     ///
     /// # Arguments
     /// * `product_ids` - 要下架的商��� ID 列表
-    pub async fn batch_deactivate_products(&self, product_ids: &[String]) -> Result<()> {
+    /// * `filter` - 当前用户的数据范围
+    pub async fn batch_deactivate_products(&self, product_ids: &[String], filter: &DataFilter) -> Result<()> {
         let mut products = Vec::new();
         for id in product_ids {
-            let mut product = self.repo.find_by_id(id).await?.ok_or("商品不存在")?;
+            let mut product = self.find_in_scope(id, filter).await?;
             product.deactivate()?;
             products.push(product);
         }
@@ -267,17 +350,141 @@ where
     /// # Arguments
     /// * `product_ids` - 商品 ID 列表
     /// * `change_type` - 调价方式
+    /// * `filter` - 当前用户的数据范围
     pub async fn batch_change_products_price(
         &self,
         product_ids: &[String],
         change_type: PriceChangeType,
+        filter: &DataFilter,
     ) -> Result<()> {
         let mut products = Vec::new();
         for id in product_ids {
-            let mut product = self.repo.find_by_id(id).await?.ok_or("商品不存在")?;
+            let mut product = self.find_in_scope(id, filter).await?;
             product.change_price(&change_type)?;
             products.push(product);
         }
         self.repo.update_many(&products).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use entities::product::{Product, SKU};
+
+    use super::ProductService;
+    use crate::{
+        errors::{Error, Resource},
+        product::{IProductRepository, ProductSearchParams, UpdateProductParams, UpdateSkuParams},
+        role::{DataFilter, ScopeCondition},
+        testing::{EmptyProductRelations, MemoryProductRepository},
+    };
+
+    fn product(id: &str, supplier_id: &str) -> Product {
+        Product::builder()
+            .id(id.to_string())
+            .name(format!("商品{}", id))
+            .description(String::new())
+            .brand_id("b1".to_string())
+            .category_id("c1".to_string())
+            .supplier_id(supplier_id.to_string())
+            .main_image("main.png".to_string())
+            .add_sku(SKU::new(
+                format!("sku{}", id),
+                format!("code{}", id),
+                String::new(),
+                10.0,
+                1,
+                vec![],
+            ))
+            .created_by("u1".to_string())
+            .build()
+            .unwrap()
+    }
+
+    fn search() -> ProductSearchParams {
+        ProductSearchParams {
+            keyword: None,
+            category_id: None,
+            brand_id: None,
+            supplier_id: None,
+            status: None,
+            is_featured: None,
+            price_range: None,
+            sort_by: None,
+            page: None,
+            page_size: None,
+        }
+    }
+
+    fn rename(id: &str) -> UpdateProductParams {
+        UpdateProductParams {
+            id: id.to_string(),
+            name: Some("新名称".to_string()),
+            description: None,
+            brand_id: None,
+            category_id: None,
+            supplier_id: None,
+            main_image: None,
+            images: None,
+            status: None,
+            sort_order: None,
+            is_featured: None,
+        }
+    }
+
+    fn restock(product_id: &str, id: &str) -> UpdateSkuParams {
+        UpdateSkuParams {
+            product_id: product_id.to_string(),
+            id: id.to_string(),
+            name: None,
+            price: None,
+            original_price: None,
+            stock: Some(99),
+            specs: None,
+            image: None,
+            status: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn scoped_products_are_invisible_outside_the_scope() {
+        let repo = MemoryProductRepository::new(vec![product("1", "s1"), product("2", "s2")]);
+        let service = ProductService::new(
+            repo.clone(),
+            EmptyProductRelations,
+            EmptyProductRelations,
+            EmptyProductRelations,
+        );
+        let only_s1 = DataFilter::AnyOf(vec![ScopeCondition {
+            field: "supplier_id".to_string(),
+            values: vec!["s1".to_string()],
+        }]);
+
+        let (total, items) = service.search_products(&search(), &only_s1).await.unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(items[0].id, "1");
+        let (total, _) = service
+            .search_products(&search(), &DataFilter::AnyOf(vec![]))
+            .await
+            .unwrap();
+        assert_eq!(total, 0);
+
+        // 范围之外的商品及其 SKU 视为不存在
+        let result = service.update_product(rename("2"), &only_s1).await;
+        assert!(matches!(result, Err(Error::NotFound(Resource::Product))));
+        let result = service.update_sku(restock("2", "sku2"), &only_s1).await;
+        assert!(matches!(result, Err(Error::NotFound(Resource::Product))));
+        let result = service.delete_sku("2", "sku2", &only_s1).await;
+        assert!(matches!(result, Err(Error::NotFound(Resource::Product))));
+
+        service.update_product(rename("1"), &only_s1).await.unwrap();
+        service.update_sku(restock("1", "sku1"), &only_s1).await.unwrap();
+        let result = service.update_sku(restock("1", "sku2"), &only_s1).await;
+        assert!(matches!(result, Err(Error::NotFound(Resource::Sku))));
+
+        let stored = repo.find_by_id("1").await.unwrap().unwrap();
+        assert_eq!(stored.name, "新名称");
+        assert_eq!(stored.skus[0].stock, 99);
+        assert_eq!(repo.find_by_id("2").await.unwrap().unwrap().skus.len(), 1);
+    }
+}
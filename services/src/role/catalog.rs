@@ -0,0 +1,139 @@
+use entities::RouteItem;
+use rbac::RoutePattern;
+
+use crate::errors::{Error, Result};
+
+/// 权限目录, 由注册路由时记录的受权限控制的接口组成
+///
+/// 角色授予的权限必须对应目录中的接口, 通配的方法(`*`)或路径(`/*`)需要至少覆盖一个接口,
+/// 路径中最多只能有一个通配符
+#[derive(Debug, Clone, Default)]
+pub struct PermissionCatalog {
+    items: Vec<RouteItem>,
+}
+
+impl PermissionCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一个接口, 同一方法和路径只保留第一次记录
+    pub fn add(&mut self, module: &str, method: &str, path: &str, description: &str) {
+        let method = method.to_uppercase();
+        if self.find(&method, path).is_some() {
+            return;
+        }
+
+        self.items.push(RouteItem::new(
+            module.to_string(),
+            method,
+            path.to_string(),
+            description.to_string(),
+        ));
+    }
+
+    /// 目录中的全部接口, 按注册顺序排列
+    pub fn items(&self) -> &[RouteItem] {
+        &self.items
+    }
+
+    /// 检查权限是否都对应目录中的接口
+    ///
+    /// 路径包含多个通配符时, 权限检查的匹配需要大量回溯, 因此不允许
+    ///
+    /// # 返回值
+    ///
+    /// 返回去重后的权限, 精确匹配的权限使用目录中的模块和描述
+    pub fn resolve(&self, permissions: Vec<RouteItem>) -> Result<Vec<RouteItem>> {
+        let mut resolved: Vec<RouteItem> = vec![];
+
+        for mut permission in permissions {
+            permission.method = permission.method.to_uppercase();
+
+            let exists = resolved
+                .iter()
+                .any(|item| item.method == permission.method && item.path == permission.path);
+            if exists {
+                continue;
+            }
+
+            let pattern = RoutePattern::new(&permission.path);
+            if pattern.wildcards() > 1 {
+                return Err(Error::InvalidArgument(format!(
+                    "权限 {} {} 最多只能包含一个通配符",
+                    permission.method, permission.path
+                )));
+            }
+
+            if let Some(item) = self.find(&permission.method, &permission.path) {
+                resolved.push(item.clone());
+            } else if self.covers(&permission, &pattern) {
+                resolved.push(permission);
+            } else {
+                return Err(Error::InvalidArgument(format!(
+                    "权限 {} {} 不存在",
+                    permission.method, permission.path
+                )));
+            }
+        }
+
+        Ok(resolved)
+    }
+
+    fn find(&self, method: &str, path: &str) -> Option<&RouteItem> {
+        self.items
+            .iter()
+            .find(|item| item.method == method && item.path == path)
+    }
+
+    /// 通配的权限是否覆盖目录中的至少一个接口, 路径按权限检查的路由匹配规则比较
+    ///
+    /// 模式直接编译使用, 不进入权限检查的全局缓存
+    fn covers(&self, permission: &RouteItem, pattern: &RoutePattern) -> bool {
+        if permission.method != "*" && pattern.wildcards() == 0 {
+            return false;
+        }
+
+        self.items.iter().any(|item| {
+            let method_matches = permission.method == "*" || permission.method == item.method;
+            method_matches && pattern.matches(&item.path)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn permission(method: &str, path: &str) -> RouteItem {
+        RouteItem::new(String::new(), method.to_string(), path.to_string(), String::new())
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut catalog = PermissionCatalog::new();
+        catalog.add("admin", "GET", "/admins", "获取管理员列表");
+        catalog.add("admin", "PUT", "/admins/:id/role", "修改管理员角色");
+        catalog.add("role", "get", "/roles", "获取角色列表");
+
+        let resolved = catalog
+            .resolve(vec![
+                permission("get", "/admins"),
+                permission("GET", "/admins"),
+                permission("*", "/roles"),
+                permission("PUT", "/admins/*"),
+                permission("*", "/*"),
+            ])
+            .unwrap();
+
+        assert_eq!(resolved.len(), 4);
+        assert_eq!(resolved[0].module, "admin");
+        assert_eq!(resolved[0].description, "获取管理员列表");
+
+        assert!(catalog.resolve(vec![permission("POST", "/admins")]).is_err());
+        assert!(catalog.resolve(vec![permission("GET", "/admins/:id")]).is_err());
+        assert!(catalog.resolve(vec![permission("DELETE", "/admins/*")]).is_err());
+        assert!(catalog.resolve(vec![permission("*", "/products/*")]).is_err());
+        assert!(catalog.resolve(vec![permission("PUT", "/*/:id/*")]).is_err());
+    }
+}
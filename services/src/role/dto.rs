@@ -1,10 +1,13 @@
-use entities::RouteItem;
+use entities::{DataScope, RouteItem};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateRoleParams {
     pub name: String,
     pub permissions: Vec<RouteItem>,
+    pub parents: Vec<String>,
+    pub data_scopes: Vec<DataScope>,
+    pub require_two_factor: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -12,4 +15,7 @@ pub struct UpdateRoleParams {
     pub id: String,
     pub name: Option<String>,
     pub permissions: Option<Vec<RouteItem>>,
+    pub parents: Option<Vec<String>>,
+    pub data_scopes: Option<Vec<DataScope>>,
+    pub require_two_factor: Option<bool>,
 }
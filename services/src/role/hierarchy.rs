@@ -0,0 +1,27 @@
+use std::collections::{HashSet, VecDeque};
+
+use entities::Role;
+
+use crate::{admin::IRoleRepository, errors::Result};
+
+/// 查找指定角色及其继承的所有父角色
+///
+/// 按广度优先顺序返回, 不存在的角色会被跳过, 继承关系中的环只访问一次
+pub async fn resolve_roles<R: IRoleRepository>(repo: &R, names: &[String]) -> Result<Vec<Role>> {
+    let mut visited = HashSet::new();
+    let mut queue: VecDeque<String> = names.iter().cloned().collect();
+    let mut roles = vec![];
+
+    while let Some(name) = queue.pop_front() {
+        if !visited.insert(name.clone()) {
+            continue;
+        }
+
+        if let Some(role) = repo.find_by_name(&name).await? {
+            queue.extend(role.parents.iter().cloned());
+            roles.push(role);
+        }
+    }
+
+    Ok(roles)
+}
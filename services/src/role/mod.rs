@@ -1,5 +1,11 @@
 mod service;
 mod dto;
+mod catalog;
+mod hierarchy;
+mod scope;
 
 pub use service::RoleService;
 pub use dto::*;
+pub use catalog::PermissionCatalog;
+pub use hierarchy::resolve_roles;
+pub use scope::{validate_data_scopes, DataFilter, ScopeCondition, OWNER_FIELD, PRODUCT_RESOURCE};
@@ -0,0 +1,162 @@
+use entities::{DataScope, ScopeRule};
+use rbac::EffectiveScope;
+
+use crate::errors::{Error, Result};
+
+/// 商品资源名称
+pub const PRODUCT_RESOURCE: &str = "product";
+
+/// 记录创建人的字段, `Own` 规则按该字段过滤
+pub const OWNER_FIELD: &str = "created_by";
+
+/// 支持数据范围的资源, 以及 `In` 规则可以使用的字段
+const SCOPED_RESOURCES: &[(&str, &[&str])] =
+    &[(PRODUCT_RESOURCE, &["supplier_id", "category_id", "brand_id"])];
+
+/// 检查角色的数据范围只引用支持的资源和字段
+pub fn validate_data_scopes(scopes: &[DataScope]) -> Result<()> {
+    for scope in scopes {
+        let (_, fields) = SCOPED_RESOURCES
+            .iter()
+            .find(|(resource, _)| *resource == scope.resource)
+            .ok_or_else(|| Error::InvalidArgument(format!("资源 {} 不支持数据范围", scope.resource)))?;
+
+        // 没有规则的范围无法访问任何数据, 需要限制为空时应不授予该资源的接口权限
+        if scope.rules.is_empty() {
+            return Err(Error::InvalidArgument(format!(
+                "资源 {} 的数据范围至少需要一条规则",
+                scope.resource
+            )));
+        }
+
+        for rule in &scope.rules {
+            if let ScopeRule::In { field, values } = rule {
+                if !fields.contains(&field.as_str()) {
+                    return Err(Error::InvalidArgument(format!(
+                        "资源 {} 不支持按 {} 限定数据范围",
+                        scope.resource, field
+                    )));
+                }
+                if values.is_empty() {
+                    return Err(Error::InvalidArgument(format!(
+                        "数据范围 {} 的取值不能为空",
+                        field
+                    )));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 查询条件: 字段取值在列表中
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopeCondition {
+    pub field: String,
+    pub values: Vec<String>,
+}
+
+/// 仓储查询时附加的数据范围过滤条件
+///
+/// 没有默认值, 调用方必须根据 RBAC 计算出的数据范围显式传入
+///
+/// * `All` - 不限制
+/// * `AnyOf` - 满足任意一个条件, 没有条件时不返回任何数据
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataFilter {
+    All,
+    AnyOf(Vec<ScopeCondition>),
+}
+
+impl DataFilter {
+    /// 根据 RBAC 计算出的数据范围生成过滤条件
+    ///
+    /// # 参数
+    ///
+    /// * `scope` - 用户对资源的有效数据范围
+    /// * `user_id` - 当前用户 ID, 用于 `Own` 规则
+    pub fn new(scope: EffectiveScope, user_id: &str) -> Self {
+        match scope {
+            EffectiveScope::All => Self::All,
+            EffectiveScope::Rules(rules) => Self::AnyOf(
+                rules
+                    .into_iter()
+                    .filter_map(|rule| match rule {
+                        rbac::ScopeRule::All => None,
+                        rbac::ScopeRule::Own => Some(ScopeCondition {
+                            field: OWNER_FIELD.to_string(),
+                            values: vec![user_id.to_string()],
+                        }),
+                        rbac::ScopeRule::In { field, values } => Some(ScopeCondition { field, values }),
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// 检查单条记录是否在数据范围内
+    ///
+    /// # 参数
+    ///
+    /// * `value_of` - 读取记录字段值, 不支持的字段返回 None
+    pub fn allows<'a, F>(&self, value_of: F) -> bool
+    where
+        F: Fn(&str) -> Option<&'a str>,
+    {
+        match self {
+            Self::All => true,
+            Self::AnyOf(conditions) => conditions.iter().any(|condition| {
+                value_of(&condition.field).is_some_and(|value| condition.values.iter().any(|v| v == value))
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_filter() {
+        let filter = DataFilter::new(
+            EffectiveScope::Rules(vec![
+                rbac::ScopeRule::Own,
+                rbac::ScopeRule::In {
+                    field: "supplier_id".to_string(),
+                    values: vec!["s1".to_string(), "s2".to_string()],
+                },
+            ]),
+            "u1",
+        );
+
+        let product = |supplier_id: &'static str, created_by: &'static str| {
+            move |field: &str| match field {
+                "supplier_id" => Some(supplier_id),
+                OWNER_FIELD => Some(created_by),
+                _ => None,
+            }
+        };
+
+        assert!(filter.allows(product("s2", "u2")));
+        assert!(filter.allows(product("s3", "u1")));
+        assert!(!filter.allows(product("s3", "u2")));
+        assert!(!DataFilter::AnyOf(vec![]).allows(product("s1", "u1")));
+        assert!(DataFilter::new(EffectiveScope::All, "u1").allows(product("s3", "u2")));
+
+        let scopes = vec![DataScope {
+            resource: PRODUCT_RESOURCE.to_string(),
+            rules: vec![ScopeRule::In {
+                field: "price".to_string(),
+                values: vec!["1".to_string()],
+            }],
+        }];
+        assert!(validate_data_scopes(&scopes).is_err());
+
+        let scopes = vec![DataScope {
+            resource: PRODUCT_RESOURCE.to_string(),
+            rules: vec![],
+        }];
+        assert!(validate_data_scopes(&scopes).is_err());
+    }
+}
@@ -1,29 +1,57 @@
-use entities::Role;
+use std::sync::Arc;
+
+use entities::{Role, RouteItem};
 
 use crate::{
     admin::{IAdminRepository, IRoleRepository},
-    errors::Result,
+    errors::{Error, Resource, Result},
 };
 use libs::next_id;
 
+use super::catalog::PermissionCatalog;
 use super::dto::{CreateRoleParams, UpdateRoleParams};
+use super::hierarchy::resolve_roles;
+use super::scope::validate_data_scopes;
 
 pub struct RoleService<R: IRoleRepository, A: IAdminRepository> {
     repo: R,
     admin_repo: A,
+    catalog: Option<Arc<PermissionCatalog>>,
 }
 
 impl<R: IRoleRepository, A: IAdminRepository> RoleService<R, A> {
     pub fn new(repo: R, admin_repo: A) -> Self {
-        Self { repo, admin_repo }
+        Self {
+            repo,
+            admin_repo,
+            catalog: None,
+        }
+    }
+
+    /// 设置权限目录, 创建或修改角色时检查授予的权限都对应实际的接口
+    pub fn with_permission_catalog(mut self, catalog: Arc<PermissionCatalog>) -> Self {
+        self.catalog = Some(catalog);
+        self
     }
 
-    pub async fn create_role(&self, params: CreateRoleParams) -> Result<()> {
+    pub async fn create_role(&self, params: CreateRoleParams) -> Result<Role> {
+        check_name(&params.name)?;
+        if self.repo.exists(&params.name).await? {
+            return Err(Error::Conflict("角色已存在".to_string()));
+        }
+
+        self.check_parents(&params.name, &params.parents).await?;
+        let permissions = self.resolve_permissions(params.permissions)?;
+        validate_data_scopes(&params.data_scopes)?;
+
         let id = next_id().await;
-        let role = Role::new(id, params.name, params.permissions);
+        let mut role = Role::new(id, params.name, permissions);
+        role.parents = params.parents;
+        role.data_scopes = params.data_scopes;
+        role.require_two_factor = params.require_two_factor;
 
         self.repo.create(&role).await?;
-        Ok(())
+        Ok(role)
     }
 
     pub async fn get_role_list(&self) -> Result<Vec<Role>> {
@@ -31,36 +59,59 @@ impl<R: IRoleRepository, A: IAdminRepository> RoleService<R, A> {
         Ok(roles)
     }
 
-    pub async fn update_role(&self, params: UpdateRoleParams) -> Result<()> {
-        let mut role = self.repo.find_by_id(&params.id).await?.ok_or("角色不存在")?;
-
-        if let Some(name) = params.name {
+    pub async fn update_role(&self, params: UpdateRoleParams) -> Result<Role> {
+        let mut role = self
+            .repo
+            .find_by_id(&params.id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Role))?;
+
+        // 管理员和子角色按名称引用角色, 只有未被使用的角色可以改名
+        if let Some(name) = params.name.filter(|name| *name != role.name) {
+            check_name(&name)?;
+            if self.repo.exists(&name).await? {
+                return Err(Error::Conflict("角色已存在".to_string()));
+            }
+            self.check_unused(&role.name, "修改名称").await?;
             role.name = name;
         }
 
         if let Some(permissions) = params.permissions {
-            role.permissions = permissions;
+            role.permissions = self.resolve_permissions(permissions)?;
         }
 
-        self.repo.update(&role).await?;
-        Ok(())
-    }
+        if let Some(parents) = params.parents {
+            role.parents = parents;
+        }
 
-    pub async fn delete_role(&self, id: String) -> Result<()> {
-        let mut role = self.repo.find_by_id(&id).await?.ok_or("角色不存在")?;
+        self.check_parents(&role.name, &role.parents).await?;
 
-        // 检查是否有管理员正在使用该角色
-        let admins = self.admin_repo.find_all().await?;
-        let role_in_use = admins.iter().any(|admin| admin.role_name == role.name);
+        if let Some(data_scopes) = params.data_scopes {
+            validate_data_scopes(&data_scopes)?;
+            role.data_scopes = data_scopes;
+        }
 
-        if role_in_use {
-            return Err("该角色正在使用中,无法删除".into());
+        if let Some(require_two_factor) = params.require_two_factor {
+            role.require_two_factor = require_two_factor;
         }
 
+        self.repo.update(&role).await?;
+        Ok(role)
+    }
+
+    pub async fn delete_role(&self, id: String) -> Result<Role> {
+        let mut role = self
+            .repo
+            .find_by_id(&id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Role))?;
+
+        self.check_unused(&role.name, "删除").await?;
+
         role.base.delete();
         self.repo.update(&role).await?;
 
-        Ok(())
+        Ok(role)
     }
 
     pub async fn get_role_by_id(&self, id: String) -> Result<Option<Role>> {
@@ -73,4 +124,131 @@ impl<R: IRoleRepository, A: IAdminRepository> RoleService<R, A> {
         let role = roles.into_iter().find(|r| r.name == name);
         Ok(role)
     }
+
+    /// 按权限目录检查权限, 未设置权限目录时不检查
+    fn resolve_permissions(&self, permissions: Vec<RouteItem>) -> Result<Vec<RouteItem>> {
+        match &self.catalog {
+            Some(catalog) => catalog.resolve(permissions),
+            None => Ok(permissions),
+        }
+    }
+
+    /// 检查没有管理员正在使用该角色, 也没有其他角色继承该角色
+    async fn check_unused(&self, name: &str, action: &str) -> Result<()> {
+        let admins = self.admin_repo.find_by_role(name).await?;
+        if !admins.is_empty() {
+            return Err(Error::Conflict(format!("该角色正在使用中,无法{}", action)));
+        }
+
+        let roles = self.repo.find_all().await?;
+        if roles
+            .iter()
+            .any(|r| r.parents.iter().any(|parent| parent == name))
+        {
+            return Err(Error::Conflict(format!("该角色被其他角色继承,无法{}", action)));
+        }
+
+        Ok(())
+    }
+
+    /// 检查父角色都存在, 且不会形成循环继承
+    async fn check_parents(&self, name: &str, parents: &[String]) -> Result<()> {
+        if parents.iter().any(|parent| parent == name) {
+            return Err(Error::InvalidArgument("角色不能继承自身".to_string()));
+        }
+
+        for parent in parents {
+            if !self.repo.exists(parent).await? {
+                return Err(Error::InvalidArgument(format!("父角色 {} 不存在", parent)));
+            }
+        }
+
+        let ancestors = resolve_roles(&self.repo, parents).await?;
+        if ancestors.iter().any(|role| role.name == name) {
+            return Err(Error::InvalidArgument("角色继承关系不能形成循环".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+/// 检查角色名称不含 `:`, 带有 `:` 前缀的名称是 RBAC 中管理员和 API 密钥的主体
+fn check_name(name: &str) -> Result<()> {
+    if name.contains(':') {
+        return Err(Error::InvalidArgument("角色名称不能包含 `:`".to_string()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use entities::{Admin, Role, Secret};
+
+    use super::RoleService;
+    use crate::{
+        errors::Error,
+        role::UpdateRoleParams,
+        testing::{MemoryAdminRepository, MemoryRoleRepository},
+    };
+
+    fn role(id: &str, name: &str, parents: &[&str]) -> Role {
+        let mut role = Role::new(id.to_string(), name.to_string(), vec![]);
+        role.parents = parents.iter().map(|parent| parent.to_string()).collect();
+        role
+    }
+
+    fn rename(id: &str, name: &str) -> UpdateRoleParams {
+        UpdateRoleParams {
+            id: id.to_string(),
+            name: Some(name.to_string()),
+            permissions: None,
+            parents: None,
+            data_scopes: None,
+            require_two_factor: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn update_role_rejects_unsafe_renames() {
+        let roles = vec![
+            role("1", "viewer", &[]),
+            role("2", "editor", &["viewer"]),
+            role("3", "auditor", &[]),
+            role("4", "draft", &[]),
+        ];
+        let admin = Admin::new(
+            "1".to_string(),
+            Secret::default(),
+            "alice".to_string(),
+            vec!["auditor".to_string()],
+        );
+        let service = RoleService::new(
+            MemoryRoleRepository::new(roles),
+            MemoryAdminRepository::new(vec![admin]),
+        );
+
+        // 不能改成已有角色的名称
+        let result = service.update_role(rename("4", "viewer")).await;
+        assert!(matches!(result, Err(Error::Conflict(_))));
+
+        // 被子角色继承或被管理员使用的角色不能改名
+        let result = service.update_role(rename("1", "reader")).await;
+        assert!(matches!(result, Err(Error::Conflict(_))));
+        let result = service.update_role(rename("3", "inspector")).await;
+        assert!(matches!(result, Err(Error::Conflict(_))));
+
+        let result = service.update_role(rename("4", "user:alice")).await;
+        assert!(matches!(result, Err(Error::InvalidArgument(_))));
+
+        // 名称不变时不受影响
+        assert_eq!(
+            service.update_role(rename("1", "viewer")).await.unwrap().name,
+            "viewer"
+        );
+        assert_eq!(
+            service.update_role(rename("4", "writer")).await.unwrap().name,
+            "writer"
+        );
+    }
 }
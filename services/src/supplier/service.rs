@@ -1,6 +1,6 @@
 use super::dto::*;
 use super::repository::ISupplierRepository;
-use crate::errors::Result;
+use crate::errors::{Error, Resource, Result};
 use entities::product::Supplier;
 
 pub struct SupplierService<R>
@@ -34,7 +34,7 @@ where
             .repo
             .find_by_id(&params.id)
             .await?
-            .ok_or("供应商不存在")?;
+            .ok_or(Error::NotFound(Resource::Supplier))?;
 
         params.apply(&mut supplier);
 
@@ -59,4 +59,4 @@ where
 
         Ok(supplier_list)
     }
-} 
\ No newline at end of file
+}
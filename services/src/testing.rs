@@ -0,0 +1,588 @@
+//! 测试用的内存仓储
+//!
+//! 只实现服务测试需要的行为, 消息的领取和续租按数据库实现的条件模拟。
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use entities::{
+    product::{Brand, Category, Product, Supplier},
+    Admin, Broadcast, BroadcastStatus, InternalMessage, Message, MessageStatus, Role, WebhookEndpoint,
+    MAX_RETRY_TIMES,
+};
+
+use crate::{
+    admin::{IAdminRepository, IRoleRepository},
+    brand::IBrandRepository,
+    broadcast::{BroadcastQuery, IBroadcastRepository},
+    category::ICategoryRepository,
+    errors::{Error, Result},
+    internal_message::{IInternalMessageRepository, InternalMessageQuery},
+    notification::{IMessageRepository, MessageLease, MessageQuery, NotificationService},
+    product::{scope_value, IProductRepository, ProductDetail, ProductSearchParams},
+    role::DataFilter,
+    supplier::ISupplierRepository,
+    webhook::IWebhookEndpointRepository,
+};
+
+/// 使用内存仓储的消息服务
+pub type MemoryNotificationService = NotificationService<
+    MemoryMessageRepository,
+    MemoryInternalMessageRepository,
+    MemoryBroadcastRepository,
+    MemoryAdminRepository,
+    MemoryWebhookRepository,
+>;
+
+/// 创建使用内存仓储的消息服务, 返回的仓储与服务共享数据
+pub fn notification_service(
+    admins: Vec<Admin>,
+) -> (
+    MemoryNotificationService,
+    MemoryMessageRepository,
+    MemoryBroadcastRepository,
+) {
+    let messages = MemoryMessageRepository::default();
+    let broadcasts = MemoryBroadcastRepository::default();
+    let service = NotificationService::new(
+        messages.clone(),
+        MemoryInternalMessageRepository,
+        broadcasts.clone(),
+        MemoryAdminRepository::new(admins),
+        MemoryWebhookRepository,
+    );
+
+    (service, messages, broadcasts)
+}
+
+#[derive(Clone, Default)]
+pub struct MemoryMessageRepository {
+    messages: Arc<Mutex<Vec<Message>>>,
+    failing_updates: Arc<Mutex<HashSet<String>>>,
+    failing_batch: Arc<Mutex<Option<usize>>>,
+}
+
+impl MemoryMessageRepository {
+    pub fn get(&self, id: &str) -> Message {
+        self.all().into_iter().find(|m| m.base.id == id).unwrap()
+    }
+
+    pub fn all(&self) -> Vec<Message> {
+        self.messages.lock().unwrap().clone()
+    }
+
+    /// 直接修改存储的消息, 用于模拟其他实例的操作
+    pub fn modify(&self, id: &str, f: impl FnOnce(&mut Message)) {
+        let mut messages = self.messages.lock().unwrap();
+        f(messages.iter_mut().find(|m| m.base.id == id).unwrap());
+    }
+
+    /// 下一次更新该消息时返回错误
+    pub fn fail_next_update(&self, id: &str) {
+        self.failing_updates.lock().unwrap().insert(id.to_string());
+    }
+
+    /// 第`batch`次(从0开始)批量写入时返回错误
+    pub fn fail_batch(&self, batch: usize) {
+        *self.failing_batch.lock().unwrap() = Some(batch);
+    }
+
+    fn lease_where(&self, lease: &MessageLease, matches: impl Fn(&Message) -> bool) -> Option<Message> {
+        let mut messages = self.messages.lock().unwrap();
+        let message = messages.iter_mut().find(|m| {
+            matches(m) && !m.is_leased(lease.now) && !m.is_deferred(lease.now) && m.base.deleted_at == 0
+        })?;
+
+        message.lease(&lease.worker, lease.until);
+        message.base.version += 1;
+        Some(message.clone())
+    }
+}
+
+#[async_trait]
+impl IMessageRepository for MemoryMessageRepository {
+    async fn create(&self, message: &Message) -> Result<()> {
+        self.messages.lock().unwrap().push(message.clone());
+        Ok(())
+    }
+
+    async fn create_many(&self, messages: &[Message]) -> Result<()> {
+        let mut failing_batch = self.failing_batch.lock().unwrap();
+        match *failing_batch {
+            Some(0) => return Err(Error::RepositoryError("create failed".to_string())),
+            Some(batch) => *failing_batch = Some(batch - 1),
+            None => {}
+        }
+
+        self.messages.lock().unwrap().extend_from_slice(messages);
+        Ok(())
+    }
+
+    async fn update(&self, message: &Message) -> Result<()> {
+        if self.failing_updates.lock().unwrap().remove(&message.base.id) {
+            return Err(Error::RepositoryError("update failed".to_string()));
+        }
+
+        let mut messages = self.messages.lock().unwrap();
+        let stored = messages
+            .iter_mut()
+            .find(|m| m.base.id == message.base.id && m.base.version == message.base.version)
+            .ok_or(Error::OptimisticLock)?;
+
+        *stored = message.clone();
+        stored.base.version += 1;
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: &str) -> Result<Option<Message>> {
+        Ok(self.all().into_iter().find(|m| m.base.id == id))
+    }
+
+    async fn claim_next(
+        &self,
+        status: MessageStatus,
+        broadcast_id: Option<&str>,
+        lease: &MessageLease,
+    ) -> Result<Option<Message>> {
+        Ok(self.lease_where(lease, |m| {
+            m.status == status
+                && (status != MessageStatus::Failed || m.retry_times < MAX_RETRY_TIMES)
+                && broadcast_id.is_none_or(|id| m.broadcast_id.as_deref() == Some(id))
+        }))
+    }
+
+    async fn claim_by_id(
+        &self,
+        id: &str,
+        status: MessageStatus,
+        lease: &MessageLease,
+    ) -> Result<Option<Message>> {
+        Ok(self.lease_where(lease, |m| m.base.id == id && m.status == status))
+    }
+
+    async fn renew_lease(&self, message: &Message, lease: &MessageLease) -> Result<Option<Message>> {
+        let mut messages = self.messages.lock().unwrap();
+        let Some(stored) = messages.iter_mut().find(|m| {
+            m.base.id == message.base.id
+                && m.base.version == message.base.version
+                && m.locked_by.as_deref() == Some(lease.worker.as_str())
+        }) else {
+            return Ok(None);
+        };
+
+        stored.lease_until = lease.until;
+        stored.base.version += 1;
+        Ok(Some(stored.clone()))
+    }
+
+    async fn query(&self, _query: MessageQuery) -> Result<Vec<Message>> {
+        Ok(self.all())
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct MemoryBroadcastRepository {
+    broadcasts: Arc<Mutex<Vec<Broadcast>>>,
+}
+
+impl MemoryBroadcastRepository {
+    pub fn get(&self, id: &str) -> Broadcast {
+        self.broadcasts
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|b| b.base.id == id)
+            .cloned()
+            .unwrap()
+    }
+
+    fn set_status(&self, id: &str, status: BroadcastStatus, total: Option<u64>) {
+        let mut broadcasts = self.broadcasts.lock().unwrap();
+        if let Some(broadcast) = broadcasts.iter_mut().find(|b| b.base.id == id) {
+            broadcast.status = status;
+            broadcast.total = total.unwrap_or(broadcast.total);
+        }
+    }
+}
+
+#[async_trait]
+impl IBroadcastRepository for MemoryBroadcastRepository {
+    async fn create(&self, broadcast: &Broadcast) -> Result<()> {
+        self.broadcasts.lock().unwrap().push(broadcast.clone());
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: &str) -> Result<Option<Broadcast>> {
+        Ok(self
+            .broadcasts
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|b| b.base.id == id)
+            .cloned())
+    }
+
+    async fn query(&self, _query: &BroadcastQuery) -> Result<Vec<Broadcast>> {
+        Ok(self.broadcasts.lock().unwrap().clone())
+    }
+
+    async fn record_delivery(&self, id: &str, sent: i64, failed: i64) -> Result<Option<Broadcast>> {
+        let mut broadcasts = self.broadcasts.lock().unwrap();
+        let Some(broadcast) = broadcasts.iter_mut().find(|b| b.base.id == id) else {
+            return Ok(None);
+        };
+
+        broadcast.sent = (broadcast.sent as i64 + sent) as u64;
+        broadcast.failed = (broadcast.failed as i64 + failed) as u64;
+        Ok(Some(broadcast.clone()))
+    }
+
+    async fn mark_completed(&self, id: &str) -> Result<()> {
+        if self.get(id).status == BroadcastStatus::Sending {
+            self.set_status(id, BroadcastStatus::Completed, None);
+        }
+        Ok(())
+    }
+
+    async fn mark_failed(&self, id: &str, total: u64) -> Result<()> {
+        self.set_status(id, BroadcastStatus::Failed, Some(total));
+        Ok(())
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct MemoryAdminRepository {
+    admins: Arc<Mutex<Vec<Admin>>>,
+}
+
+impl MemoryAdminRepository {
+    pub fn new(admins: Vec<Admin>) -> Self {
+        Self {
+            admins: Arc::new(Mutex::new(admins)),
+        }
+    }
+
+    fn find(&self, f: impl Fn(&Admin) -> bool) -> Vec<Admin> {
+        self.admins
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|a| f(a))
+            .cloned()
+            .collect()
+    }
+}
+
+#[async_trait]
+impl IAdminRepository for MemoryAdminRepository {
+    async fn create(&self, admin: &Admin) -> Result<()> {
+        self.admins.lock().unwrap().push(admin.clone());
+        Ok(())
+    }
+
+    async fn update(&self, admin: &Admin) -> Result<()> {
+        let mut admins = self.admins.lock().unwrap();
+        if let Some(stored) = admins.iter_mut().find(|a| a.base.id == admin.base.id) {
+            *stored = admin.clone();
+        }
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: &str) -> Result<Option<Admin>> {
+        Ok(self.find(|a| a.base.id == id).pop())
+    }
+
+    async fn find_by_account(&self, account: &str) -> Result<Option<Admin>> {
+        Ok(self.find(|a| a.secret.account == account).pop())
+    }
+
+    async fn find_by_sso(&self, issuer: &str, subject: &str) -> Result<Option<Admin>> {
+        Ok(self
+            .find(|a| {
+                a.sso
+                    .as_ref()
+                    .is_some_and(|sso| sso.issuer == issuer && sso.subject == subject)
+            })
+            .pop())
+    }
+
+    async fn find_all(&self) -> Result<Vec<Admin>> {
+        Ok(self.find(|_| true))
+    }
+
+    async fn find_by_ids(&self, ids: &[String]) -> Result<Vec<Admin>> {
+        Ok(self.find(|a| ids.contains(&a.base.id)))
+    }
+
+    async fn find_by_role(&self, role_name: &str) -> Result<Vec<Admin>> {
+        Ok(self.find(|a| a.role_names.iter().any(|r| r == role_name)))
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct MemoryRoleRepository {
+    roles: Arc<Mutex<Vec<Role>>>,
+}
+
+impl MemoryRoleRepository {
+    pub fn new(roles: Vec<Role>) -> Self {
+        Self {
+            roles: Arc::new(Mutex::new(roles)),
+        }
+    }
+
+    fn find(&self, f: impl Fn(&Role) -> bool) -> Vec<Role> {
+        self.roles
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.base.deleted_at == 0 && f(r))
+            .cloned()
+            .collect()
+    }
+}
+
+#[async_trait]
+impl IRoleRepository for MemoryRoleRepository {
+    async fn create(&self, role: &Role) -> Result<()> {
+        self.roles.lock().unwrap().push(role.clone());
+        Ok(())
+    }
+
+    async fn update(&self, role: &Role) -> Result<()> {
+        let mut roles = self.roles.lock().unwrap();
+        if let Some(stored) = roles.iter_mut().find(|r| r.base.id == role.base.id) {
+            *stored = role.clone();
+        }
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: &str) -> Result<Option<Role>> {
+        Ok(self.find(|r| r.base.id == id).pop())
+    }
+
+    async fn find_all(&self) -> Result<Vec<Role>> {
+        Ok(self.find(|_| true))
+    }
+
+    async fn exists(&self, name: &str) -> Result<bool> {
+        Ok(!self.find(|r| r.name == name).is_empty())
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Option<Role>> {
+        Ok(self.find(|r| r.name == name).pop())
+    }
+}
+
+/// 站内消息的发送总是成功
+#[derive(Clone, Default)]
+pub struct MemoryInternalMessageRepository;
+
+#[async_trait]
+impl IInternalMessageRepository for MemoryInternalMessageRepository {
+    async fn create(&self, _message: &InternalMessage) -> Result<()> {
+        Ok(())
+    }
+
+    async fn find_by_query(&self, _query: &InternalMessageQuery) -> Result<Vec<InternalMessage>> {
+        Ok(vec![])
+    }
+
+    async fn count_by_query(&self, _query: &InternalMessageQuery) -> Result<u64> {
+        Ok(0)
+    }
+
+    async fn count_unread(&self, _recipient: &str) -> Result<u64> {
+        Ok(0)
+    }
+
+    async fn mark_as_read(&self, _id: &str, _recipient: &str) -> Result<bool> {
+        Ok(false)
+    }
+
+    async fn mark_many_as_read(&self, _ids: &[String], _recipient: &str) -> Result<u64> {
+        Ok(0)
+    }
+
+    async fn mark_all_as_read(&self, _recipient: &str) -> Result<u64> {
+        Ok(0)
+    }
+
+    async fn archive(&self, _id: &str, _recipient: &str) -> Result<bool> {
+        Ok(false)
+    }
+
+    async fn delete(&self, _id: &str, _recipient: &str) -> Result<bool> {
+        Ok(false)
+    }
+}
+
+/// 没有任何 Webhook 端点, 发送到 Webhook 渠道的消息都会失败
+#[derive(Clone, Default)]
+pub struct MemoryWebhookRepository;
+
+#[async_trait]
+impl IWebhookEndpointRepository for MemoryWebhookRepository {
+    async fn create(&self, _endpoint: &WebhookEndpoint) -> Result<()> {
+        Ok(())
+    }
+
+    async fn update(&self, _endpoint: &WebhookEndpoint) -> Result<()> {
+        Ok(())
+    }
+
+    async fn find_by_id(&self, _id: &str) -> Result<Option<WebhookEndpoint>> {
+        Ok(None)
+    }
+
+    async fn find_all(&self) -> Result<Vec<WebhookEndpoint>> {
+        Ok(vec![])
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct MemoryProductRepository {
+    products: Arc<Mutex<Vec<Product>>>,
+}
+
+impl MemoryProductRepository {
+    pub fn new(products: Vec<Product>) -> Self {
+        Self {
+            products: Arc::new(Mutex::new(products)),
+        }
+    }
+
+    fn find(&self, f: impl Fn(&Product) -> bool) -> Vec<Product> {
+        self.products
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|p| p.base.deleted_at == 0 && f(p))
+            .cloned()
+            .collect()
+    }
+}
+
+#[async_trait]
+impl IProductRepository for MemoryProductRepository {
+    async fn create(&self, product: &Product) -> Result<()> {
+        self.products.lock().unwrap().push(product.clone());
+        Ok(())
+    }
+
+    async fn update(&self, product: &Product) -> Result<()> {
+        let mut products = self.products.lock().unwrap();
+        if let Some(stored) = products.iter_mut().find(|p| p.base.id == product.base.id) {
+            *stored = product.clone();
+        }
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: &str) -> Result<Option<Product>> {
+        Ok(self.find(|p| p.base.id == id).pop())
+    }
+
+    async fn find_all(&self) -> Result<Vec<Product>> {
+        Ok(self.find(|_| true))
+    }
+
+    async fn find_by_category(&self, category_id: &str) -> Result<Vec<Product>> {
+        Ok(self.find(|p| p.category_id == category_id))
+    }
+
+    async fn find_featured(&self) -> Result<Vec<Product>> {
+        Ok(self.find(|p| p.is_featured))
+    }
+
+    /// 只按供应商和数据范围过滤, 不分页
+    async fn search(
+        &self,
+        params: &ProductSearchParams,
+        filter: &DataFilter,
+    ) -> Result<(u64, Vec<ProductDetail>)> {
+        let products = self.find(|p| {
+            params.supplier_id.as_ref().is_none_or(|id| *id == p.supplier_id)
+                && filter.allows(|field| scope_value(p, field))
+        });
+
+        Ok((
+            products.len() as u64,
+            products.into_iter().map(ProductDetail::from).collect(),
+        ))
+    }
+
+    async fn update_many(&self, products: &[Product]) -> Result<()> {
+        for product in products {
+            IProductRepository::update(self, product).await?;
+        }
+        Ok(())
+    }
+}
+
+/// 商品关联的品牌、分类和供应商都不存在
+#[derive(Clone, Default)]
+pub struct EmptyProductRelations;
+
+#[async_trait]
+impl IBrandRepository for EmptyProductRelations {
+    async fn create(&self, _brand: &Brand) -> Result<()> {
+        Ok(())
+    }
+
+    async fn update(&self, _brand: &Brand) -> Result<()> {
+        Ok(())
+    }
+
+    async fn find_by_id(&self, _id: &str) -> Result<Option<Brand>> {
+        Ok(None)
+    }
+
+    async fn find_all(&self) -> Result<Vec<Brand>> {
+        Ok(vec![])
+    }
+}
+
+#[async_trait]
+impl ICategoryRepository for EmptyProductRelations {
+    async fn create(&self, _category: &Category) -> Result<()> {
+        Ok(())
+    }
+
+    async fn update(&self, _category: &Category) -> Result<()> {
+        Ok(())
+    }
+
+    async fn find_by_id(&self, _id: &str) -> Result<Option<Category>> {
+        Ok(None)
+    }
+
+    async fn find_all(&self) -> Result<Vec<Category>> {
+        Ok(vec![])
+    }
+
+    async fn find_children(&self, _parent_id: &str) -> Result<Vec<Category>> {
+        Ok(vec![])
+    }
+}
+
+#[async_trait]
+impl ISupplierRepository for EmptyProductRelations {
+    async fn create(&self, _supplier: &Supplier) -> Result<()> {
+        Ok(())
+    }
+
+    async fn update(&self, _supplier: &Supplier) -> Result<()> {
+        Ok(())
+    }
+
+    async fn find_by_id(&self, _id: &str) -> Result<Option<Supplier>> {
+        Ok(None)
+    }
+
+    async fn find_all(&self) -> Result<Vec<Supplier>> {
+        Ok(vec![])
+    }
+}
@@ -0,0 +1,48 @@
+use entities::WebhookEndpoint;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateWebhookParams {
+    pub name: String,
+    pub url: String,
+    pub created_by: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateWebhookParams {
+    pub id: String,
+    pub name: Option<String>,
+    pub url: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+/// 端点详情, 不包含签名密钥
+#[derive(Debug, Serialize)]
+pub struct WebhookDetail {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub is_active: bool,
+    pub created_by: String,
+    pub created_at: u64,
+}
+
+impl From<WebhookEndpoint> for WebhookDetail {
+    fn from(endpoint: WebhookEndpoint) -> Self {
+        Self {
+            id: endpoint.base.id,
+            name: endpoint.name,
+            url: endpoint.url,
+            is_active: endpoint.is_active,
+            created_by: endpoint.created_by,
+            created_at: endpoint.base.created_at,
+        }
+    }
+}
+
+/// 签名密钥只在创建和轮换时返回一次
+#[derive(Debug, Serialize)]
+pub struct WebhookSecret {
+    pub id: String,
+    pub secret: String,
+}
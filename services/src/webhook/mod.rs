@@ -0,0 +1,7 @@
+mod dto;
+mod repository;
+mod service;
+
+pub use dto::*;
+pub use repository::IWebhookEndpointRepository;
+pub use service::WebhookService;
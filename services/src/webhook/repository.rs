@@ -0,0 +1,11 @@
+use crate::errors::Result;
+use async_trait::async_trait;
+use entities::WebhookEndpoint;
+
+#[async_trait]
+pub trait IWebhookEndpointRepository: Send + Sync {
+    async fn create(&self, endpoint: &WebhookEndpoint) -> Result<()>;
+    async fn update(&self, endpoint: &WebhookEndpoint) -> Result<()>;
+    async fn find_by_id(&self, id: &str) -> Result<Option<WebhookEndpoint>>;
+    async fn find_all(&self) -> Result<Vec<WebhookEndpoint>>;
+}
@@ -0,0 +1,109 @@
+use entities::WebhookEndpoint;
+
+use super::dto::{CreateWebhookParams, UpdateWebhookParams, WebhookDetail, WebhookSecret};
+use super::repository::IWebhookEndpointRepository;
+use crate::errors::{Error, Resource, Result};
+
+/// 签名密钥的随机字节数
+const SECRET_BYTES: usize = 32;
+
+pub struct WebhookService<W: IWebhookEndpointRepository> {
+    repo: W,
+}
+
+impl<W: IWebhookEndpointRepository> WebhookService<W> {
+    pub fn new(repo: W) -> Self {
+        Self { repo }
+    }
+
+    /// 注册一个 webhook 端点
+    ///
+    /// # Returns
+    /// * `Ok(WebhookSecret)` - 端点ID和签名密钥, 密钥之后不会再次返回
+    pub async fn create_webhook(&self, params: CreateWebhookParams) -> Result<WebhookSecret> {
+        validate_url(&params.url)?;
+
+        let secret = libs::random_token(SECRET_BYTES);
+        let endpoint = WebhookEndpoint::new(
+            libs::next_id().await,
+            params.name,
+            params.url,
+            secret.clone(),
+            params.created_by,
+        );
+        self.repo.create(&endpoint).await?;
+
+        Ok(WebhookSecret {
+            id: endpoint.base.id,
+            secret,
+        })
+    }
+
+    pub async fn get_webhook_list(&self) -> Result<Vec<WebhookDetail>> {
+        let endpoints = self.repo.find_all().await?;
+        Ok(endpoints.into_iter().map(WebhookDetail::from).collect())
+    }
+
+    pub async fn update_webhook(&self, params: UpdateWebhookParams) -> Result<()> {
+        let mut endpoint = self
+            .repo
+            .find_by_id(&params.id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Webhook))?;
+
+        if let Some(name) = params.name {
+            endpoint.name = name;
+        }
+
+        if let Some(url) = params.url {
+            validate_url(&url)?;
+            endpoint.url = url;
+        }
+
+        if let Some(is_active) = params.is_active {
+            endpoint.is_active = is_active;
+        }
+
+        self.repo.update(&endpoint).await?;
+        Ok(())
+    }
+
+    /// 轮换签名密钥, 旧密钥立即失效
+    pub async fn rotate_secret(&self, id: String) -> Result<WebhookSecret> {
+        let mut endpoint = self
+            .repo
+            .find_by_id(&id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Webhook))?;
+
+        endpoint.secret = libs::random_token(SECRET_BYTES);
+        self.repo.update(&endpoint).await?;
+
+        Ok(WebhookSecret {
+            id: endpoint.base.id,
+            secret: endpoint.secret,
+        })
+    }
+
+    pub async fn delete_webhook(&self, id: String) -> Result<()> {
+        let mut endpoint = self
+            .repo
+            .find_by_id(&id)
+            .await?
+            .ok_or(Error::NotFound(Resource::Webhook))?;
+
+        endpoint.base.delete();
+        self.repo.update(&endpoint).await?;
+
+        Ok(())
+    }
+}
+
+fn validate_url(url: &str) -> Result<()> {
+    let parsed = reqwest::Url::parse(url).map_err(|_| "端点地址格式不正确")?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(Error::InvalidArgument("端点地址只支持 http 和 https".to_string()));
+    }
+
+    Ok(())
+}